@@ -0,0 +1,289 @@
+use std::time::Instant;
+
+use glam::UVec2;
+use tokio::sync::mpsc::{Sender, error::TrySendError};
+
+use splix_event::KeyEvent;
+use splix_frame::Frame;
+use splix_id::{ClientId, PaneId, SessionId};
+use splix_input::InputDecoder;
+use splix_key::MouseTracking;
+use splix_layout::SplitDirection;
+use splix_protocol::ServerMessage;
+
+use crate::key_bindings::ROOT_TABLE;
+
+/// A client connected to the server.
+pub struct ClientConnection {
+    id: ClientId,
+    dimensions: Option<UVec2>,
+    session: Option<SessionId>,
+    key_table: String,
+    repeat_deadline: Option<Instant>,
+    read_only: bool,
+    /// The separator being dragged with the mouse, as `Window::move_border` takes it.
+    border_drag: Option<(PaneId, SplitDirection)>,
+    input_decoder: InputDecoder,
+    /// Shown instead of the status line until the next key.
+    message: Option<String>,
+    control: bool,
+    command_count: u64,
+    last_frame: Option<Frame>,
+    last_title: Option<String>,
+    last_mouse_tracking: Option<MouseTracking>,
+    last_activity: Instant,
+    /// Set once a message other than a frame didn't fit in the channel to the client.
+    overflowed: bool,
+    message_sender: Sender<ServerMessage>,
+}
+
+impl ClientConnection {
+    pub fn new(id: ClientId, message_sender: Sender<ServerMessage>) -> Self {
+        Self {
+            id,
+            dimensions: None,
+            session: None,
+            key_table: String::from(ROOT_TABLE),
+            repeat_deadline: None,
+            read_only: false,
+            border_drag: None,
+            input_decoder: InputDecoder::new(),
+            message: None,
+            control: false,
+            command_count: 0,
+            last_frame: None,
+            last_title: None,
+            last_mouse_tracking: None,
+            last_activity: Instant::now(),
+            overflowed: false,
+            message_sender,
+        }
+    }
+
+    pub fn get_id(&self) -> ClientId {
+        self.id
+    }
+
+    /// `None` until the client reports the size of its terminal.
+    pub fn get_dimensions(&self) -> Option<UVec2> {
+        self.dimensions
+    }
+
+    pub fn set_dimensions(&mut self, dimensions: UVec2) {
+        self.dimensions = Some(dimensions);
+    }
+
+    /// The session the client is attached to, if any.
+    pub fn get_session(&self) -> Option<SessionId> {
+        self.session
+    }
+
+    pub fn set_session(&mut self, session: Option<SessionId>) {
+        self.session = session;
+        self.last_frame = None;
+        self.last_title = None;
+        self.last_mouse_tracking = None;
+    }
+
+    /// The table the next key the client sends is looked up in, the root table unless e.g. the
+    /// prefix key was just pressed.
+    pub fn get_key_table(&self) -> &str {
+        &self.key_table
+    }
+
+    /// Switching tables ends any repeat.
+    pub fn set_key_table(&mut self, key_table: &str) {
+        self.key_table = key_table.to_string();
+        self.repeat_deadline = None;
+    }
+
+    /// Until when repeatable keys of the current table may be pressed again, after one of them
+    /// was.
+    pub fn get_repeat_deadline(&self) -> Option<Instant> {
+        self.repeat_deadline
+    }
+
+    pub fn start_repeat(&mut self, key_table: &str, repeat_deadline: Instant) {
+        self.key_table = key_table.to_string();
+        self.repeat_deadline = Some(repeat_deadline);
+    }
+
+    /// Read-only clients may only detach.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn get_border_drag(&self) -> Option<(PaneId, SplitDirection)> {
+        self.border_drag
+    }
+
+    pub fn set_border_drag(&mut self, border_drag: Option<(PaneId, SplitDirection)>) {
+        self.border_drag = border_drag;
+    }
+
+    /// Each message of input holds whole keys, as the client decoded them before sending, but a
+    /// paste may go on over several.
+    pub fn decode_input(&mut self, input: &[u8]) -> Vec<KeyEvent> {
+        let mut key_events = self.input_decoder.push(input);
+        key_events.extend(self.input_decoder.flush());
+        key_events
+    }
+
+    pub fn get_message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub fn set_message(&mut self, message: Option<String>) {
+        self.message = message;
+    }
+
+    /// Control clients get text notifications instead of frames, and send commands as lines.
+    pub fn is_control(&self) -> bool {
+        self.control
+    }
+
+    pub fn set_control(&mut self, control: bool) {
+        self.control = control;
+    }
+
+    /// Numbers the replies to the commands of a control client.
+    pub fn next_command_number(&mut self) -> u64 {
+        let number = self.command_count;
+        self.command_count += 1;
+        number
+    }
+
+    /// When the user last typed into, resized or attached the client.
+    pub fn get_last_activity(&self) -> Instant {
+        self.last_activity
+    }
+
+    pub fn mark_active(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    /// Sends only the lines that changed since the previous frame when possible, and nothing at
+    /// all if the frame didn't change.
+    ///
+    /// Returns whether the client still needs a frame, because it was too far behind to take
+    /// this one. The frame after it is then sent whole.
+    pub fn send_frame(&mut self, frame: Frame) -> bool {
+        let message = match self
+            .last_frame
+            .as_ref()
+            .and_then(|last_frame| frame.diff(last_frame))
+        {
+            Some(update) if update.is_empty() => return false,
+            Some(update) => ServerMessage::FrameUpdate(update),
+            None => ServerMessage::Frame(frame.clone()),
+        };
+
+        self.last_frame = Some(frame);
+        if self.try_send(message) {
+            return false;
+        }
+
+        self.last_frame = None;
+        true
+    }
+
+    /// Titles are only sent when they change. Returns whether the title still needs to be sent,
+    /// like `send_frame`.
+    pub fn send_title(&mut self, title: String) -> bool {
+        if self.last_title.as_ref() == Some(&title) {
+            return false;
+        }
+
+        self.last_title = Some(title.clone());
+        if self.try_send(ServerMessage::SetTitle(title)) {
+            return false;
+        }
+
+        self.last_title = None;
+        true
+    }
+
+    /// The tracking is only sent when it changes. Returns whether it still needs to be sent, like
+    /// `send_frame`.
+    pub fn send_mouse_tracking(&mut self, tracking: MouseTracking) -> bool {
+        if self.last_mouse_tracking == Some(tracking) {
+            return false;
+        }
+
+        self.last_mouse_tracking = Some(tracking);
+        if self.try_send(ServerMessage::SetMouseTracking(tracking)) {
+            return false;
+        }
+
+        self.last_mouse_tracking = None;
+        true
+    }
+
+    /// Never waits for the client to read its messages, which would hold up the server. Clients
+    /// too far behind to take a message other than a frame are marked as overflowed, to be
+    /// disconnected rather than miss it. Messages to a client that already went away are
+    /// dropped.
+    pub fn send(&mut self, message: ServerMessage) {
+        if !self.try_send(message) {
+            self.overflowed = true;
+        }
+    }
+
+    pub fn is_overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    /// Whether the message was sent or the client went away, as opposed to it being behind.
+    fn try_send(&self, message: ServerMessage) -> bool {
+        !matches!(
+            self.message_sender.try_send(message),
+            Err(TrySendError::Full(_))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use tokio::sync::mpsc;
+
+    use splix_frame::Frame;
+    use splix_id::ClientId;
+    use splix_protocol::ServerMessage;
+
+    use super::ClientConnection;
+
+    #[test]
+    fn sends_a_whole_frame_after_one_the_client_was_too_far_behind_for() {
+        let (message_sender, mut message_receiver) = mpsc::channel(1);
+        let mut client = ClientConnection::new(ClientId::new(0), message_sender);
+        let mut frame = Frame::new(UVec2::new(4, 2));
+
+        assert!(!client.send_frame(frame.clone()));
+        frame.set_cell(UVec2::new(0, 0), 'a');
+        assert!(client.send_frame(frame.clone()));
+        assert!(!client.is_overflowed());
+
+        message_receiver.try_recv().unwrap();
+        assert!(!client.send_frame(frame.clone()));
+        assert_eq!(
+            message_receiver.try_recv().unwrap(),
+            ServerMessage::Frame(frame)
+        );
+    }
+
+    #[test]
+    fn overflows_when_the_client_is_too_far_behind_for_other_messages() {
+        let (message_sender, _message_receiver) = mpsc::channel(1);
+        let mut client = ClientConnection::new(ClientId::new(0), message_sender);
+
+        client.send(ServerMessage::Detach);
+        assert!(!client.is_overflowed());
+        client.send(ServerMessage::Detach);
+        assert!(client.is_overflowed());
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    fs, io,
+    os::unix::{fs::FileTypeExt, net},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::{
+    net::{UnixListener, UnixStream},
+    sync::mpsc::{self, Receiver, Sender},
+    task::JoinHandle,
+    time,
+};
+
+use splix_event::{ClientConnectEvent, ClientMessageEvent, Event};
+use splix_id::ClientId;
+use splix_protocol::{
+    ClientMessage, MessageReader, MessageWriter, PROTOCOL_VERSION, ServerMessage,
+    check_protocol_version,
+};
+
+/// Accepts client connections on the server socket, turning everything they send into events.
+pub struct ClientListener {
+    socket_path: PathBuf,
+    accept_task: JoinHandle<()>,
+    write_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+const SERVER_MESSAGE_CHANNEL_CAPACITY: usize = 64;
+
+/// How long shutting down waits for messages to reach clients that stopped reading them.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+impl ClientListener {
+    /// How long accepting waits after failing, e.g. while out of file descriptors, which would
+    /// otherwise fail again right away.
+    pub(crate) const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+    pub fn new(socket_path: &Path, event_sender: Sender<Event>) -> splix_error::Result<Self> {
+        Self::remove_stale_socket(socket_path)?;
+
+        let listener = UnixListener::bind(socket_path).map_err(splix_error::Error::BindSocket)?;
+        let write_tasks = Arc::new(Mutex::new(Vec::new()));
+        let accept_task = tokio::spawn({
+            let write_tasks = write_tasks.clone();
+            async move {
+                Self::accept(listener, event_sender, write_tasks).await;
+            }
+        });
+
+        Ok(Self {
+            socket_path: socket_path.to_path_buf(),
+            accept_task,
+            write_tasks,
+        })
+    }
+
+    /// Stops accepting clients and waits for the messages already sent to the connected ones to
+    /// be written, which requires dropping every sender of them first.
+    pub async fn shut_down(&self) {
+        self.accept_task.abort();
+
+        let write_tasks: Vec<JoinHandle<()>> = self
+            .write_tasks
+            .lock()
+            .expect("write tasks lock poisoned")
+            .drain(..)
+            .collect();
+        let written = async {
+            for write_task in write_tasks {
+                write_task.await.ok();
+            }
+        };
+        time::timeout(SHUTDOWN_TIMEOUT, written).await.ok();
+    }
+
+    /// A socket left behind by a server that didn't shut down cleanly can't be bound again, but
+    /// one that still accepts connections belongs to a running server. Anything else at the path
+    /// is left alone.
+    pub(crate) fn remove_stale_socket(socket_path: &Path) -> splix_error::Result<()> {
+        let metadata = match fs::symlink_metadata(socket_path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(splix_error::Error::BindSocket(e)),
+        };
+        if !metadata.file_type().is_socket() {
+            return Err(splix_error::Error::NotASocket(socket_path.to_path_buf()));
+        }
+
+        if net::UnixStream::connect(socket_path).is_ok() {
+            return Err(splix_error::Error::ServerAlreadyRunning(
+                socket_path.to_path_buf(),
+            ));
+        }
+
+        match fs::remove_file(socket_path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(splix_error::Error::BindSocket(e)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn accept(
+        listener: UnixListener,
+        event_sender: Sender<Event>,
+        write_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    ) {
+        let mut next_client_id = 0;
+
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    eprintln!("Error while accepting a client: {e}");
+                    time::sleep(Self::ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            let client = ClientId::new(next_client_id);
+            next_client_id += 1;
+
+            let write_task = Self::serve(client, stream, event_sender.clone());
+
+            let mut write_tasks = write_tasks.lock().expect("write tasks lock poisoned");
+            write_tasks.retain(|write_task| !write_task.is_finished());
+            write_tasks.push(write_task);
+        }
+    }
+
+    /// Returns the task writing messages to the client.
+    fn serve(client: ClientId, stream: UnixStream, event_sender: Sender<Event>) -> JoinHandle<()> {
+        let (read_half, write_half) = stream.into_split();
+        let (message_sender, message_receiver): (Sender<ServerMessage>, Receiver<ServerMessage>) =
+            mpsc::channel(SERVER_MESSAGE_CHANNEL_CAPACITY);
+
+        let write_task = tokio::spawn(async move {
+            Self::write_messages(MessageWriter::new(write_half), message_receiver).await;
+        });
+
+        tokio::spawn(async move {
+            let mut message_reader = MessageReader::new(read_half);
+            if let Err(e) = Self::greet(&mut message_reader, &message_sender).await {
+                // Dropping the sender afterwards closes the connection.
+                message_sender
+                    .send(ServerMessage::Error(e.to_string()))
+                    .await
+                    .ok();
+                return;
+            }
+
+            if event_sender
+                .send(Event::ClientConnect(ClientConnectEvent::new(
+                    client,
+                    message_sender,
+                )))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            while let Ok(Some(message)) = message_reader.read::<ClientMessage>().await {
+                if event_sender
+                    .send(Event::ClientMessage(ClientMessageEvent::new(
+                        client, message,
+                    )))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+
+            event_sender
+                .send(Event::ClientDisconnect(client))
+                .await
+                .ok();
+        });
+
+        write_task
+    }
+
+    /// Clients must introduce themselves with the protocol version they speak before being
+    /// served.
+    async fn greet<R: tokio::io::AsyncRead + Unpin>(
+        message_reader: &mut MessageReader<R>,
+        message_sender: &Sender<ServerMessage>,
+    ) -> splix_error::Result<()> {
+        let Some(ClientMessage::Hello { version }) = message_reader.read().await? else {
+            return Err(splix_error::Error::ExpectedHello);
+        };
+        check_protocol_version(version)?;
+
+        message_sender
+            .send(ServerMessage::Hello {
+                version: PROTOCOL_VERSION,
+            })
+            .await
+            .ok();
+
+        Ok(())
+    }
+
+    async fn write_messages<W: tokio::io::AsyncWrite + Unpin>(
+        mut message_writer: MessageWriter<W>,
+        mut message_receiver: Receiver<ServerMessage>,
+    ) {
+        while let Some(message) = message_receiver.recv().await {
+            let exit = message == ServerMessage::Exit;
+
+            if message_writer.write(&message).await.is_err() || exit {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for ClientListener {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        fs::remove_file(&self.socket_path).ok();
+    }
+}
@@ -0,0 +1,784 @@
+use std::path::{Path, PathBuf};
+
+use splix_command::{CommandLine, LineNumber, SplixCommand};
+use splix_format::Format;
+use splix_id::{ClientId, PaneId, SessionId, WindowId};
+use splix_key::Key;
+use splix_layout::SplitDirection;
+use splix_options::OptionScope;
+use splix_pane::Pane;
+use splix_protocol::ServerMessage;
+use splix_terminal::Command;
+
+use crate::{
+    ConfigFile, Splix, formats,
+    json_listener::JsonListener,
+    key_binding::KeyBinding,
+    key_bindings::{PREFIX_TABLE, ROOT_TABLE},
+    options::{PREFIX_OPTION, PREFIX2_OPTION},
+};
+
+/// What `list-sessions`, `list-windows`, `list-panes` and `list-buffers` print without `-F`.
+const LIST_SESSIONS_FORMAT: &str =
+    "#{session_name}: #{session_windows} windows (#{session_id})#{?session_attached, (attached),}";
+const LIST_WINDOWS_FORMAT: &str = "#{window_index}: #{window_name}#{window_flags} \
+     (#{window_panes} panes) [#{window_width}x#{window_height}] (#{window_id})";
+const LIST_PANES_FORMAT: &str = "#{pane_index}: [#{pane_width}x#{pane_height}] (#{pane_id})\
+     #{?pane_active, (active),}#{?pane_dead, (dead),}";
+const LIST_BUFFERS_FORMAT: &str = "#{buffer_name}: #{buffer_size} bytes: \"#{buffer_sample}\"";
+
+/// What pastes are put between for programs that asked for bracketed paste.
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+
+impl Splix {
+    /// Runs a command given as its arguments, returning what it printed.
+    pub(crate) async fn run_command(
+        &mut self,
+        arguments: &[String],
+    ) -> splix_error::Result<String> {
+        let command = SplixCommand::parse(arguments)?;
+        let output = self.execute_command(command).await?;
+        self.redraw_pending = true;
+
+        Ok(output)
+    }
+
+    pub(crate) async fn execute_command(
+        &mut self,
+        command: SplixCommand,
+    ) -> splix_error::Result<String> {
+        match command {
+            SplixCommand::NewSession {
+                print,
+                name,
+                start_directory,
+                command,
+                ..
+            } => {
+                let session =
+                    self.new_session(name, Self::build_command(command, start_directory))?;
+                Ok(Self::print_id(print, session))
+            }
+            SplixCommand::NewWindow {
+                detached,
+                print,
+                format,
+                name,
+                target,
+                start_directory,
+                command,
+            } => {
+                let session = self.resolve_session(target.as_deref())?;
+                let window = self.new_window(
+                    session,
+                    name,
+                    Self::build_command(command, start_directory),
+                    detached,
+                )?;
+                Ok(self.print_window(print, window, format))
+            }
+            SplixCommand::SplitWindow {
+                horizontal,
+                detached,
+                print,
+                format,
+                target,
+                start_directory,
+                command,
+                ..
+            } => {
+                let new_pane = self.split_window(
+                    target.as_deref(),
+                    horizontal,
+                    detached,
+                    start_directory,
+                    command,
+                )?;
+                Ok(self.print_pane(print, new_pane, format))
+            }
+            SplixCommand::DetachClient { target } => {
+                if target.is_none()
+                    && let Some(client) = self.command_client
+                {
+                    self.detach_client(client).await;
+                    return Ok(String::new());
+                }
+
+                let session = self.resolve_session(target.as_deref())?;
+                for client in self.get_attached_clients(session) {
+                    self.detach_client(client).await;
+                }
+                Ok(String::new())
+            }
+            SplixCommand::ListSessions { format } => Ok(self.list_sessions(&Format::new(
+                format.unwrap_or_else(|| String::from(LIST_SESSIONS_FORMAT)),
+            ))),
+            SplixCommand::ListWindows { format, target } => {
+                let session = self.resolve_session(target.as_deref())?;
+                let format = format.unwrap_or_else(|| String::from(LIST_WINDOWS_FORMAT));
+                Ok(self.list_windows(session, &Format::new(format)))
+            }
+            SplixCommand::ListPanes { format, target } => {
+                let window = self.resolve_window(target.as_deref())?;
+                let format = format.unwrap_or_else(|| String::from(LIST_PANES_FORMAT));
+                Ok(self.list_panes(window, &Format::new(format)))
+            }
+            SplixCommand::KillSession { target } => {
+                let session = self.resolve_session(target.as_deref())?;
+                self.kill_session(session).await;
+                Ok(String::new())
+            }
+            SplixCommand::KillWindow { target } => {
+                let window = self.resolve_window(target.as_deref())?;
+                if let Some(session) = self.get_session_mut(window.get_session()) {
+                    session.kill_window(window);
+                }
+                self.remove_empty_session(window.get_session()).await;
+                Ok(String::new())
+            }
+            SplixCommand::KillPane { target } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let session = pane.get_window().get_session();
+                if let Some(session) = self.get_session_mut(session) {
+                    session.kill_pane(pane);
+                }
+                self.remove_empty_session(session).await;
+                Ok(String::new())
+            }
+            SplixCommand::RespawnPane {
+                kill,
+                target,
+                command,
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                if !kill && self.get_pane(pane).is_some_and(|pane| !pane.is_exited()) {
+                    return Err(splix_error::Error::PaneStillActive(pane.to_string()));
+                }
+
+                let command = (!command.is_empty()).then(|| Command::new(command));
+                self.respawn_pane(pane, command, false)?;
+                Ok(String::new())
+            }
+            SplixCommand::KillServer => {
+                self.exiting = true;
+                Ok(String::new())
+            }
+            SplixCommand::RenameSession { target, new_name } => {
+                let session = self.resolve_session(target.as_deref())?;
+                if self
+                    .sessions
+                    .iter()
+                    .any(|other| other.get_id() != session && other.get_name() == new_name)
+                {
+                    return Err(splix_error::Error::DuplicateSession(new_name));
+                }
+
+                if let Some(session) = self.get_session_mut(session) {
+                    session.set_name(new_name);
+                }
+                Ok(String::new())
+            }
+            SplixCommand::RenameWindow { target, new_name } => {
+                let window = self.resolve_window(target.as_deref())?;
+                if let Some(window) = self
+                    .get_session_mut(window.get_session())
+                    .and_then(|session| session.get_window_mut(window))
+                {
+                    window.set_name(new_name);
+                }
+                Ok(String::new())
+            }
+            SplixCommand::SelectWindow { target } => {
+                let window = self.resolve_window(target.as_deref())?;
+                if let Some(session) = self.get_session_mut(window.get_session()) {
+                    session.select_window(window);
+                }
+                Ok(String::new())
+            }
+            SplixCommand::SelectPane { target } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                if let Some(session) = self.get_session_mut(pane.get_window().get_session()) {
+                    session.select_window(pane.get_window());
+                    if let Some(window) = session.get_window_mut(pane.get_window()) {
+                        window.select_pane(pane);
+                    }
+                }
+                Ok(String::new())
+            }
+            SplixCommand::SendKeys {
+                copy_mode_command: true,
+                target,
+                keys,
+                ..
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let command = keys.first().map(String::as_str).unwrap_or_default();
+                self.get_pane_mut(pane)
+                    .ok_or_else(|| splix_error::Error::PaneNotFound(pane.to_string()))?
+                    .run_copy_mode_command(command)?;
+                Ok(String::new())
+            }
+            SplixCommand::SendKeys {
+                literal,
+                target,
+                keys,
+                ..
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let input = self.encode_keys(pane, &keys, literal);
+                self.send_pane_input(pane, &input).await;
+                Ok(String::new())
+            }
+            SplixCommand::CopyMode {
+                exit_at_bottom,
+                page_up,
+                quit,
+                target,
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let pane = self
+                    .get_pane_mut(pane)
+                    .ok_or_else(|| splix_error::Error::PaneNotFound(pane.to_string()))?;
+                if quit {
+                    pane.exit_copy_mode();
+                } else {
+                    pane.enter_copy_mode(exit_at_bottom);
+                    if page_up {
+                        pane.run_copy_mode_command("page-up")?;
+                    }
+                }
+                Ok(String::new())
+            }
+            SplixCommand::SendPrefix { secondary, target } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let option = if secondary {
+                    PREFIX2_OPTION
+                } else {
+                    PREFIX_OPTION
+                };
+
+                let session = pane.get_window().get_session();
+                if let Some(prefix) = self.get_session_option(session, option).as_key().flatten() {
+                    let input = self.encode_keys(pane, &[prefix.to_string()], false);
+                    self.send_pane_input(pane, &input).await;
+                }
+                Ok(String::new())
+            }
+            SplixCommand::CapturePane {
+                print,
+                escapes,
+                join,
+                start,
+                end,
+                buffer,
+                target,
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let grid = self
+                    .get_pane(pane)
+                    .ok_or_else(|| splix_error::Error::PaneNotFound(pane.to_string()))?
+                    .get_grid();
+
+                // Lines past either end of the grid are clamped to it.
+                let start = match start {
+                    Some(LineNumber::Line(line)) => line,
+                    Some(LineNumber::Edge) => i64::MIN,
+                    None => 0,
+                };
+                let end = match end {
+                    Some(LineNumber::Line(line)) => line,
+                    Some(LineNumber::Edge) | None => i64::MAX,
+                };
+                let capture = grid.capture(start, end, escapes, join);
+
+                if print {
+                    return Ok(capture);
+                }
+                self.paste_buffers.add(buffer, capture);
+                Ok(String::new())
+            }
+            SplixCommand::ListBuffers { format } => Ok(self.list_buffers(&Format::new(
+                format.unwrap_or_else(|| String::from(LIST_BUFFERS_FORMAT)),
+            ))),
+            SplixCommand::ShowBuffer { buffer } => Ok(self
+                .paste_buffers
+                .get(buffer.as_deref())?
+                .get_data()
+                .to_string()),
+            SplixCommand::PasteBuffer {
+                delete,
+                buffer,
+                target,
+            } => {
+                let pane = self.resolve_pane(target.as_deref())?;
+                let data = if delete {
+                    self.paste_buffers
+                        .remove(buffer.as_deref())?
+                        .get_data()
+                        .to_string()
+                } else {
+                    self.paste_buffers
+                        .get(buffer.as_deref())?
+                        .get_data()
+                        .to_string()
+                };
+
+                // Pasted lines are entered like typed ones. Escapes are left out, so that the text
+                // can't end a bracketed paste early or pass for keys.
+                let mut input = data.replace('\n', "\r").replace('\x1b', "");
+                if self
+                    .get_pane(pane)
+                    .is_some_and(|pane| pane.get_keyboard_modes().is_bracketed_paste())
+                {
+                    input = format!("{PASTE_START}{input}{PASTE_END}");
+                }
+                self.send_pane_input(pane, input.as_bytes()).await;
+                Ok(String::new())
+            }
+            SplixCommand::DeleteBuffer { buffer } => {
+                self.paste_buffers.remove(buffer.as_deref())?;
+                Ok(String::new())
+            }
+            SplixCommand::OpenJsonSocket { socket_path } => {
+                let socket_path = self.open_json_socket(socket_path)?;
+                Ok(format!("{}\n", socket_path.display()))
+            }
+            SplixCommand::CloseJsonSocket => {
+                self.json_listener = None;
+                self.json_connections.clear();
+                Ok(String::new())
+            }
+            SplixCommand::BindKey {
+                root,
+                repeat,
+                table,
+                key,
+                command,
+            } => {
+                // Mistakes show up when binding rather than when pressing the key.
+                SplixCommand::parse(&command)?;
+                let table = Self::key_table_name(root, table);
+                self.key_bindings
+                    .bind(&table, KeyBinding::new(key, repeat, command));
+                Ok(String::new())
+            }
+            SplixCommand::UnbindKey {
+                root, table, key, ..
+            } => {
+                let table = Self::key_table_name(root, table);
+                self.key_bindings.unbind(&table, key)?;
+                Ok(String::new())
+            }
+            SplixCommand::ListKeys { table } => self.list_keys(table.as_deref()),
+            SplixCommand::SwitchClient { table, target } => {
+                let client = self
+                    .command_client
+                    .and_then(|client| self.client_index(client))
+                    .ok_or(splix_error::Error::NoCurrentClient)?;
+
+                if let Some(target) = target {
+                    let session = self.resolve_session(Some(&target))?;
+                    self.attach_client(client, Some(&session.to_string())).await;
+                }
+                if let Some(table) = table {
+                    self.key_bindings.get_table(&table)?;
+                    self.clients[client].set_key_table(&table);
+                }
+                Ok(String::new())
+            }
+            SplixCommand::SetOption {
+                global,
+                window,
+                pane,
+                unset,
+                target,
+                option,
+                value,
+            } => {
+                let scope = Self::option_scope(global, window, pane);
+                let value = value.filter(|_| !unset);
+                self.set_option(scope, target.as_deref(), &option, value.as_deref())?;
+                Ok(String::new())
+            }
+            SplixCommand::ShowOptions {
+                global,
+                window,
+                pane,
+                inherited,
+                values_only,
+                target,
+                option,
+            } => self.show_options(
+                Self::option_scope(global, window, pane),
+                target.as_deref(),
+                option.as_deref(),
+                inherited,
+                values_only,
+            ),
+            SplixCommand::SourceFile { path } => {
+                self.source_file(&ConfigFile::read(path)?).await?;
+                Ok(String::new())
+            }
+            SplixCommand::ShowMessages => Ok(self
+                .messages
+                .iter()
+                .map(|message| format!("{message}\n"))
+                .collect()),
+        }
+    }
+
+    /// Keys are bound in the prefix table unless told otherwise.
+    fn key_table_name(root: bool, table: Option<String>) -> String {
+        match (root, table) {
+            (true, _) => String::from(ROOT_TABLE),
+            (false, Some(table)) => table,
+            (false, None) => String::from(PREFIX_TABLE),
+        }
+    }
+
+    /// Bindings are listed as the commands that would bind them again.
+    fn list_keys(&self, table: Option<&str>) -> splix_error::Result<String> {
+        let tables = match table {
+            Some(table) => vec![self.key_bindings.get_table(table)?],
+            None => self.key_bindings.get_tables().iter().collect(),
+        };
+
+        let mut output = String::new();
+        for table in tables {
+            for binding in table.get_bindings() {
+                let mut arguments = vec![String::from("bind-key")];
+                if binding.is_repeat() {
+                    arguments.push(String::from("-r"));
+                }
+                arguments.extend([
+                    String::from("-T"),
+                    table.get_name().to_string(),
+                    binding.get_key().to_string(),
+                ]);
+                arguments.extend_from_slice(binding.get_command());
+
+                output += &format!("{}\n", CommandLine::new(arguments));
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// The level the flags of `set-option` and `show-options` pick, if any.
+    fn option_scope(global: bool, window: bool, pane: bool) -> Option<OptionScope> {
+        match (global, window, pane) {
+            (true, _, _) => Some(OptionScope::Server),
+            (_, true, _) => Some(OptionScope::Window),
+            (_, _, true) => Some(OptionScope::Pane),
+            _ => None,
+        }
+    }
+
+    /// Runs every command of the file, even after one failed, reporting each failure along with
+    /// the line it's on.
+    pub(crate) async fn source_file(
+        &mut self,
+        config_file: &ConfigFile,
+    ) -> splix_error::Result<()> {
+        let mut errors = Vec::new();
+        for (number, line) in config_file.get_lines() {
+            let result = match line.parse::<CommandLine>() {
+                // Boxed, as the file may source others.
+                Ok(command_line) => Box::pin(self.run_command(command_line.get_arguments()))
+                    .await
+                    .map(drop),
+                Err(e) => Err(e),
+            };
+
+            // The usage following mistakes in the arguments is left out.
+            if let Err(e) = result {
+                let message = e.to_string();
+                errors.push(format!(
+                    "{}:{number}: {}",
+                    config_file.get_path().display(),
+                    message.lines().next().unwrap_or_default()
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(splix_error::Error::ConfigFile(errors))
+        }
+    }
+
+    /// Splits the target pane, starting the new one where the user currently is unless told
+    /// otherwise.
+    pub(crate) fn split_window(
+        &mut self,
+        target: Option<&str>,
+        horizontal: bool,
+        detached: bool,
+        start_directory: Option<PathBuf>,
+        command: Vec<String>,
+    ) -> splix_error::Result<PaneId> {
+        let pane = self.resolve_pane(target)?;
+        let direction = if horizontal {
+            SplitDirection::Horizontal
+        } else {
+            SplitDirection::Vertical
+        };
+        let start_directory = start_directory.or_else(|| self.get_pane_working_directory(pane));
+
+        self.split_pane(
+            pane,
+            direction,
+            Self::build_command(command, start_directory),
+            detached,
+        )
+    }
+
+    /// The socket is created next to the server socket by default, and reopening it elsewhere
+    /// disconnects the tools using the previous one.
+    fn open_json_socket(&mut self, socket_path: Option<PathBuf>) -> splix_error::Result<PathBuf> {
+        let socket_path = socket_path.unwrap_or_else(|| {
+            self.terminal_config
+                .get_server_socket()
+                .unwrap_or(Path::new("splix"))
+                .with_extension("json")
+        });
+
+        if let Some(json_listener) = &self.json_listener {
+            if json_listener.get_socket_path() == socket_path {
+                return Ok(socket_path);
+            }
+
+            self.json_listener = None;
+            self.json_connections.clear();
+        }
+
+        self.json_listener = Some(JsonListener::new(&socket_path, self.event_sender.clone())?);
+        Ok(socket_path)
+    }
+
+    fn split_pane(
+        &mut self,
+        pane: PaneId,
+        direction: SplitDirection,
+        command: Command,
+        detached: bool,
+    ) -> splix_error::Result<PaneId> {
+        let Some(window) = self
+            .get_session_mut(pane.get_window().get_session())
+            .and_then(|session| session.get_window_mut(pane.get_window()))
+        else {
+            return Err(splix_error::Error::PaneNotFound(pane.to_string()));
+        };
+
+        let new_pane = window.split_pane(pane, direction, command)?;
+        if detached {
+            window.select_pane(pane);
+        }
+
+        Ok(new_pane)
+    }
+
+    /// Arguments that aren't key names are sent as text, as are all of them when `literal`.
+    fn encode_keys(&self, pane: PaneId, keys: &[String], literal: bool) -> Vec<u8> {
+        let keyboard_modes = self
+            .get_pane(pane)
+            .map(Pane::get_keyboard_modes)
+            .unwrap_or_default();
+
+        keys.iter()
+            .flat_map(|key| match key.parse::<Key>() {
+                Ok(parsed_key) if !literal => parsed_key.encode(keyboard_modes),
+                _ => key.as_bytes().to_vec(),
+            })
+            .collect()
+    }
+
+    async fn send_pane_input(&mut self, pane: PaneId, input: &[u8]) {
+        let Some(window) = self
+            .get_session_mut(pane.get_window().get_session())
+            .and_then(|session| session.get_window_mut(pane.get_window()))
+        else {
+            return;
+        };
+
+        window.process_pane_input(pane, input);
+    }
+
+    /// Clients attached to the session are told it exited, and the server exits along with its
+    /// last session.
+    async fn kill_session(&mut self, session: SessionId) {
+        for client in self.get_attached_clients(session) {
+            self.drop_client_session(client, ServerMessage::Exit).await;
+        }
+
+        self.sessions
+            .retain(|candidate| candidate.get_id() != session);
+        if self.active_session == Some(session) {
+            self.active_session = self.sessions.last().map(|session| session.get_id());
+        }
+
+        if self.sessions.is_empty() {
+            self.exiting = true;
+        }
+    }
+
+    pub(crate) async fn remove_empty_session(&mut self, session: SessionId) {
+        if self
+            .get_session(session)
+            .is_some_and(|session| session.is_empty())
+        {
+            self.kill_session(session).await;
+        }
+    }
+
+    fn get_attached_clients(&self, session: SessionId) -> Vec<ClientId> {
+        self.get_session(session)
+            .map(|session| session.get_attached_clients().to_vec())
+            .unwrap_or_default()
+    }
+
+    fn list_sessions(&self, format: &Format) -> String {
+        self.sessions
+            .iter()
+            .map(|session| format.expand(&formats::session_variables(session)) + "\n")
+            .collect()
+    }
+
+    fn list_windows(&self, session: SessionId, format: &Format) -> String {
+        let Some(session) = self.get_session(session) else {
+            return String::new();
+        };
+
+        session
+            .get_windows()
+            .iter()
+            .map(|window| format.expand(&formats::window_variables(session, window)) + "\n")
+            .collect()
+    }
+
+    /// Panes are listed in the order they're laid out in.
+    fn list_panes(&self, window: WindowId, format: &Format) -> String {
+        let Some(session) = self.get_session(window.get_session()) else {
+            return String::new();
+        };
+        let Some(window) = session.get_window(window) else {
+            return String::new();
+        };
+
+        window
+            .get_pane_ids()
+            .into_iter()
+            .filter_map(|pane| window.get_pane(pane))
+            .map(|pane| format.expand(&formats::pane_variables(session, window, pane)) + "\n")
+            .collect()
+    }
+
+    /// Buffers are listed most recent first.
+    fn list_buffers(&self, format: &Format) -> String {
+        self.paste_buffers
+            .get_buffers()
+            .iter()
+            .map(|buffer| format.expand(&formats::buffer_variables(buffer)) + "\n")
+            .collect()
+    }
+
+    /// Prints what `format` expands to for a new window, or its ID without a format.
+    fn print_window(&self, print: bool, window: WindowId, format: Option<String>) -> String {
+        let Some(session) = self.get_session(window.get_session()).filter(|_| print) else {
+            return String::new();
+        };
+        let Some(window) = session.get_window(window) else {
+            return String::new();
+        };
+
+        let format = Format::new(format.unwrap_or_else(|| String::from("#{window_id}")));
+        format.expand(&formats::window_variables(session, window)) + "\n"
+    }
+
+    /// Prints what `format` expands to for a new pane, or its ID without a format.
+    fn print_pane(&self, print: bool, pane: PaneId, format: Option<String>) -> String {
+        let Some(session) = self
+            .get_session(pane.get_window().get_session())
+            .filter(|_| print)
+        else {
+            return String::new();
+        };
+        let Some((window, pane)) = session
+            .get_window(pane.get_window())
+            .and_then(|window| Some((window, window.get_pane(pane)?)))
+        else {
+            return String::new();
+        };
+
+        let format = Format::new(format.unwrap_or_else(|| String::from("#{pane_id}")));
+        format.expand(&formats::pane_variables(session, window, pane)) + "\n"
+    }
+
+    fn build_command(arguments: Vec<String>, start_directory: Option<PathBuf>) -> Command {
+        let command = if arguments.is_empty() {
+            Command::shell()
+        } else {
+            Command::new(arguments)
+        };
+
+        match start_directory {
+            Some(start_directory) => command.with_working_directory(start_directory),
+            None => command,
+        }
+    }
+
+    fn print_id(print: bool, id: impl std::fmt::Display) -> String {
+        if print {
+            format!("{id}\n")
+        } else {
+            String::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_event::PaneExitEvent;
+    use splix_terminal::Command;
+
+    use crate::tests::test_server;
+
+    fn arguments(arguments: &[&str]) -> Vec<String> {
+        arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn respawns_exited_panes_and_running_ones_only_with_k() {
+        let mut splix = test_server();
+        splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let pane = splix.resolve_pane(None).unwrap();
+
+        assert!(matches!(
+            splix.run_command(&arguments(&["respawn-pane"])).await,
+            Err(splix_error::Error::PaneStillActive(_))
+        ));
+        assert_eq!(splix.get_pane(pane).unwrap().get_generation(), 0);
+
+        splix
+            .run_command(&arguments(&["respawn-pane", "-k", "-t", &pane.to_string()]))
+            .await
+            .unwrap();
+        assert_eq!(splix.get_pane(pane).unwrap().get_generation(), 1);
+
+        splix.handle_pane_exit(&PaneExitEvent::new(pane, 1)).await;
+        splix
+            .run_command(&arguments(&["respawnp", "sleep", "10"]))
+            .await
+            .unwrap();
+        let respawned = splix.get_pane(pane).unwrap();
+        assert!(!respawned.is_exited());
+        assert_eq!(respawned.get_generation(), 2);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A file of commands run one after the other, like the config file run when the server starts.
+///
+/// Each line holds a single command, quoted like in control mode. Blank lines and lines starting
+/// with `#` are skipped.
+pub struct ConfigFile {
+    path: PathBuf,
+    lines: Vec<(usize, String)>,
+}
+
+const CONFIG_DIRECTORY_ENVIRONMENT_VARIABLE: &str = "XDG_CONFIG_HOME";
+const HOME_ENVIRONMENT_VARIABLE: &str = "HOME";
+const FALLBACK_CONFIG_DIRECTORY: &str = ".config";
+const CONFIG_FILE_PATH: &str = "splix/config";
+const COMMENT_PREFIX: char = '#';
+
+impl ConfigFile {
+    pub fn read(path: impl Into<PathBuf>) -> splix_error::Result<Self> {
+        let path = path.into();
+        let contents = fs::read_to_string(&path)
+            .map_err(|e| splix_error::Error::ReadConfigFile(path.clone(), e))?;
+
+        Ok(Self::parse(path, &contents))
+    }
+
+    /// Splits the `contents` of the file at `path` into its commands.
+    pub fn parse(path: impl Into<PathBuf>, contents: &str) -> Self {
+        let lines = contents
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with(COMMENT_PREFIX))
+            .map(|(number, line)| (number, line.to_string()))
+            .collect();
+
+        Self {
+            path: path.into(),
+            lines,
+        }
+    }
+
+    /// Reads the config file of the user, which is `$XDG_CONFIG_HOME/splix/config`, or
+    /// `~/.config/splix/config` when the variable isn't set. Not having one isn't an error.
+    pub fn load() -> splix_error::Result<Option<Self>> {
+        let Some(path) = Self::resolve_path() else {
+            return Ok(None);
+        };
+
+        match Self::read(path) {
+            Err(splix_error::Error::ReadConfigFile(_, e))
+                if e.kind() == io::ErrorKind::NotFound =>
+            {
+                Ok(None)
+            }
+            result => result.map(Some),
+        }
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The commands of the file, along with the numbers of the lines they're on, from 1.
+    pub fn get_lines(&self) -> &[(usize, String)] {
+        &self.lines
+    }
+
+    fn resolve_path() -> Option<PathBuf> {
+        let config_directory = match env::var_os(CONFIG_DIRECTORY_ENVIRONMENT_VARIABLE) {
+            Some(config_directory) if !config_directory.is_empty() => {
+                PathBuf::from(config_directory)
+            }
+            _ => PathBuf::from(env::var_os(HOME_ENVIRONMENT_VARIABLE)?)
+                .join(FALLBACK_CONFIG_DIRECTORY),
+        };
+
+        Some(config_directory.join(CONFIG_FILE_PATH))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigFile;
+    use crate::tests::test_server;
+
+    #[test]
+    fn skips_blank_lines_and_comments() {
+        let config_file = ConfigFile::parse(
+            "config",
+            "# Use C-a like screen.\n\n  set -g prefix C-a  \n\tbind a send-prefix\n",
+        );
+        assert_eq!(
+            config_file.get_lines(),
+            [
+                (3, String::from("set -g prefix C-a")),
+                (4, String::from("bind a send-prefix")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn runs_every_command_and_reports_each_failure() {
+        let mut splix = test_server();
+        let config_file = ConfigFile::parse(
+            "config",
+            "set -g prefix C-a\nfrobnicate\nset -g repeat-time soon\nset -g mode-keys vi\n'",
+        );
+
+        let Err(splix_error::Error::ConfigFile(errors)) = splix.source_file(&config_file).await
+        else {
+            panic!("the config file has mistakes");
+        };
+        assert_eq!(errors.len(), 3);
+        assert!(errors[0].starts_with("config:2: "));
+        assert_eq!(
+            errors[1],
+            "config:3: invalid value for option repeat-time: soon"
+        );
+        assert!(errors[2].starts_with("config:5: "));
+
+        assert_eq!(
+            splix
+                .show_options(None, None, Some("prefix"), false, true)
+                .unwrap(),
+            "C-a\n"
+        );
+        assert_eq!(
+            splix
+                .show_options(None, None, Some("mode-keys"), false, true)
+                .unwrap(),
+            "vi\n"
+        );
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use splix_command::CommandLine;
+use splix_id::PaneId;
+use splix_protocol::ServerMessage;
+
+use crate::{Splix, control_notification::ControlNotification, control_snapshot::ControlSnapshot};
+
+/// Control mode, where clients send commands as lines and get back text like tmux's: replies
+/// framed by `%begin` and `%end` or `%error`, and notifications like `%output` or `%window-add`
+/// about whatever changes in their session.
+impl Splix {
+    /// An empty line detaches the client.
+    pub(crate) async fn run_control_command(&mut self, index: usize, line: &str) {
+        let client = self.clients[index].get_id();
+        if line.is_empty() {
+            self.detach_client(client).await;
+            return;
+        }
+
+        let number = self.clients[index].next_command_number();
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs())
+            .unwrap_or_default();
+
+        self.command_client = Some(client);
+        let result = match line.parse::<CommandLine>() {
+            Ok(command_line) => self.run_command(command_line.get_arguments()).await,
+            Err(e) => Err(e),
+        };
+        self.command_client = None;
+        let (mut output, end) = match result {
+            Ok(output) => (output, "%end"),
+            Err(e) => (e.to_string().trim_end().to_string(), "%error"),
+        };
+        if !output.is_empty() && !output.ends_with('\n') {
+            output.push('\n');
+        }
+
+        // The command may have detached or otherwise dropped the client.
+        if let Some(index) = self.client_index(client) {
+            self.clients[index].send(ServerMessage::ControlOutput(format!(
+                "%begin {time} {number} 1\n{output}{end} {time} {number} 1\n"
+            )));
+        }
+    }
+
+    /// Starts telling the client about changes from the current state of the sessions on.
+    pub(crate) async fn start_control_mode(&mut self, index: usize) {
+        self.catch_up_subscribers().await;
+        self.clients[index].set_control(true);
+    }
+
+    /// Tells those already subscribed about what changed before a new subscriber, which only
+    /// hears about changes from the current state of the sessions on.
+    pub(crate) async fn catch_up_subscribers(&mut self) {
+        self.notify_subscribers().await;
+        self.control_snapshot = ControlSnapshot::new(&self.sessions);
+    }
+
+    pub(crate) fn record_control_output(&mut self, pane: PaneId, output: char) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        match self.control_output.last_mut() {
+            Some((last_pane, last_output)) if *last_pane == pane => last_output.push(output),
+            _ => self.control_output.push((pane, String::from(output))),
+        }
+    }
+
+    /// Sends the output of panes recorded since the last time, then notifications about the
+    /// changes to the sessions, to control clients and subscribed JSON connections.
+    pub(crate) async fn notify_subscribers(&mut self) {
+        if !self.has_subscribers() {
+            return;
+        }
+
+        let output = std::mem::take(&mut self.control_output);
+        let snapshot = ControlSnapshot::new(&self.sessions);
+        let notifications = self.control_snapshot.diff(&snapshot);
+        self.control_snapshot = snapshot;
+
+        self.notify_control_clients(&output, &notifications).await;
+        self.notify_json_subscribers(&output, &notifications);
+    }
+
+    fn has_subscribers(&self) -> bool {
+        self.clients.iter().any(|client| client.is_control())
+            || self
+                .json_connections
+                .iter()
+                .any(|connection| connection.is_subscribed())
+    }
+
+    /// Control clients only hear about their own session.
+    async fn notify_control_clients(
+        &mut self,
+        output: &[(PaneId, String)],
+        notifications: &[ControlNotification],
+    ) {
+        for client in self.clients.iter_mut().filter(|client| client.is_control()) {
+            let Some(session) = client.get_session() else {
+                continue;
+            };
+
+            let mut text = String::new();
+            for (pane, data) in output.iter() {
+                if pane.get_window().get_session() == session {
+                    writeln!(text, "%output {pane} {}", Self::escape_control_output(data)).ok();
+                }
+            }
+            for notification in notifications.iter() {
+                if notification
+                    .get_session()
+                    .is_none_or(|notified_session| notified_session == session)
+                {
+                    writeln!(text, "{notification}").ok();
+                }
+            }
+
+            if !text.is_empty() {
+                client.send(ServerMessage::ControlOutput(text));
+            }
+        }
+    }
+
+    /// Control characters and backslashes are written as octal escapes like `\015`, so that
+    /// output fits on a line.
+    fn escape_control_output(output: &str) -> String {
+        let mut escaped = String::with_capacity(output.len());
+        for c in output.chars() {
+            if c < ' ' || c == '\\' {
+                write!(escaped, "\\{:03o}", c as u32).ok();
+            } else {
+                escaped.push(c);
+            }
+        }
+
+        escaped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_protocol::ServerMessage;
+    use splix_terminal::Command;
+    use tokio::sync::mpsc::Receiver;
+
+    use crate::{
+        Splix,
+        tests::{attach_test_client, test_server},
+    };
+
+    fn control_output(message_receiver: &mut Receiver<ServerMessage>) -> String {
+        let mut output = String::new();
+        while let Ok(message) = message_receiver.try_recv() {
+            if let ServerMessage::ControlOutput(text) = message {
+                output.push_str(&text);
+            }
+        }
+
+        output
+    }
+
+    #[test]
+    fn escapes_control_characters_and_backslashes_in_octal() {
+        assert_eq!(
+            Splix::escape_control_output("ls\r\n\x1b[1m\\é"),
+            "ls\\015\\012\\033[1m\\134é"
+        );
+    }
+
+    #[tokio::test]
+    async fn frames_replies_to_commands() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(
+                Some(String::from("work")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let (client, mut message_receiver) =
+            attach_test_client(&mut splix, session, UVec2::new(80, 24), false);
+        let index = splix.client_index(client).unwrap();
+        splix.start_control_mode(index).await;
+
+        splix
+            .run_control_command(index, "list-sessions -F '#{session_name}'")
+            .await;
+        splix
+            .run_control_command(index, "kill-window -t nowhere")
+            .await;
+
+        // The time of each reply, in seconds, is left out.
+        let output: Vec<String> = control_output(&mut message_receiver)
+            .lines()
+            .map(|line| match line.split_once(' ') {
+                Some((guard, rest)) if guard.starts_with('%') => {
+                    format!("{guard} {}", rest.split_once(' ').unwrap().1)
+                }
+                _ => line.to_string(),
+            })
+            .collect();
+        assert_eq!(
+            output,
+            [
+                "%begin 0 1",
+                "work",
+                "%end 0 1",
+                "%begin 1 1",
+                "can't find window nowhere",
+                "%error 1 1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn notifies_control_clients_only_about_their_session() {
+        let mut splix = test_server();
+        let work = splix
+            .new_session(
+                Some(String::from("work")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let other = splix
+            .new_session(
+                Some(String::from("other")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let (client, mut message_receiver) =
+            attach_test_client(&mut splix, work, UVec2::new(80, 24), false);
+        let index = splix.client_index(client).unwrap();
+        splix.start_control_mode(index).await;
+
+        let work_window = splix
+            .get_session_mut(work)
+            .unwrap()
+            .new_window(None, Command::new(vec![String::from("cat")]), true)
+            .unwrap();
+        splix
+            .get_session_mut(other)
+            .unwrap()
+            .new_window(None, Command::new(vec![String::from("cat")]), true)
+            .unwrap();
+        splix.notify_subscribers().await;
+
+        assert_eq!(
+            control_output(&mut message_receiver),
+            format!("%window-add {work_window}\n")
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use std::fmt;
+
+use splix_id::{SessionId, WindowId};
+
+/// A change to the sessions that control clients and JSON subscribers are told about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControlNotification {
+    SessionsChanged,
+    SessionRenamed(SessionId, String),
+    WindowAdd(WindowId),
+    WindowRenamed(WindowId, String),
+    /// The new layout of the window, as described by `Window::get_layout_description`.
+    LayoutChange(WindowId, String),
+    WindowClose(WindowId),
+}
+
+impl ControlNotification {
+    /// The session whose control clients should hear about the change, or `None` for all of
+    /// them.
+    pub fn get_session(&self) -> Option<SessionId> {
+        match self {
+            Self::SessionsChanged | Self::SessionRenamed(..) => None,
+            Self::WindowAdd(window)
+            | Self::WindowRenamed(window, _)
+            | Self::LayoutChange(window, _)
+            | Self::WindowClose(window) => Some(window.get_session()),
+        }
+    }
+}
+
+/// The line control clients get, like `%window-add @1`.
+impl fmt::Display for ControlNotification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SessionsChanged => write!(f, "%sessions-changed"),
+            Self::SessionRenamed(session, name) => write!(f, "%session-renamed {session} {name}"),
+            Self::WindowAdd(window) => write!(f, "%window-add {window}"),
+            Self::WindowRenamed(window, name) => write!(f, "%window-renamed {window} {name}"),
+            Self::LayoutChange(window, layout) => write!(f, "%layout-change {window} {layout}"),
+            Self::WindowClose(window) => write!(f, "%window-close {window}"),
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use splix_id::{SessionId, WindowId};
+use splix_session::Session;
+
+use crate::control_notification::ControlNotification;
+
+/// What control clients and JSON subscribers were last told about the sessions and their
+/// windows, to notify them of whatever changed since.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ControlSnapshot {
+    sessions: Vec<(SessionId, String)>,
+    /// The name and layout of every window.
+    windows: Vec<(WindowId, String, String)>,
+}
+
+impl ControlSnapshot {
+    pub fn new(sessions: &[Session]) -> Self {
+        Self {
+            sessions: sessions
+                .iter()
+                .map(|session| (session.get_id(), session.get_name().to_string()))
+                .collect(),
+            windows: sessions
+                .iter()
+                .flat_map(Session::get_windows)
+                .map(|window| {
+                    (
+                        window.get_id(),
+                        window.get_name().to_string(),
+                        window.get_layout_description(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Lists the notifications leading from `self` to `current`.
+    pub fn diff(&self, current: &Self) -> Vec<ControlNotification> {
+        let mut notifications = Vec::new();
+
+        let session_ids = |snapshot: &Self| -> Vec<SessionId> {
+            snapshot.sessions.iter().map(|(id, _)| *id).collect()
+        };
+        if session_ids(self) != session_ids(current) {
+            notifications.push(ControlNotification::SessionsChanged);
+        }
+
+        for (id, name) in current.sessions.iter() {
+            if self
+                .sessions
+                .iter()
+                .any(|(previous_id, previous_name)| previous_id == id && previous_name != name)
+            {
+                notifications.push(ControlNotification::SessionRenamed(*id, name.clone()));
+            }
+        }
+
+        for (id, name, layout) in current.windows.iter() {
+            let Some((_, previous_name, previous_layout)) = self
+                .windows
+                .iter()
+                .find(|(previous_id, _, _)| previous_id == id)
+            else {
+                notifications.push(ControlNotification::WindowAdd(*id));
+                continue;
+            };
+
+            if previous_name != name {
+                notifications.push(ControlNotification::WindowRenamed(*id, name.clone()));
+            }
+            if previous_layout != layout {
+                notifications.push(ControlNotification::LayoutChange(*id, layout.clone()));
+            }
+        }
+
+        for (id, _, _) in self.windows.iter() {
+            if !current
+                .windows
+                .iter()
+                .any(|(current_id, _, _)| current_id == id)
+            {
+                notifications.push(ControlNotification::WindowClose(*id));
+            }
+        }
+
+        notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_terminal::Command;
+
+    use super::ControlSnapshot;
+    use crate::{Splix, control_notification::ControlNotification, tests::test_server};
+
+    async fn run(splix: &mut Splix, arguments: &[&str]) {
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        splix.run_command(&arguments).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn notifies_of_what_changed_between_snapshots() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(
+                Some(String::from("work")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let first_window = splix.resolve_window(None).unwrap();
+        let snapshot = ControlSnapshot::new(&splix.sessions);
+        assert!(
+            snapshot
+                .diff(&ControlSnapshot::new(&splix.sessions))
+                .is_empty()
+        );
+
+        run(&mut splix, &["rename-session", "-t", "work", "main"]).await;
+        run(&mut splix, &["rename-window", "-t", "main:0", "editor"]).await;
+        run(&mut splix, &["split-window", "-t", "main:0", "cat"]).await;
+        run(&mut splix, &["new-window", "-d", "cat"]).await;
+        let second_window = splix.resolve_window(Some("main:1")).unwrap();
+        let layout = splix
+            .get_session(session)
+            .unwrap()
+            .get_window(first_window)
+            .unwrap()
+            .get_layout_description();
+
+        let current = ControlSnapshot::new(&splix.sessions);
+        assert_eq!(
+            snapshot.diff(&current),
+            [
+                ControlNotification::SessionRenamed(session, String::from("main")),
+                ControlNotification::WindowRenamed(first_window, String::from("editor")),
+                ControlNotification::LayoutChange(first_window, layout),
+                ControlNotification::WindowAdd(second_window),
+            ]
+        );
+
+        let snapshot = current;
+        run(&mut splix, &["kill-window", "-t", "main:1"]).await;
+        splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        assert_eq!(
+            snapshot.diff(&ControlSnapshot::new(&splix.sessions)),
+            [
+                ControlNotification::SessionsChanged,
+                ControlNotification::WindowAdd(splix.sessions[1].get_active_window()),
+                ControlNotification::WindowClose(second_window),
+            ]
+        );
+    }
+}
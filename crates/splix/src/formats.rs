@@ -0,0 +1,83 @@
+use splix_format::FormatVariables;
+use splix_pane::Pane;
+use splix_session::Session;
+use splix_window::Window;
+
+use crate::paste_buffer::PasteBuffer;
+
+/// How many characters of each buffer `buffer_sample` shows.
+const BUFFER_SAMPLE_LENGTH: usize = 50;
+
+/// The variables formats can refer to for a session.
+pub(crate) fn session_variables(session: &Session) -> FormatVariables {
+    FormatVariables::new()
+        .with_variable("session_name", session.get_name())
+        .with_variable("session_id", session.get_id())
+        .with_variable("session_windows", session.get_windows().len())
+        .with_variable("session_attached", session.get_attached_clients().len())
+        .with_variable("session_width", session.get_dimensions().x)
+        .with_variable("session_height", session.get_dimensions().y)
+}
+
+/// The variables of a window, along with those of its session.
+pub(crate) fn window_variables(session: &Session, window: &Window) -> FormatVariables {
+    let active = window.get_id() == session.get_active_window();
+    let last = Some(window.get_id()) == session.get_last_window();
+    let flags = match (active, last) {
+        (true, _) => "*",
+        (false, true) => "-",
+        (false, false) => "",
+    };
+
+    session_variables(session)
+        .with_variable("window_index", window.get_index())
+        .with_variable("window_name", window.get_name())
+        .with_variable("window_id", window.get_id())
+        .with_variable("window_panes", window.get_panes().len())
+        .with_variable("window_width", window.get_dimensions().x)
+        .with_variable("window_height", window.get_dimensions().y)
+        .with_variable("window_flags", flags)
+        .with_flag("window_active", active)
+        .with_flag("window_last_flag", last)
+}
+
+/// The variables of a pane, along with those of its window and session.
+pub(crate) fn pane_variables(session: &Session, window: &Window, pane: &Pane) -> FormatVariables {
+    let working_directory = pane
+        .get_working_directory()
+        .map(|working_directory| working_directory.display().to_string())
+        .unwrap_or_default();
+    let process = pane.get_process();
+
+    window_variables(session, window)
+        .with_variable(
+            "pane_index",
+            window.get_pane_index(pane.get_id()).unwrap_or_default(),
+        )
+        .with_variable("pane_id", pane.get_id())
+        .with_variable("pane_width", pane.get_dimensions().x)
+        .with_variable("pane_height", pane.get_dimensions().y)
+        .with_variable("pane_current_path", working_directory)
+        .with_variable(
+            "pane_current_command",
+            process.get_foreground_command().unwrap_or_default(),
+        )
+        .with_variable("pane_pid", process.get_pid())
+        .with_flag("pane_active", pane.get_id() == window.get_active_pane())
+        .with_flag("pane_last", Some(pane.get_id()) == window.get_last_pane())
+        .with_flag("pane_dead", pane.is_exited())
+}
+
+pub(crate) fn buffer_variables(buffer: &PasteBuffer) -> FormatVariables {
+    let sample: String = buffer
+        .get_data()
+        .chars()
+        .take(BUFFER_SAMPLE_LENGTH)
+        .flat_map(char::escape_default)
+        .collect();
+
+    FormatVariables::new()
+        .with_variable("buffer_name", buffer.get_name())
+        .with_variable("buffer_size", buffer.get_data().len())
+        .with_variable("buffer_sample", sample)
+}
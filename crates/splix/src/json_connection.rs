@@ -0,0 +1,40 @@
+use tokio::sync::mpsc::Sender;
+
+use splix_id::ClientId;
+
+/// A tool connected to the JSON socket.
+pub struct JsonConnection {
+    id: ClientId,
+    subscribed: bool,
+    line_sender: Sender<String>,
+}
+
+impl JsonConnection {
+    pub fn new(id: ClientId, line_sender: Sender<String>) -> Self {
+        Self {
+            id,
+            subscribed: false,
+            line_sender,
+        }
+    }
+
+    pub fn get_id(&self) -> ClientId {
+        self.id
+    }
+
+    /// Whether the connection gets events about output and changes to the sessions.
+    pub fn is_subscribed(&self) -> bool {
+        self.subscribed
+    }
+
+    pub fn set_subscribed(&mut self, subscribed: bool) {
+        self.subscribed = subscribed;
+    }
+
+    /// Lines are dropped while the connection has too many of them unread, rather than holding
+    /// up the server until it reads them. Connections that went away are dropped once their
+    /// disconnect event arrives.
+    pub fn send(&self, line: String) {
+        self.line_sender.try_send(line).ok();
+    }
+}
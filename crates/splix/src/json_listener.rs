@@ -0,0 +1,165 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    sync::mpsc::{self, Receiver, Sender},
+    task::JoinHandle,
+    time,
+};
+
+use splix_event::{Event, JsonConnectEvent, JsonRequestEvent};
+use splix_id::ClientId;
+use splix_rpc::{RpcRequest, RpcResponse, Value};
+
+use crate::client_listener::ClientListener;
+
+/// Accepts connections on the JSON socket, where tools send JSON-RPC requests one per line and
+/// get back responses and the events they subscribed to the same way.
+pub struct JsonListener {
+    socket_path: PathBuf,
+    accept_task: JoinHandle<()>,
+    /// Aborted along with the listener, which closes the connections.
+    connection_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+const LINE_CHANNEL_CAPACITY: usize = 64;
+
+impl JsonListener {
+    pub fn new(socket_path: &Path, event_sender: Sender<Event>) -> splix_error::Result<Self> {
+        ClientListener::remove_stale_socket(socket_path)?;
+
+        let listener = UnixListener::bind(socket_path).map_err(splix_error::Error::BindSocket)?;
+        let connection_tasks = Arc::new(Mutex::new(Vec::new()));
+        let accept_task = tokio::spawn(Self::accept(
+            listener,
+            event_sender,
+            connection_tasks.clone(),
+        ));
+
+        Ok(Self {
+            socket_path: socket_path.to_path_buf(),
+            accept_task,
+            connection_tasks,
+        })
+    }
+
+    pub fn get_socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    async fn accept(
+        listener: UnixListener,
+        event_sender: Sender<Event>,
+        connection_tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+    ) {
+        let mut next_connection_id = 0;
+
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    eprintln!("Error while accepting a JSON connection: {e}");
+                    time::sleep(ClientListener::ACCEPT_RETRY_DELAY).await;
+                    continue;
+                }
+            };
+
+            let connection = ClientId::new(next_connection_id);
+            next_connection_id += 1;
+
+            let tasks = Self::serve(connection, stream, event_sender.clone());
+
+            let mut connection_tasks = connection_tasks
+                .lock()
+                .expect("connection tasks lock poisoned");
+            connection_tasks.retain(|task| !task.is_finished());
+            connection_tasks.extend(tasks);
+        }
+    }
+
+    /// Requests that can't be parsed are answered right away, without involving the server.
+    ///
+    /// Returns the tasks reading and writing the connection.
+    fn serve(
+        connection: ClientId,
+        stream: UnixStream,
+        event_sender: Sender<Event>,
+    ) -> [JoinHandle<()>; 2] {
+        let (read_half, mut write_half) = stream.into_split();
+        let (line_sender, mut line_receiver): (Sender<String>, Receiver<String>) =
+            mpsc::channel(LINE_CHANNEL_CAPACITY);
+
+        let write_task = tokio::spawn(async move {
+            while let Some(line) = line_receiver.recv().await {
+                if write_half.write_all(line.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        let read_task = tokio::spawn(async move {
+            if event_sender
+                .send(Event::JsonConnect(JsonConnectEvent::new(
+                    connection,
+                    line_sender.clone(),
+                )))
+                .await
+                .is_err()
+            {
+                return;
+            }
+
+            let mut lines = BufReader::new(read_half).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if line.trim().is_empty() {
+                    continue;
+                }
+
+                match line.parse::<RpcRequest>() {
+                    Ok(request) => {
+                        if event_sender
+                            .send(Event::JsonRequest(JsonRequestEvent::new(
+                                connection, request,
+                            )))
+                            .await
+                            .is_err()
+                        {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        let response = RpcResponse::new(Value::Null, Err(e));
+                        line_sender.send(response.to_line()).await.ok();
+                    }
+                }
+            }
+
+            event_sender
+                .send(Event::JsonDisconnect(connection))
+                .await
+                .ok();
+        });
+
+        [read_task, write_task]
+    }
+}
+
+impl Drop for JsonListener {
+    fn drop(&mut self) {
+        self.accept_task.abort();
+        for task in self
+            .connection_tasks
+            .lock()
+            .expect("connection tasks lock poisoned")
+            .drain(..)
+        {
+            task.abort();
+        }
+        fs::remove_file(&self.socket_path).ok();
+    }
+}
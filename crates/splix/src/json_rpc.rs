@@ -0,0 +1,552 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use splix_command::{LineNumber, SplixCommand};
+use splix_id::{ClientId, PaneId};
+use splix_layout::Geometry;
+use splix_pane::Pane;
+use splix_rpc::{RpcError, RpcNotification, RpcRequest, RpcResponse, Value, json};
+use splix_session::Session;
+use splix_window::Window;
+
+use crate::{Splix, control_notification::ControlNotification};
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct NoParams {}
+
+/// Listings cover every session without a target.
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct TargetParams {
+    target: Option<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SplitParams {
+    target: Option<String>,
+    horizontal: bool,
+    detached: bool,
+    start_directory: Option<PathBuf>,
+    command: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SendKeysParams {
+    target: Option<String>,
+    keys: Vec<String>,
+    literal: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CaptureParams {
+    target: Option<String>,
+    start: Option<i64>,
+    end: Option<i64>,
+    escapes: bool,
+    join: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CommandParams {
+    arguments: Vec<String>,
+}
+
+/// The JSON-RPC API served on the JSON socket, for tools that would rather not speak the client
+/// protocol: queries returning sessions, windows and panes as objects, actions mirroring the
+/// commands, and a subscription to events like those of control mode.
+impl Splix {
+    pub(crate) async fn handle_json_request(&mut self, connection: ClientId, request: &RpcRequest) {
+        let result = self.call_json_method(connection, request).await;
+        let response = RpcResponse::new(request.get_id().clone(), result);
+
+        if let Some(connection) = self
+            .json_connections
+            .iter()
+            .find(|candidate| candidate.get_id() == connection)
+        {
+            connection.send(response.to_line());
+        }
+    }
+
+    /// Sends the output and changes since the last time to subscribed connections.
+    pub(crate) fn notify_json_subscribers(
+        &self,
+        output: &[(PaneId, String)],
+        notifications: &[ControlNotification],
+    ) {
+        let mut lines = String::new();
+        for (pane, data) in output.iter() {
+            lines +=
+                &RpcNotification::new("output", json!({ "pane": pane.to_string(), "data": data }))
+                    .to_line();
+        }
+        for notification in notifications.iter() {
+            lines += &Self::notification_json(notification).to_line();
+        }
+
+        if lines.is_empty() {
+            return;
+        }
+        for connection in self
+            .json_connections
+            .iter()
+            .filter(|connection| connection.is_subscribed())
+        {
+            connection.send(lines.clone());
+        }
+    }
+
+    async fn call_json_method(
+        &mut self,
+        connection: ClientId,
+        request: &RpcRequest,
+    ) -> Result<Value, RpcError> {
+        match request.get_method() {
+            "list_sessions" => {
+                request.parse_params::<NoParams>()?;
+                Ok(self.sessions.iter().map(Self::session_json).collect())
+            }
+            "list_windows" => {
+                let params: TargetParams = request.parse_params()?;
+                self.list_windows_json(params.target.as_deref())
+            }
+            "list_panes" => {
+                let params: TargetParams = request.parse_params()?;
+                self.list_panes_json(params.target.as_deref())
+            }
+            "split" => {
+                let params: SplitParams = request.parse_params()?;
+                let pane = self
+                    .split_window(
+                        params.target.as_deref(),
+                        params.horizontal,
+                        params.detached,
+                        params.start_directory,
+                        params.command,
+                    )
+                    .map_err(Self::command_error)?;
+                self.redraw_pending = true;
+
+                Ok(self.pane_json(pane).unwrap_or_default())
+            }
+            "send_keys" => {
+                let params: SendKeysParams = request.parse_params()?;
+                self.execute_json_command(SplixCommand::SendKeys {
+                    literal: params.literal,
+                    copy_mode_command: false,
+                    target: params.target,
+                    keys: params.keys,
+                })
+                .await?;
+
+                Ok(Value::Null)
+            }
+            "capture" => {
+                let params: CaptureParams = request.parse_params()?;
+                let text = self
+                    .execute_json_command(SplixCommand::CapturePane {
+                        print: true,
+                        escapes: params.escapes,
+                        join: params.join,
+                        start: params.start.map(LineNumber::Line),
+                        end: params.end.map(LineNumber::Line),
+                        buffer: None,
+                        target: params.target,
+                    })
+                    .await?;
+
+                Ok(json!({ "text": text }))
+            }
+            "command" => {
+                let params: CommandParams = request.parse_params()?;
+                let output = self
+                    .run_command(&params.arguments)
+                    .await
+                    .map_err(Self::command_error)?;
+
+                Ok(json!({ "output": output }))
+            }
+            "subscribe" => {
+                request.parse_params::<NoParams>()?;
+                self.catch_up_subscribers().await;
+                self.set_json_subscribed(connection, true);
+                Ok(Value::Null)
+            }
+            "unsubscribe" => {
+                request.parse_params::<NoParams>()?;
+                self.set_json_subscribed(connection, false);
+                Ok(Value::Null)
+            }
+            method => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    async fn execute_json_command(&mut self, command: SplixCommand) -> Result<String, RpcError> {
+        let output = self
+            .execute_command(command)
+            .await
+            .map_err(Self::command_error)?;
+        self.redraw_pending = true;
+
+        Ok(output)
+    }
+
+    fn set_json_subscribed(&mut self, connection: ClientId, subscribed: bool) {
+        if let Some(connection) = self
+            .json_connections
+            .iter_mut()
+            .find(|candidate| candidate.get_id() == connection)
+        {
+            connection.set_subscribed(subscribed);
+        }
+    }
+
+    fn list_windows_json(&self, target: Option<&str>) -> Result<Value, RpcError> {
+        let sessions = match target {
+            Some(_) => {
+                let session = self.resolve_session(target).map_err(Self::command_error)?;
+                self.get_session(session).into_iter().collect()
+            }
+            None => self.sessions.iter().collect::<Vec<&Session>>(),
+        };
+
+        Ok(sessions
+            .into_iter()
+            .flat_map(|session| {
+                session
+                    .get_windows()
+                    .iter()
+                    .map(|window| Self::window_json(session, window))
+            })
+            .collect())
+    }
+
+    /// Panes are listed in the order they're laid out in.
+    fn list_panes_json(&self, target: Option<&str>) -> Result<Value, RpcError> {
+        let windows = match target {
+            Some(_) => {
+                let window = self.resolve_window(target).map_err(Self::command_error)?;
+                self.get_session(window.get_session())
+                    .and_then(|session| Some((session, session.get_window(window)?)))
+                    .into_iter()
+                    .collect()
+            }
+            None => self
+                .sessions
+                .iter()
+                .flat_map(|session| {
+                    session
+                        .get_windows()
+                        .iter()
+                        .map(move |window| (session, window))
+                })
+                .collect::<Vec<(&Session, &Window)>>(),
+        };
+
+        Ok(windows
+            .into_iter()
+            .flat_map(|(session, window)| {
+                window
+                    .get_pane_geometries()
+                    .into_iter()
+                    .filter_map(move |(pane, geometry)| {
+                        Some(Self::pane_geometry_json(
+                            session,
+                            window,
+                            window.get_pane(pane)?,
+                            &geometry,
+                        ))
+                    })
+            })
+            .collect())
+    }
+
+    fn pane_json(&self, pane: PaneId) -> Option<Value> {
+        let session = self.get_session(pane.get_window().get_session())?;
+        let window = session.get_window(pane.get_window())?;
+        let (_, geometry) = window
+            .get_pane_geometries()
+            .into_iter()
+            .find(|(candidate, _)| *candidate == pane)?;
+
+        Some(Self::pane_geometry_json(
+            session,
+            window,
+            window.get_pane(pane)?,
+            &geometry,
+        ))
+    }
+
+    fn session_json(session: &Session) -> Value {
+        json!({
+            "id": session.get_id().to_string(),
+            "name": session.get_name(),
+            "windows": session.get_windows().len(),
+            "attached": session.get_attached_clients().len(),
+            "width": session.get_dimensions().x,
+            "height": session.get_dimensions().y,
+        })
+    }
+
+    fn window_json(session: &Session, window: &Window) -> Value {
+        json!({
+            "id": window.get_id().to_string(),
+            "session": session.get_id().to_string(),
+            "index": window.get_index(),
+            "name": window.get_name(),
+            "active": window.get_id() == session.get_active_window(),
+            "last": Some(window.get_id()) == session.get_last_window(),
+            "panes": window.get_panes().len(),
+            "width": window.get_dimensions().x,
+            "height": window.get_dimensions().y,
+            "layout": window.get_layout_description(),
+        })
+    }
+
+    /// Along with where the pane is in its window, describes the process running in it.
+    fn pane_geometry_json(
+        session: &Session,
+        window: &Window,
+        pane: &Pane,
+        geometry: &Geometry,
+    ) -> Value {
+        let process = pane.get_process();
+        let working_directory = pane
+            .get_working_directory()
+            .map(|working_directory| working_directory.display().to_string());
+
+        json!({
+            "id": pane.get_id().to_string(),
+            "window": window.get_id().to_string(),
+            "session": session.get_id().to_string(),
+            "index": window.get_pane_index(pane.get_id()),
+            "active": pane.get_id() == window.get_active_pane(),
+            "dead": pane.is_exited(),
+            "x": geometry.get_position().x,
+            "y": geometry.get_position().y,
+            "width": geometry.get_dimensions().x,
+            "height": geometry.get_dimensions().y,
+            "pid": process.get_pid().as_raw(),
+            "current_command": process.get_foreground_command(),
+            "current_path": working_directory,
+        })
+    }
+
+    /// Events are named after the notifications of control mode, like `window_add` for
+    /// `%window-add`.
+    fn notification_json(notification: &ControlNotification) -> RpcNotification {
+        match notification {
+            ControlNotification::SessionsChanged => {
+                RpcNotification::new("sessions_changed", json!({}))
+            }
+            ControlNotification::SessionRenamed(session, name) => RpcNotification::new(
+                "session_renamed",
+                json!({ "session": session.to_string(), "name": name }),
+            ),
+            ControlNotification::WindowAdd(window) => {
+                RpcNotification::new("window_add", json!({ "window": window.to_string() }))
+            }
+            ControlNotification::WindowRenamed(window, name) => RpcNotification::new(
+                "window_renamed",
+                json!({ "window": window.to_string(), "name": name }),
+            ),
+            ControlNotification::LayoutChange(window, layout) => RpcNotification::new(
+                "layout_change",
+                json!({ "window": window.to_string(), "layout": layout }),
+            ),
+            ControlNotification::WindowClose(window) => {
+                RpcNotification::new("window_close", json!({ "window": window.to_string() }))
+            }
+        }
+    }
+
+    fn command_error(error: splix_error::Error) -> RpcError {
+        RpcError::command_failed(error.to_string().trim_end())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_id::ClientId;
+    use splix_rpc::{RpcError, Value, json};
+    use splix_terminal::Command;
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use crate::{Splix, json_connection::JsonConnection, tests::test_server};
+
+    const TEST_CONNECTION_CHANNEL_CAPACITY: usize = 16;
+
+    fn connect(splix: &mut Splix) -> (ClientId, Receiver<String>) {
+        let (line_sender, line_receiver) = mpsc::channel(TEST_CONNECTION_CHANNEL_CAPACITY);
+        let connection = ClientId::new(splix.json_connections.len());
+        splix
+            .json_connections
+            .push(JsonConnection::new(connection, line_sender));
+
+        (connection, line_receiver)
+    }
+
+    async fn call(
+        splix: &mut Splix,
+        connection: ClientId,
+        line_receiver: &mut Receiver<String>,
+        method: &str,
+        params: Value,
+    ) -> Value {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        splix
+            .handle_json_request(connection, &request.to_string().parse().unwrap())
+            .await;
+
+        line_receiver.try_recv().unwrap().parse().unwrap()
+    }
+
+    fn events(line_receiver: &mut Receiver<String>) -> Vec<Value> {
+        let mut events = Vec::new();
+        while let Ok(lines) = line_receiver.try_recv() {
+            events.extend(lines.lines().map(|line| line.parse::<Value>().unwrap()));
+        }
+
+        events
+    }
+
+    #[tokio::test]
+    async fn answers_queries_and_actions() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(
+                Some(String::from("work")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let (connection, mut line_receiver) = connect(&mut splix);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "list_sessions",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(response["result"][0]["id"], session.to_string());
+        assert_eq!(response["result"][0]["name"], "work");
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "split",
+            json!({ "target": "work:0", "horizontal": true, "command": ["cat"] }),
+        )
+        .await;
+        let pane = response["result"]["id"].as_str().unwrap().to_string();
+        assert_eq!(splix.get_active_pane().unwrap().to_string(), pane);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "list_panes",
+            json!({ "target": "work:0" }),
+        )
+        .await;
+        assert_eq!(response["result"].as_array().unwrap().len(), 2);
+        assert_eq!(response["result"][1]["id"], pane);
+    }
+
+    #[tokio::test]
+    async fn reports_unknown_methods_bad_params_and_failed_commands() {
+        let mut splix = test_server();
+        splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let (connection, mut line_receiver) = connect(&mut splix);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "frobnicate",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], RpcError::METHOD_NOT_FOUND);
+        assert_eq!(response["id"], 1);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "list_sessions",
+            json!({ "target": "work" }),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], RpcError::INVALID_PARAMS);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "list_windows",
+            json!({ "target": "nowhere" }),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], RpcError::COMMAND_FAILED);
+        assert_eq!(response["error"]["message"], "can't find session nowhere");
+    }
+
+    #[tokio::test]
+    async fn sends_events_to_subscribed_connections_only() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let (connection, mut line_receiver) = connect(&mut splix);
+        let (_, mut other_line_receiver) = connect(&mut splix);
+
+        let response = call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "subscribe",
+            Value::Null,
+        )
+        .await;
+        assert_eq!(response["result"], Value::Null);
+
+        let window = splix
+            .new_window(session, None, Command::new(vec![String::from("cat")]), true)
+            .unwrap();
+        splix.notify_subscribers().await;
+        assert_eq!(
+            events(&mut line_receiver),
+            [json!({
+                "jsonrpc": "2.0",
+                "method": "window_add",
+                "params": { "window": window.to_string() },
+            })]
+        );
+        assert!(events(&mut other_line_receiver).is_empty());
+
+        call(
+            &mut splix,
+            connection,
+            &mut line_receiver,
+            "unsubscribe",
+            Value::Null,
+        )
+        .await;
+        splix
+            .new_window(session, None, Command::new(vec![String::from("cat")]), true)
+            .unwrap();
+        splix.notify_subscribers().await;
+        assert!(events(&mut line_receiver).is_empty());
+    }
+}
@@ -0,0 +1,10 @@
+/// What becomes of a key a client sent, once looked up in its key table.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum KeyAction {
+    /// Sent on to the active pane, since nothing is bound to it.
+    Forward,
+    /// Swallowed, like the prefix key or unbound keys after it.
+    Ignore,
+    /// Runs the command bound to the key, given as its arguments.
+    Run(Vec<String>),
+}
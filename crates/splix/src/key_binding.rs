@@ -0,0 +1,33 @@
+use splix_key::Key;
+
+/// A command run when a key is pressed while its table is the one keys are looked up in.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyBinding {
+    key: Key,
+    repeat: bool,
+    command: Vec<String>,
+}
+
+impl KeyBinding {
+    pub fn new(key: Key, repeat: bool, command: Vec<String>) -> Self {
+        Self {
+            key,
+            repeat,
+            command,
+        }
+    }
+
+    pub fn get_key(&self) -> Key {
+        self.key
+    }
+
+    /// Repeatable keys can be pressed again without the prefix until the repeat time runs out.
+    pub fn is_repeat(&self) -> bool {
+        self.repeat
+    }
+
+    /// The command run for the key, as its arguments.
+    pub fn get_command(&self) -> &[String] {
+        &self.command
+    }
+}
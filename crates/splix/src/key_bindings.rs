@@ -0,0 +1,240 @@
+use splix_command::CommandLine;
+use splix_key::Key;
+
+use crate::{key_binding::KeyBinding, key_table::KeyTable};
+
+/// Every key table of the server.
+///
+/// Keys are looked up in the root table until one of the keys set by the `prefix` and `prefix2`
+/// options is pressed, then in the prefix table for a single key. While the active pane is in
+/// copy mode, keys are looked up in the copy mode table picked by the `mode-keys` option instead
+/// of the root table, and those without a binding are dropped rather than typed into the pane.
+pub struct KeyBindings {
+    tables: Vec<KeyTable>,
+}
+
+pub const ROOT_TABLE: &str = "root";
+pub const PREFIX_TABLE: &str = "prefix";
+
+/// Tables that stay around even without bindings.
+const BUILT_IN_TABLES: &[&str] = &[
+    ROOT_TABLE,
+    PREFIX_TABLE,
+    COPY_MODE_VI_TABLE,
+    COPY_MODE_EMACS_TABLE,
+];
+
+const COPY_MODE_VI_TABLE: &str = "copy-mode-vi";
+const COPY_MODE_EMACS_TABLE: &str = "copy-mode-emacs";
+
+/// The key, whether it repeats and the command of each binding of the prefix table.
+const DEFAULT_PREFIX_BINDINGS: &[(&str, bool, &str)] = &[
+    ("C-b", false, "send-prefix"),
+    ("d", false, "detach-client"),
+    ("c", false, "new-window"),
+    ("\"", false, "split-window"),
+    ("%", false, "split-window -h"),
+    ("x", false, "kill-pane"),
+    ("&", false, "kill-window"),
+    ("n", false, "select-window -t :+"),
+    ("p", false, "select-window -t :-"),
+    ("l", false, "select-window -t :!"),
+    ("0", false, "select-window -t :0"),
+    ("1", false, "select-window -t :1"),
+    ("2", false, "select-window -t :2"),
+    ("3", false, "select-window -t :3"),
+    ("4", false, "select-window -t :4"),
+    ("5", false, "select-window -t :5"),
+    ("6", false, "select-window -t :6"),
+    ("7", false, "select-window -t :7"),
+    ("8", false, "select-window -t :8"),
+    ("9", false, "select-window -t :9"),
+    ("o", false, "select-pane -t :.+"),
+    (";", false, "select-pane -t :.!"),
+    ("Up", true, "select-pane -t {up-of}"),
+    ("Down", true, "select-pane -t {down-of}"),
+    ("Left", true, "select-pane -t {left-of}"),
+    ("Right", true, "select-pane -t {right-of}"),
+    ("]", false, "paste-buffer"),
+    ("[", false, "copy-mode"),
+    ("PageUp", false, "copy-mode -u"),
+];
+
+/// The key and command of each binding of the copy mode tables.
+const DEFAULT_COPY_MODE_VI_BINDINGS: &[(&str, &str)] = &[
+    ("q", "send-keys -X cancel"),
+    ("C-c", "send-keys -X cancel"),
+    ("k", "send-keys -X scroll-up"),
+    ("Up", "send-keys -X scroll-up"),
+    ("j", "send-keys -X scroll-down"),
+    ("Down", "send-keys -X scroll-down"),
+    ("C-u", "send-keys -X halfpage-up"),
+    ("C-d", "send-keys -X halfpage-down"),
+    ("C-b", "send-keys -X page-up"),
+    ("PageUp", "send-keys -X page-up"),
+    ("C-f", "send-keys -X page-down"),
+    ("PageDown", "send-keys -X page-down"),
+    ("g", "send-keys -X history-top"),
+    ("G", "send-keys -X history-bottom"),
+];
+
+const DEFAULT_COPY_MODE_EMACS_BINDINGS: &[(&str, &str)] = &[
+    ("q", "send-keys -X cancel"),
+    ("Escape", "send-keys -X cancel"),
+    ("C-c", "send-keys -X cancel"),
+    ("C-p", "send-keys -X scroll-up"),
+    ("Up", "send-keys -X scroll-up"),
+    ("C-n", "send-keys -X scroll-down"),
+    ("Down", "send-keys -X scroll-down"),
+    ("M-v", "send-keys -X page-up"),
+    ("PageUp", "send-keys -X page-up"),
+    ("C-v", "send-keys -X page-down"),
+    ("PageDown", "send-keys -X page-down"),
+    ("M-<", "send-keys -X history-top"),
+    ("M->", "send-keys -X history-bottom"),
+];
+
+impl KeyBindings {
+    pub fn new() -> Self {
+        let mut tables: Vec<KeyTable> = BUILT_IN_TABLES
+            .iter()
+            .map(|name| KeyTable::new(name.to_string()))
+            .collect();
+
+        for (key, repeat, command) in DEFAULT_PREFIX_BINDINGS.iter() {
+            tables[1].bind(Self::default_binding(key, *repeat, command));
+        }
+        for (key, command) in DEFAULT_COPY_MODE_VI_BINDINGS.iter() {
+            tables[2].bind(Self::default_binding(key, false, command));
+        }
+        for (key, command) in DEFAULT_COPY_MODE_EMACS_BINDINGS.iter() {
+            tables[3].bind(Self::default_binding(key, false, command));
+        }
+
+        Self { tables }
+    }
+
+    fn default_binding(key: &str, repeat: bool, command: &str) -> KeyBinding {
+        let key = key.parse().expect("default bindings have valid keys");
+        let command: CommandLine = command.parse().expect("default bindings are quoted");
+        KeyBinding::new(key, repeat, command.into_arguments())
+    }
+
+    /// The table keys are looked up in while the active pane is in copy mode, for the value of
+    /// the `mode-keys` option.
+    pub fn get_copy_mode_table(mode_keys: &str) -> &'static str {
+        if mode_keys == "vi" {
+            COPY_MODE_VI_TABLE
+        } else {
+            COPY_MODE_EMACS_TABLE
+        }
+    }
+
+    pub fn get_tables(&self) -> &[KeyTable] {
+        &self.tables
+    }
+
+    pub fn get_table(&self, name: &str) -> splix_error::Result<&KeyTable> {
+        self.tables
+            .iter()
+            .find(|table| table.get_name() == name)
+            .ok_or_else(|| splix_error::Error::KeyTableNotFound(name.to_string()))
+    }
+
+    pub fn find(&self, table: &str, key: Key) -> Option<&KeyBinding> {
+        self.get_table(table).ok()?.find(key)
+    }
+
+    /// Binding a key in a table that doesn't exist yet creates it.
+    pub fn bind(&mut self, table: &str, binding: KeyBinding) {
+        match self.get_table_mut(table) {
+            Ok(table) => table.bind(binding),
+            Err(_) => {
+                let mut new_table = KeyTable::new(table.to_string());
+                new_table.bind(binding);
+                self.tables.push(new_table);
+            }
+        }
+    }
+
+    /// Unbinds `key`, or every key of the table without one. Tables that aren't built in go away
+    /// along with their last binding.
+    pub fn unbind(&mut self, table: &str, key: Option<Key>) -> splix_error::Result<()> {
+        let key_table = self.get_table_mut(table)?;
+        match key {
+            Some(key) => key_table.unbind(key),
+            None => key_table.clear(),
+        }
+
+        self.tables
+            .retain(|table| BUILT_IN_TABLES.contains(&table.get_name()) || !table.is_empty());
+
+        Ok(())
+    }
+
+    fn get_table_mut(&mut self, name: &str) -> splix_error::Result<&mut KeyTable> {
+        self.tables
+            .iter_mut()
+            .find(|table| table.get_name() == name)
+            .ok_or_else(|| splix_error::Error::KeyTableNotFound(name.to_string()))
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{KeyBindings, PREFIX_TABLE, ROOT_TABLE};
+    use crate::key_binding::KeyBinding;
+
+    #[test]
+    fn binds_defaults_in_the_prefix_table() {
+        let key_bindings = KeyBindings::new();
+        let detach = key_bindings
+            .find(PREFIX_TABLE, "d".parse().unwrap())
+            .unwrap();
+        assert_eq!(detach.get_command(), ["detach-client"]);
+        assert!(
+            key_bindings
+                .find(ROOT_TABLE, "d".parse().unwrap())
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn creates_and_removes_custom_tables() {
+        let mut key_bindings = KeyBindings::new();
+        let key = "C-x".parse().unwrap();
+        key_bindings.bind(
+            "custom",
+            KeyBinding::new(key, false, vec![String::from("kill-pane")]),
+        );
+        assert!(key_bindings.find("custom", key).is_some());
+
+        key_bindings.unbind("custom", Some(key)).unwrap();
+        assert!(key_bindings.get_table("custom").is_err());
+        assert!(key_bindings.unbind("custom", None).is_err());
+
+        key_bindings.unbind(PREFIX_TABLE, None).unwrap();
+        assert!(key_bindings.get_table(PREFIX_TABLE).unwrap().is_empty());
+    }
+
+    #[test]
+    fn picks_the_copy_mode_table_for_the_mode_keys() {
+        let key_bindings = KeyBindings::new();
+        let q = "q".parse().unwrap();
+        let escape = "Escape".parse().unwrap();
+        let emacs_table = KeyBindings::get_copy_mode_table("emacs");
+        let cancel = key_bindings.find(emacs_table, escape).unwrap();
+        assert_eq!(cancel.get_command(), ["send-keys", "-X", "cancel"]);
+
+        let vi_table = KeyBindings::get_copy_mode_table("vi");
+        assert_eq!(vi_table, "copy-mode-vi");
+        assert!(key_bindings.find(vi_table, q).is_some());
+        assert!(key_bindings.find(vi_table, escape).is_none());
+    }
+}
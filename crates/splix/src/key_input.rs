@@ -0,0 +1,242 @@
+use std::time::{Duration, Instant};
+
+use splix_command::SplixCommand;
+use splix_event::Paste;
+use splix_id::ClientId;
+use splix_key::{Key, KeyboardModes};
+use splix_pane::Pane;
+
+use crate::{
+    Splix,
+    key_action::KeyAction,
+    key_bindings::{KeyBindings, PREFIX_TABLE, ROOT_TABLE},
+    options::{MODE_KEYS_OPTION, PREFIX_OPTION, PREFIX2_OPTION, REPEAT_TIME_OPTION},
+};
+
+/// Input from attached clients, where keys bound in the client's key table run their commands
+/// and everything else is typed into the active pane of its session.
+impl Splix {
+    /// Input typed before a key binding reaches the pane before the binding's command runs, so
+    /// it ends up where the user was when typing it.
+    ///
+    /// Keys the client's terminal sent in enhanced sequences, and every key typed into a pane
+    /// whose program asked for those, are sent the way the program asked for. Pasted text is
+    /// never looked up in key tables. The markers around it only reach panes
+    /// whose program asked for bracketed paste.
+    pub(crate) async fn process_client_keys(&mut self, client: ClientId, input: &[u8]) {
+        let mut forwarded_input = Vec::with_capacity(input.len());
+        let Some(index) = self.client_index(client) else {
+            return;
+        };
+        let key_events = self.clients[index].decode_input(input);
+
+        for key_event in key_events.iter() {
+            // A binding may have detached the client.
+            let Some(index) = self
+                .client_index(client)
+                .filter(|index| self.clients[*index].get_session().is_some())
+            else {
+                return;
+            };
+
+            match key_event.get_paste() {
+                Some(Paste::Text) => {
+                    forwarded_input.extend_from_slice(key_event.get_bytes());
+                    continue;
+                }
+                Some(Paste::Start | Paste::End) => {
+                    if self.get_keyboard_modes(index).is_bracketed_paste() {
+                        forwarded_input.extend_from_slice(key_event.get_bytes());
+                    }
+                    continue;
+                }
+                None => {}
+            }
+
+            if let Some(mouse) = key_event.get_mouse() {
+                self.forward_client_input(index, &forwarded_input).await;
+                forwarded_input.clear();
+                self.process_client_mouse(index, mouse);
+                continue;
+            }
+
+            let action = match key_event.get_key() {
+                Some(key) => self.look_up_key(index, key),
+                None => KeyAction::Forward,
+            };
+
+            match action {
+                KeyAction::Forward => {
+                    let keyboard_modes = self.get_keyboard_modes(index);
+                    match key_event.get_key() {
+                        Some(key) if key_event.is_enhanced() || keyboard_modes.is_enhanced() => {
+                            forwarded_input.extend(key.encode(keyboard_modes))
+                        }
+                        // Only programs using the kitty keyboard protocol may know what to make of
+                        // its sequences that splix couldn't decode.
+                        None if key_event.is_enhanced()
+                            && keyboard_modes.get_kitty_flags() == 0 => {}
+                        _ => forwarded_input.extend_from_slice(key_event.get_bytes()),
+                    }
+                }
+                KeyAction::Ignore => {}
+                KeyAction::Run(command) => {
+                    self.forward_client_input(index, &forwarded_input).await;
+                    forwarded_input.clear();
+                    self.run_key_binding(client, &command).await;
+                }
+            }
+        }
+
+        if let Some(index) = self.client_index(client) {
+            self.forward_client_input(index, &forwarded_input).await;
+        }
+    }
+
+    /// Moves the client to the table the key leads to: the prefix table after a prefix key,
+    /// and back to the root table after any other key unless it repeats.
+    fn look_up_key(&mut self, index: usize, key: Key) -> KeyAction {
+        let Some(session) = self.clients[index].get_session() else {
+            return KeyAction::Ignore;
+        };
+        let prefixes = [PREFIX_OPTION, PREFIX2_OPTION]
+            .map(|option| self.get_session_option(session, option).as_key().flatten());
+        let repeat_time = self
+            .get_session_option(session, REPEAT_TIME_OPTION)
+            .as_number()
+            .unwrap_or_default();
+
+        let now = Instant::now();
+        let client = &mut self.clients[index];
+        if client
+            .get_repeat_deadline()
+            .is_some_and(|repeat_deadline| now > repeat_deadline)
+        {
+            client.set_key_table(ROOT_TABLE);
+        }
+
+        let table = client.get_key_table().to_string();
+        let repeating = client.get_repeat_deadline().is_some();
+        if table == ROOT_TABLE {
+            if prefixes.contains(&Some(key)) {
+                client.set_key_table(PREFIX_TABLE);
+                return KeyAction::Ignore;
+            }
+
+            let copy_mode_pane = self
+                .get_session(session)
+                .and_then(|session| self.get_pane(session.get_active_pane()))
+                .filter(|pane| pane.get_copy_mode().is_some())
+                .map(Pane::get_id);
+            if let Some(pane) = copy_mode_pane {
+                let mode_keys = self.get_window_option(pane.get_window(), MODE_KEYS_OPTION);
+                let table =
+                    KeyBindings::get_copy_mode_table(mode_keys.as_str().unwrap_or_default());
+                return match self.key_bindings.find(table, key) {
+                    Some(binding) => KeyAction::Run(binding.get_command().to_vec()),
+                    None => KeyAction::Ignore,
+                };
+            }
+
+            return match self.key_bindings.find(ROOT_TABLE, key) {
+                Some(binding) => KeyAction::Run(binding.get_command().to_vec()),
+                None => KeyAction::Forward,
+            };
+        }
+
+        client.set_key_table(ROOT_TABLE);
+        match self.key_bindings.find(&table, key) {
+            Some(binding) if !repeating || binding.is_repeat() => {
+                if binding.is_repeat() {
+                    client.start_repeat(&table, now + Duration::from_millis(repeat_time));
+                }
+                KeyAction::Run(binding.get_command().to_vec())
+            }
+            // Other keys end the repeat, and are handled as if it had already run out.
+            _ if repeating => self.look_up_key(index, key),
+            _ => KeyAction::Ignore,
+        }
+    }
+
+    /// Bound commands run on behalf of the client, so that e.g. `detach-client` detaches just
+    /// it. Read-only clients may only detach.
+    async fn run_key_binding(&mut self, client: ClientId, command: &[String]) {
+        let read_only = self
+            .client_index(client)
+            .is_some_and(|index| self.clients[index].is_read_only());
+        if read_only
+            && !matches!(
+                SplixCommand::parse(command),
+                Ok(SplixCommand::DetachClient { .. })
+            )
+        {
+            return;
+        }
+
+        self.command_client = Some(client);
+        // There's nowhere to show what bound commands print or why they failed yet.
+        self.run_command(command).await.ok();
+        self.command_client = None;
+    }
+
+    /// The keyboard modes the program in the active pane of the client's session asked for,
+    /// which keys typed into it must follow.
+    fn get_keyboard_modes(&self, index: usize) -> KeyboardModes {
+        self.clients[index]
+            .get_session()
+            .and_then(|session| self.get_session(session))
+            .and_then(|session| self.get_pane(session.get_active_pane()))
+            .map(|pane| pane.get_keyboard_modes())
+            .unwrap_or_default()
+    }
+
+    async fn forward_client_input(&mut self, index: usize, input: &[u8]) {
+        let client = &self.clients[index];
+        let Some(session) = client.get_session().filter(|_| !client.is_read_only()) else {
+            return;
+        };
+
+        if let Some(session) = self.get_session_mut(session) {
+            session.process_input(input);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_protocol::ServerMessage;
+    use splix_terminal::Command;
+
+    use crate::tests::{attach_test_client, test_server};
+
+    #[tokio::test]
+    async fn lets_read_only_clients_only_detach() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let (client, mut message_receiver) =
+            attach_test_client(&mut splix, session, UVec2::new(80, 24), true);
+
+        splix
+            .run_key_binding(client, &[String::from("new-window")])
+            .await;
+        assert_eq!(splix.get_session(session).unwrap().get_windows().len(), 1);
+
+        splix
+            .run_key_binding(client, &[String::from("detach-client")])
+            .await;
+        assert!(
+            splix
+                .get_session(session)
+                .unwrap()
+                .get_attached_clients()
+                .is_empty()
+        );
+        assert!(
+            std::iter::from_fn(|| message_receiver.try_recv().ok())
+                .any(|message| matches!(message, ServerMessage::Detach))
+        );
+    }
+}
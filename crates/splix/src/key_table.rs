@@ -0,0 +1,50 @@
+use splix_key::Key;
+
+use crate::key_binding::KeyBinding;
+
+/// A named set of key bindings, like the `prefix` table keys are looked up in after the prefix.
+pub struct KeyTable {
+    name: String,
+    bindings: Vec<KeyBinding>,
+}
+
+impl KeyTable {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            bindings: Vec::new(),
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_bindings(&self) -> &[KeyBinding] {
+        &self.bindings
+    }
+
+    pub fn find(&self, key: Key) -> Option<&KeyBinding> {
+        self.bindings
+            .iter()
+            .find(|binding| binding.get_key() == key)
+    }
+
+    /// Replaces any binding the key already has.
+    pub fn bind(&mut self, binding: KeyBinding) {
+        self.unbind(binding.get_key());
+        self.bindings.push(binding);
+    }
+
+    pub fn unbind(&mut self, key: Key) {
+        self.bindings.retain(|binding| binding.get_key() != key);
+    }
+
+    pub fn clear(&mut self) {
+        self.bindings.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bindings.is_empty()
+    }
+}
@@ -1,97 +1,786 @@
+mod client_connection;
+mod client_listener;
+mod commands;
+mod config_file;
+mod control_mode;
+mod control_notification;
+mod control_snapshot;
+mod formats;
+mod json_connection;
+mod json_listener;
+mod json_rpc;
+mod key_action;
+mod key_binding;
+mod key_bindings;
+mod key_input;
+mod key_table;
+mod mouse_input;
+mod options;
+mod paste_buffer;
+mod paste_buffers;
+mod status_line;
+mod targets;
+mod window_size;
+
+use std::{
+    mem,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
 use glam::UVec2;
-use splix_input::InputReceiver;
-use splix_renderer::Renderer;
-use terminal_size::{Height, Width};
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::{
+    sync::{
+        Semaphore,
+        mpsc::{self, Receiver, Sender},
+    },
+    time,
+};
 
-use splix_event::{Event, PaneUpdateEvent};
-use splix_id::SessionId;
+use client_connection::ClientConnection;
+use client_listener::ClientListener;
+pub use config_file::ConfigFile;
+use control_snapshot::ControlSnapshot;
+use json_connection::JsonConnection;
+use json_listener::JsonListener;
+use key_bindings::KeyBindings;
+use options::{EVENT_CHANNEL_CAPACITY_OPTION, REMAIN_ON_EXIT_OPTION};
+use paste_buffers::PasteBuffers;
+use splix_compositor::Compositor;
+use splix_event::{
+    ClientConnectEvent, ClientMessageEvent, Event, GridUpdate, JsonConnectEvent, PaneExitEvent,
+    PaneUpdateEvent,
+};
+use splix_format::Format;
+use splix_id::{ClientId, IdAllocator, PaneId, SessionId, WindowId};
+use splix_options::{OptionDefinition, Options};
+use splix_pane::Pane;
+use splix_protocol::{ClientMessage, ServerMessage};
 use splix_session::Session;
-use splix_termios::Termios;
+use splix_terminal::{Command, TerminalConfig};
+use status_line::StatusLine;
+pub use window_size::WindowSize;
 
+/// The server, owning every session and serving the clients attached to them.
 pub struct Splix {
-    _termios: Termios,
-    _input_receiver: InputReceiver,
+    client_listener: ClientListener,
+    /// Only listening once opened with `open-json-socket`.
+    json_listener: Option<JsonListener>,
+    /// What panes start with, apart from what the `default-shell` and `default-terminal` options
+    /// set.
+    terminal_config: TerminalConfig,
+    /// Options set for the whole server, which sessions, windows and panes inherit.
+    options: Options,
+    /// Run once the server runs.
+    config_file: Option<ConfigFile>,
+    /// Failures of the config file, shown to the first client attaching as the server has no
+    /// terminal of its own to report them on.
+    config_errors: Vec<String>,
+    /// Shown to clients so far, listed by `show-messages`.
+    messages: Vec<String>,
     sessions: Vec<Session>,
-    next_session_id: usize,
+    active_session: Option<SessionId>,
+    id_allocator: IdAllocator,
+    clients: Vec<ClientConnection>,
+    json_connections: Vec<JsonConnection>,
+    paste_buffers: PasteBuffers,
+    key_bindings: KeyBindings,
+    /// The client whose key binding or control mode line is running the current command.
+    command_client: Option<ClientId>,
+    control_snapshot: ControlSnapshot,
+    /// Output of panes not yet sent to control clients.
+    control_output: Vec<(PaneId, String)>,
     event_sender: Sender<Event>,
     event_receiver: Receiver<Event>,
-    renderer: Renderer,
+    compositor: Compositor,
+    redraw_pending: bool,
+    exiting: bool,
 }
 
-const EVENT_CHANNEL_CAPACITY: usize = 1024;
+/// How many messages `show-messages` lists, dropping the oldest ones first.
+const MESSAGE_LIMIT: usize = 100;
+
+/// What the terminal of each client is titled.
+const TITLE_FORMAT: &str = "#{session_name}:#{window_index}:#{window_name}";
+
+/// The size of sessions that no client with a known size was attached to yet.
+const DEFAULT_SESSION_DIMENSIONS: UVec2 = UVec2::new(80, 24);
+
+/// The largest size taken from clients, like tmux's, as frames of it are allocated up front.
+const MAXIMUM_CLIENT_DIMENSIONS: UVec2 = UVec2::new(10000, 10000);
+
+/// How long a redraw that a client was too far behind for waits for new events before being
+/// retried.
+const REDRAW_RETRY_DELAY: Duration = Duration::from_millis(50);
 
 impl Splix {
-    pub fn new() -> splix_error::Result<Self> {
-        let screen_dimensions = Self::retrieve_screen_dimensions()?;
+    /// The server starts without sessions, which are created by clients or by the commands of
+    /// `config_file`.
+    ///
+    /// Server options are set ahead of the other commands of `config_file`, as some of them are
+    /// only read here.
+    pub fn new(
+        terminal_config: TerminalConfig,
+        socket_path: &Path,
+        config_file: Option<ConfigFile>,
+    ) -> splix_error::Result<Self> {
+        let options = config_file
+            .as_ref()
+            .map(Self::read_server_options)
+            .unwrap_or_default();
+        let event_channel_capacity = OptionDefinition::find(EVENT_CHANNEL_CAPACITY_OPTION)?
+            .resolve([&options])
+            .as_number()
+            .and_then(|capacity| usize::try_from(capacity).ok())
+            .unwrap_or(Semaphore::MAX_PERMITS)
+            .clamp(1, Semaphore::MAX_PERMITS);
 
-        let termios = Termios::new()?;
         let (event_sender, event_receiver): (Sender<Event>, Receiver<Event>) =
-            mpsc::channel(EVENT_CHANNEL_CAPACITY);
-        let input_receiver = InputReceiver::new(event_sender.clone());
+            mpsc::channel(event_channel_capacity);
+        let client_listener = ClientListener::new(socket_path, event_sender.clone())?;
 
-        let mut splix = Self {
-            _termios: termios,
-            _input_receiver: input_receiver,
+        Ok(Self {
+            client_listener,
+            json_listener: None,
+            terminal_config: terminal_config.with_server_socket(socket_path),
+            options,
+            config_file,
+            config_errors: Vec::new(),
+            messages: Vec::new(),
             sessions: Vec::new(),
-            next_session_id: 0,
+            active_session: None,
+            id_allocator: IdAllocator::new(),
+            clients: Vec::new(),
+            json_connections: Vec::new(),
+            paste_buffers: PasteBuffers::new(),
+            key_bindings: KeyBindings::new(),
+            command_client: None,
+            control_snapshot: ControlSnapshot::default(),
+            control_output: Vec::new(),
             event_sender,
             event_receiver,
-            renderer: Renderer::new(screen_dimensions),
-        };
-
-        splix.new_session()?;
-
-        Ok(splix)
+            compositor: Compositor::new(),
+            redraw_pending: false,
+            exiting: false,
+        })
     }
 
+    /// Runs until the server is killed or its last session goes away.
+    ///
+    /// Failing commands of the config file don't stop the server, they're reported to the first
+    /// client attaching instead.
     pub async fn run(&mut self) -> splix_error::Result<()> {
-        while let Some(event) = self.event_receiver.recv().await {
+        if let Some(config_file) = self.config_file.take()
+            && let Err(e) = self.source_file(&config_file).await
+        {
+            for error in e.to_string().lines() {
+                self.add_message(error.to_string());
+                self.config_errors.push(error.to_string());
+            }
+        }
+
+        while !self.exiting {
+            let event = if self.redraw_pending {
+                match time::timeout(REDRAW_RETRY_DELAY, self.event_receiver.recv()).await {
+                    Ok(event) => event,
+                    Err(_) => {
+                        self.redraw().await;
+                        continue;
+                    }
+                }
+            } else {
+                self.event_receiver.recv().await
+            };
+            let Some(event) = event else {
+                break;
+            };
             self.handle_event(&event).await;
+
+            // Coalesce the redraws of a burst of events into a single frame.
+            if self.redraw_pending && self.event_receiver.is_empty() {
+                self.redraw().await;
+            }
+            self.disconnect_overflowed_clients();
         }
 
+        for mut client in self.clients.drain(..) {
+            client.send(ServerMessage::Exit);
+        }
+        self.client_listener.shut_down().await;
+
         Ok(())
     }
 
-    fn retrieve_screen_dimensions() -> splix_error::Result<UVec2> {
-        if let Some((Width(terminal_width), Height(terminal_height))) =
-            terminal_size::terminal_size()
+    /// Sessions without a name are named after their ID.
+    pub fn new_session(
+        &mut self,
+        name: Option<String>,
+        command: Command,
+    ) -> splix_error::Result<SessionId> {
+        let id = self.id_allocator.allocate_session_id();
+        let name = name.unwrap_or_else(|| id.get().to_string());
+        if self
+            .sessions
+            .iter()
+            .any(|session| session.get_name() == name)
         {
-            Ok(UVec2::new(terminal_width as u32, terminal_height as u32))
-        } else {
-            Err(splix_error::Error::RetrieveTerminalSize)
+            return Err(splix_error::Error::DuplicateSession(name));
         }
-    }
 
-    fn new_session(&mut self) -> splix_error::Result<()> {
-        let id = SessionId::new(self.next_session_id);
-        let session = Session::new(id, self.event_sender.clone())?;
+        let session = Session::new(
+            id,
+            name,
+            command,
+            DEFAULT_SESSION_DIMENSIONS,
+            self.get_terminal_config(),
+            self.event_sender.clone(),
+            self.id_allocator.clone(),
+        )?;
         self.sessions.push(session);
-        self.next_session_id += 1;
+        self.active_session.get_or_insert(id);
+
+        Ok(id)
+    }
+
+    pub fn new_window(
+        &mut self,
+        session: SessionId,
+        name: Option<String>,
+        command: Command,
+        detached: bool,
+    ) -> splix_error::Result<WindowId> {
+        match self.get_session_mut(session) {
+            Some(session) => session.new_window(name, command, detached),
+            None => Err(splix_error::Error::SessionNotFound(session.to_string())),
+        }
+    }
+
+    pub fn get_active_pane(&self) -> Option<PaneId> {
+        self.get_session(self.active_session?)
+            .map(Session::get_active_pane)
+    }
+
+    /// Lets new panes start where the user currently is, e.g. by passing the active pane.
+    pub fn get_pane_working_directory(&self, pane: PaneId) -> Option<PathBuf> {
+        self.get_session(pane.get_window().get_session())?
+            .get_pane_working_directory(pane)
+    }
+
+    /// Restarts the process of a pane, optionally replacing the command it runs.
+    pub fn respawn_pane(
+        &mut self,
+        pane: PaneId,
+        command: Option<Command>,
+        clear_grid: bool,
+    ) -> splix_error::Result<()> {
+        let Some(session) = self.get_session_mut(pane.get_window().get_session()) else {
+            return Err(splix_error::Error::PaneNotFound(pane.to_string()));
+        };
+
+        session.respawn_pane(pane, command, clear_grid)?;
+        self.redraw_pending = true;
 
         Ok(())
     }
 
+    fn get_session(&self, session: SessionId) -> Option<&Session> {
+        self.sessions
+            .iter()
+            .find(|candidate| candidate.get_id() == session)
+    }
+
+    fn get_session_mut(&mut self, session: SessionId) -> Option<&mut Session> {
+        self.sessions
+            .iter_mut()
+            .find(|candidate| candidate.get_id() == session)
+    }
+
+    fn get_pane(&self, pane: PaneId) -> Option<&Pane> {
+        self.get_session(pane.get_window().get_session())
+            .and_then(|session| session.get_window(pane.get_window()))
+            .and_then(|window| window.get_pane(pane))
+    }
+
+    fn get_pane_mut(&mut self, pane: PaneId) -> Option<&mut Pane> {
+        self.get_session_mut(pane.get_window().get_session())?
+            .get_window_mut(pane.get_window())?
+            .get_pane_mut(pane)
+    }
+
     async fn handle_event(&mut self, event: &Event) {
         match event {
             Event::PaneUpdate(event) => self.handle_pane_update(event),
-            Event::Input(input) => self.handle_input(*input).await,
+            Event::PaneExit(event) => self.handle_pane_exit(event).await,
+            Event::ClientConnect(event) => self.handle_client_connect(event),
+            Event::ClientMessage(event) => self.handle_client_message(event).await,
+            Event::ClientDisconnect(client) => self.handle_client_disconnect(*client),
+            Event::JsonConnect(event) => self.handle_json_connect(event),
+            Event::JsonRequest(event) => {
+                self.handle_json_request(event.get_connection(), event.get_request())
+                    .await
+            }
+            Event::JsonDisconnect(connection) => self
+                .json_connections
+                .retain(|candidate| candidate.get_id() != *connection),
         }
     }
 
+    /// Updates of panes that were killed or respawned in the meantime are dropped.
     fn handle_pane_update(&mut self, event: &PaneUpdateEvent) {
-        let session = &mut self.sessions[event.get_pane().get_window().get_session().get()];
-        session.update_pane(event.get_pane(), event.get_grid_update());
-        self.redraw();
+        let pane = event.get_pane();
+        if !self.is_current_generation(pane, event.get_generation()) {
+            return;
+        }
+
+        if let Some(session) = self.get_session_mut(pane.get_window().get_session()) {
+            session.update_pane(pane, event.get_grid_update());
+            self.redraw_pending = true;
+
+            let output = match event.get_grid_update() {
+                GridUpdate::AppendChar(c) => *c,
+                GridUpdate::NewLine => '\n',
+            };
+            self.record_control_output(pane, output);
+        }
+    }
+
+    /// Panes stay around once their program exited unless `remain-on-exit` is off for them.
+    /// Programs replaced by respawning the pane don't count.
+    async fn handle_pane_exit(&mut self, event: &PaneExitEvent) {
+        let pane = event.get_pane();
+        if !self.is_current_generation(pane, event.get_generation()) {
+            return;
+        }
+
+        let remain_on_exit = self
+            .get_pane_option(pane, REMAIN_ON_EXIT_OPTION)
+            .as_flag()
+            .unwrap_or(true);
+        let session = pane.get_window().get_session();
+        let Some(session_mut) = self.get_session_mut(session) else {
+            return;
+        };
+
+        if remain_on_exit {
+            session_mut.mark_pane_exited(pane);
+        } else {
+            session_mut.kill_pane(pane);
+            self.remove_empty_session(session).await;
+        }
+        self.redraw_pending = true;
+    }
+
+    fn is_current_generation(&self, pane: PaneId, generation: u64) -> bool {
+        self.get_pane(pane)
+            .is_some_and(|pane| pane.get_generation() == generation)
+    }
+
+    fn handle_client_connect(&mut self, event: &ClientConnectEvent) {
+        self.clients.push(ClientConnection::new(
+            event.get_client(),
+            event.get_message_sender().clone(),
+        ));
+    }
+
+    /// Connections accepted before the socket was closed are ignored.
+    fn handle_json_connect(&mut self, event: &JsonConnectEvent) {
+        if self.json_listener.is_some() {
+            self.json_connections.push(JsonConnection::new(
+                event.get_connection(),
+                event.get_line_sender().clone(),
+            ));
+        }
+    }
+
+    async fn handle_client_message(&mut self, event: &ClientMessageEvent) {
+        let Some(index) = self.client_index(event.get_client()) else {
+            return;
+        };
+
+        match event.get_message() {
+            // Read-only clients are shown the session at whatever size the others give it.
+            ClientMessage::Resize(dimensions) => {
+                let client = &mut self.clients[index];
+                client.set_dimensions(dimensions.clamp(UVec2::ONE, MAXIMUM_CLIENT_DIMENSIONS));
+                if !client.is_read_only() {
+                    client.mark_active();
+                    if let Some(session) = client.get_session() {
+                        self.resize_session(session);
+                    }
+                }
+                self.redraw_pending = true;
+            }
+            ClientMessage::Input(input) => self.handle_client_input(index, input).await,
+            ClientMessage::Attach {
+                target,
+                read_only,
+                control,
+            } => {
+                self.clients[index].set_read_only(*read_only);
+                if *control {
+                    self.start_control_mode(index).await;
+                }
+                self.attach_client(index, target.as_deref()).await;
+            }
+            ClientMessage::Command(arguments) => {
+                let client = self.clients[index].get_id();
+                let response = self.run_command(arguments).await.map_err(|e| e.to_string());
+
+                // The command may have detached or otherwise dropped the client.
+                if let Some(index) = self.client_index(client) {
+                    self.clients[index].send(ServerMessage::CommandResponse(response));
+                }
+            }
+            ClientMessage::ControlCommand(line) if self.clients[index].is_control() => {
+                self.run_control_command(index, line).await;
+            }
+            ClientMessage::ControlCommand(_) => {}
+            // Already handled by the listener when the client connected.
+            ClientMessage::Hello { .. } => {}
+        }
+    }
+
+    fn handle_client_disconnect(&mut self, client: ClientId) {
+        let Some(index) = self.client_index(client) else {
+            return;
+        };
+
+        let client = self.clients.remove(index);
+        if let Some(session) = client.get_session() {
+            if let Some(attached_session) = self.get_session_mut(session) {
+                attached_session.detach(client.get_id());
+            }
+            self.resize_session(session);
+        }
+    }
+
+    /// Dropping its connection closes it once the messages the client did take are written.
+    fn disconnect_overflowed_clients(&mut self) {
+        let overflowed_clients: Vec<ClientId> = self
+            .clients
+            .iter()
+            .filter(|client| client.is_overflowed())
+            .map(ClientConnection::get_id)
+            .collect();
+        for client in overflowed_clients {
+            self.handle_client_disconnect(client);
+        }
+    }
+
+    async fn attach_client(&mut self, index: usize, target: Option<&str>) {
+        let session = match self.resolve_session(target) {
+            Ok(session) => session,
+            Err(e) => {
+                self.clients[index].send(ServerMessage::Error(e.to_string()));
+                return;
+            }
+        };
+
+        // Attaching elsewhere leaves the session the client was attached to.
+        let client = self.clients[index].get_id();
+        if let Some(previous_session) = self.clients[index].get_session() {
+            if let Some(previous_session) = self.get_session_mut(previous_session) {
+                previous_session.detach(client);
+            }
+            self.resize_session(previous_session);
+        }
+
+        self.clients[index].set_session(Some(session));
+        if !self.clients[index].is_read_only() {
+            self.clients[index].mark_active();
+        }
+        if let Some(attached_session) = self.get_session_mut(session) {
+            attached_session.attach(client);
+        }
+        self.resize_session(session);
+        self.active_session = Some(session);
+        self.redraw_pending = true;
+
+        if self.clients[index].is_control()
+            && let Some(session) = self.get_session(session)
+        {
+            let notification = format!(
+                "%session-changed {} {}\n",
+                session.get_id(),
+                session.get_name()
+            );
+            self.clients[index].send(ServerMessage::ControlOutput(notification));
+        }
+
+        self.report_config_errors(index).await;
+    }
+
+    /// Control clients get a notification for each error, others see the first one in their
+    /// status line.
+    async fn report_config_errors(&mut self, index: usize) {
+        let config_errors = mem::take(&mut self.config_errors);
+        let Some(first_error) = config_errors.first() else {
+            return;
+        };
+
+        let client = &mut self.clients[index];
+        if client.is_control() {
+            for error in config_errors.iter() {
+                client.send(ServerMessage::ControlOutput(format!(
+                    "%config-error {error}\n"
+                )));
+            }
+        } else if config_errors.len() == 1 {
+            client.set_message(Some(first_error.clone()));
+        } else {
+            client.set_message(Some(format!(
+                "{first_error} (and {} more, see show-messages)",
+                config_errors.len() - 1
+            )));
+        }
+    }
+
+    /// Keeps the message for `show-messages`.
+    fn add_message(&mut self, message: String) {
+        if self.messages.len() == MESSAGE_LIMIT {
+            self.messages.remove(0);
+        }
+        self.messages.push(message);
+    }
+
+    async fn detach_client(&mut self, client: ClientId) {
+        self.drop_client_session(client, ServerMessage::Detach)
+            .await;
+    }
+
+    /// Leaves the client connected without a session, telling it why with `message`.
+    async fn drop_client_session(&mut self, client: ClientId, message: ServerMessage) {
+        let Some(index) = self.client_index(client) else {
+            return;
+        };
+
+        let session = self.clients[index].get_session();
+        self.clients[index].set_session(None);
+        self.clients[index].send(message);
+
+        if let Some(session) = session {
+            if let Some(attached_session) = self.get_session_mut(session) {
+                attached_session.detach(client);
+            }
+            self.resize_session(session);
+        }
     }
 
-    async fn handle_input(&mut self, input: u8) {
-        self.sessions[0].process_input(input).await;
+    /// Sizes the session for the clients attached to it according to its `window-size` option,
+    /// leaving out read-only ones.
+    ///
+    /// Sessions without such clients keep their previous size.
+    fn resize_session(&mut self, session: SessionId) {
+        let attached_clients = self
+            .clients
+            .iter()
+            .filter(|client| client.get_session() == Some(session) && !client.is_read_only())
+            .filter_map(|client| {
+                Some((
+                    self.compositor
+                        .get_window_dimensions(client.get_dimensions()?),
+                    client.get_last_activity(),
+                ))
+            });
+
+        let dimensions = match self.get_window_size(session) {
+            WindowSize::Smallest => attached_clients
+                .map(|(dimensions, _)| dimensions)
+                .reduce(UVec2::min),
+            WindowSize::Largest => attached_clients
+                .map(|(dimensions, _)| dimensions)
+                .reduce(UVec2::max),
+            WindowSize::Latest => attached_clients
+                .max_by_key(|(_, last_activity)| *last_activity)
+                .map(|(dimensions, _)| dimensions),
+        };
+
+        if let Some(dimensions) = dimensions
+            && let Some(session) = self.get_session_mut(session)
+        {
+            session.resize(dimensions);
+            self.redraw_pending = true;
+        }
     }
 
-    fn redraw(&mut self) {
-        self.renderer.begin_frame();
-        self.renderer.draw_window(self.sessions[0].get_window(0));
-        self.renderer.end_frame();
+    /// The session of the client typing becomes the one commands apply to by default.
+    async fn handle_client_input(&mut self, index: usize, input: &[u8]) {
+        let client = &mut self.clients[index];
+        let Some(session) = client.get_session() else {
+            return;
+        };
+
+        if client.get_message().is_some() {
+            client.set_message(None);
+            self.redraw_pending = true;
+        }
+
+        if !client.is_read_only() {
+            client.mark_active();
+            if self.get_window_size(session) == WindowSize::Latest {
+                self.resize_session(session);
+            }
+        }
+        self.active_session = Some(session);
+
+        let client = self.clients[index].get_id();
+        self.process_client_keys(client, input).await;
+    }
+
+    fn client_index(&self, client: ClientId) -> Option<usize> {
+        self.clients
+            .iter()
+            .position(|connection| connection.get_id() == client)
+    }
+
+    async fn redraw(&mut self) {
+        self.redraw_pending = false;
+        self.notify_subscribers().await;
+
+        let mouse_trackings: Vec<_> = self
+            .sessions
+            .iter()
+            .map(|session| (session.get_id(), self.get_mouse_tracking(session.get_id())))
+            .collect();
+
+        for client in self
+            .clients
+            .iter_mut()
+            .filter(|client| !client.is_control())
+        {
+            let (Some(session), Some(dimensions)) = (client.get_session(), client.get_dimensions())
+            else {
+                continue;
+            };
+
+            let Some(session) = self
+                .sessions
+                .iter()
+                .find(|candidate| candidate.get_id() == session)
+            else {
+                continue;
+            };
+            let Some(window) = session.get_window(session.get_active_window()) else {
+                continue;
+            };
+
+            let Some(pane) = window.get_pane(window.get_active_pane()) else {
+                continue;
+            };
+
+            let variables = formats::pane_variables(session, window, pane)
+                .with_flag("client_readonly", client.is_read_only());
+            let status_line = StatusLine::new(session, &self.options, &variables);
+            let title = Format::new(TITLE_FORMAT).expand(&variables);
+
+            let status_text = client.get_message().unwrap_or(status_line.get_text());
+            let frame = self.compositor.compose(window, dimensions, status_text);
+            // Clients too far behind get another frame once they may have caught up.
+            let frame_pending = client.send_frame(frame);
+            let title_pending = client.send_title(title);
+            let mouse_tracking_pending = mouse_trackings
+                .iter()
+                .find(|(mouse_session, _)| *mouse_session == session.get_id())
+                .is_some_and(|(_, tracking)| client.send_mouse_tracking(*tracking));
+            self.redraw_pending |= frame_pending || title_pending || mouse_tracking_pending;
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        env, fs, process,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use glam::UVec2;
+    use splix_event::PaneExitEvent;
+    use splix_id::{ClientId, SessionId};
+    use splix_protocol::ServerMessage;
+    use splix_terminal::{Command, TerminalConfig};
+    use tokio::sync::mpsc::{self, Receiver};
+
+    use crate::{ClientConnection, Splix};
+
+    const TEST_CLIENT_CHANNEL_CAPACITY: usize = 64;
+
+    static SERVER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+    /// A server without a config file, listening on a socket of its own that's unlinked right away.
+    pub(crate) fn test_server() -> Splix {
+        let socket_path = env::temp_dir().join(format!(
+            "splix-test-{}-{}",
+            process::id(),
+            SERVER_COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let splix = Splix::new(
+            TerminalConfig::new().with_default_shell("/bin/sh"),
+            &socket_path,
+            None,
+        )
+        .unwrap();
+        fs::remove_file(&socket_path).ok();
+
+        splix
+    }
+
+    /// A client attached to `session` with a terminal of `dimensions`, along with the messages it
+    /// gets.
+    pub(crate) fn attach_test_client(
+        splix: &mut Splix,
+        session: SessionId,
+        dimensions: UVec2,
+        read_only: bool,
+    ) -> (ClientId, Receiver<ServerMessage>) {
+        let (message_sender, message_receiver) = mpsc::channel(TEST_CLIENT_CHANNEL_CAPACITY);
+        let client = ClientId::new(splix.clients.len());
+        let mut client_connection = ClientConnection::new(client, message_sender);
+        client_connection.set_dimensions(dimensions);
+        client_connection.set_read_only(read_only);
+        client_connection.set_session(Some(session));
+        splix.clients.push(client_connection);
+        splix.get_session_mut(session).unwrap().attach(client);
+        splix.resize_session(session);
+
+        (client, message_receiver)
+    }
+
+    #[tokio::test]
+    async fn sizes_sessions_without_read_only_clients() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        attach_test_client(&mut splix, session, UVec2::new(80, 24), false);
+        let dimensions = splix.get_session(session).unwrap().get_dimensions();
+
+        let (read_only_client, _message_receiver) =
+            attach_test_client(&mut splix, session, UVec2::new(40, 10), true);
+        assert_eq!(
+            splix.get_session(session).unwrap().get_dimensions(),
+            dimensions
+        );
+
+        let index = splix.client_index(read_only_client).unwrap();
+        splix.clients[index].set_read_only(false);
+        splix.resize_session(session);
+        assert!(splix.get_session(session).unwrap().get_dimensions().x < dimensions.x);
+    }
+
+    #[tokio::test]
+    async fn ignores_the_exit_of_programs_replaced_by_respawning() {
+        let mut splix = test_server();
+        splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let pane = splix.get_active_pane().unwrap();
+
+        splix.respawn_pane(pane, None, false).unwrap();
+        splix.handle_pane_exit(&PaneExitEvent::new(pane, 0)).await;
+        assert!(!splix.get_pane(pane).unwrap().is_exited());
+
+        splix.handle_pane_exit(&PaneExitEvent::new(pane, 1)).await;
+        assert!(splix.get_pane(pane).unwrap().is_exited());
     }
 }
@@ -0,0 +1,251 @@
+use splix_id::SessionId;
+use splix_key::{MouseAction, MouseButton, MouseEvent, MouseTracking};
+use splix_layout::SplitDirection;
+
+use crate::{Splix, formats, options::MOUSE_OPTION, status_line::StatusLine};
+
+/// How many lines a turn of the mouse wheel scrolls in copy mode.
+const WHEEL_SCROLL_LINES: usize = 3;
+
+/// Mouse events from attached clients: clicks focus panes and select windows in the status
+/// line, dragging a separator resizes the panes next to it, and the wheel scrolls through the
+/// history in copy mode. Panes whose program asked for mouse reporting get the events instead,
+/// unless they are in copy mode.
+///
+/// Clients only report the mouse with the `mouse` option on.
+impl Splix {
+    /// Movement with no button held is only reported while a pane of the active window asked
+    /// for it, as it's a lot of events for nothing otherwise.
+    pub(crate) fn get_mouse_tracking(&self, session: SessionId) -> MouseTracking {
+        if self.get_session_option(session, MOUSE_OPTION).as_flag() != Some(true) {
+            return MouseTracking::None;
+        }
+
+        let any_event =
+            self.get_session(session)
+                .and_then(|session| session.get_window(session.get_active_window()))
+                .is_some_and(|window| {
+                    window.get_panes().iter().any(|pane| {
+                        pane.get_mouse_modes().get_tracking() == MouseTracking::AnyEvent
+                    })
+                });
+        if any_event {
+            MouseTracking::AnyEvent
+        } else {
+            MouseTracking::ButtonEvent
+        }
+    }
+
+    pub(crate) fn process_client_mouse(&mut self, index: usize, mouse: MouseEvent) {
+        let client = &self.clients[index];
+        let (Some(session), Some(dimensions)) = (client.get_session(), client.get_dimensions())
+        else {
+            return;
+        };
+        // The mouse may have been switched off after the client reported the event.
+        if client.is_read_only() || self.get_mouse_tracking(session) == MouseTracking::None {
+            return;
+        }
+
+        let position = mouse.get_position();
+        if let Some((pane, direction)) = client.get_border_drag() {
+            match mouse.get_action() {
+                MouseAction::Drag => {
+                    let position = match direction {
+                        SplitDirection::Horizontal => position.x,
+                        SplitDirection::Vertical => position.y,
+                    };
+                    if let Some(session) = self.get_session_mut(session)
+                        && let Some(window) = session.get_window_mut(pane.get_window())
+                        && window.move_border(pane, direction, position)
+                    {
+                        self.redraw_pending = true;
+                    }
+                    return;
+                }
+                action => {
+                    self.clients[index].set_border_drag(None);
+                    if action == MouseAction::Release {
+                        return;
+                    }
+                }
+            }
+        }
+
+        let left_press =
+            mouse.get_action() == MouseAction::Press && mouse.get_button() == MouseButton::Left;
+        if self.compositor.get_status_line_row(dimensions) == Some(position.y) {
+            if left_press {
+                self.select_clicked_window(index, position.x);
+            }
+            return;
+        }
+
+        let Some(session) = self.get_session_mut(session) else {
+            return;
+        };
+        let Some(window) = session.get_window_mut(session.get_active_window()) else {
+            return;
+        };
+
+        if let Some((pane, geometry)) = window.find_pane_at(position) {
+            let Some(pane_mut) = window.get_pane_mut(pane) else {
+                return;
+            };
+            let reported = pane_mut
+                .get_copy_mode()
+                .is_none()
+                .then(|| {
+                    mouse
+                        .with_position(position - geometry.get_position())
+                        .encode(pane_mut.get_mouse_modes())
+                })
+                .flatten();
+
+            match reported {
+                Some(bytes) => {
+                    pane_mut.process_input(&bytes);
+                    if mouse.get_action() == MouseAction::Press {
+                        window.select_pane(pane);
+                    }
+                }
+                None => match (mouse.get_action(), mouse.get_button()) {
+                    (MouseAction::Press, MouseButton::Left) => {
+                        window.select_pane(pane);
+                    }
+                    (MouseAction::Press, MouseButton::WheelUp) => {
+                        pane_mut.enter_copy_mode(true);
+                        for _ in 0..WHEEL_SCROLL_LINES {
+                            pane_mut.run_copy_mode_command("scroll-up").ok();
+                        }
+                    }
+                    (MouseAction::Press, MouseButton::WheelDown) => {
+                        for _ in 0..WHEEL_SCROLL_LINES {
+                            if pane_mut.run_copy_mode_command("scroll-down").is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    _ => return,
+                },
+            }
+        } else if let Some(border) = window.find_border_at(position)
+            && left_press
+        {
+            self.clients[index].set_border_drag(Some(border));
+        } else {
+            return;
+        }
+
+        self.redraw_pending = true;
+    }
+
+    /// Selects the window listed at `column` of the client's status line.
+    fn select_clicked_window(&mut self, index: usize, column: u32) {
+        let client = &self.clients[index];
+        let Some(session) = client
+            .get_session()
+            .and_then(|session| self.get_session(session))
+        else {
+            return;
+        };
+        let Some(window) = session.get_window(session.get_active_window()) else {
+            return;
+        };
+        let Some(pane) = window.get_pane(window.get_active_pane()) else {
+            return;
+        };
+
+        let variables = formats::pane_variables(session, window, pane)
+            .with_flag("client_readonly", client.is_read_only());
+        let Some(clicked) = StatusLine::new(session, &self.options, &variables).find_window(column)
+        else {
+            return;
+        };
+
+        let session = session.get_id();
+        if let Some(session) = self.get_session_mut(session)
+            && session.select_window(clicked)
+        {
+            self.redraw_pending = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_event::GridUpdate;
+    use splix_key::{Modifiers, MouseAction, MouseButton, MouseEvent, MouseTracking};
+    use splix_terminal::Command;
+
+    use crate::{
+        options::MOUSE_OPTION,
+        tests::{attach_test_client, test_server},
+    };
+
+    #[tokio::test]
+    async fn tracks_movement_only_for_panes_asking_for_it() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        assert_eq!(splix.get_mouse_tracking(session), MouseTracking::None);
+
+        splix
+            .set_option(None, None, MOUSE_OPTION, Some("on"))
+            .unwrap();
+        assert_eq!(
+            splix.get_mouse_tracking(session),
+            MouseTracking::ButtonEvent
+        );
+
+        let pane = splix.resolve_pane(None).unwrap();
+        for ch in "\x1b[?1003h".chars() {
+            splix
+                .get_pane_mut(pane)
+                .unwrap()
+                .update(&GridUpdate::AppendChar(ch));
+        }
+        assert_eq!(splix.get_mouse_tracking(session), MouseTracking::AnyEvent);
+    }
+
+    #[tokio::test]
+    async fn ignores_the_mouse_of_read_only_clients() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let left = splix.resolve_pane(None).unwrap();
+        splix
+            .run_command(&[
+                String::from("split-window"),
+                String::from("-h"),
+                String::from("cat"),
+            ])
+            .await
+            .unwrap();
+        let right = splix.resolve_pane(None).unwrap();
+        splix
+            .set_option(None, None, MOUSE_OPTION, Some("on"))
+            .unwrap();
+        let (read_only_client, _read_only_receiver) =
+            attach_test_client(&mut splix, session, UVec2::new(80, 24), true);
+        let (client, _receiver) =
+            attach_test_client(&mut splix, session, UVec2::new(80, 24), false);
+        let click = MouseEvent::new(
+            MouseAction::Press,
+            MouseButton::Left,
+            UVec2::new(1, 1),
+            Modifiers::NONE,
+        );
+
+        let read_only_index = splix.client_index(read_only_client).unwrap();
+        splix.process_client_mouse(read_only_index, click);
+        assert_eq!(splix.resolve_pane(None).unwrap(), right);
+
+        let index = splix.client_index(client).unwrap();
+        splix.process_client_mouse(index, click);
+        assert_eq!(splix.resolve_pane(None).unwrap(), left);
+    }
+}
@@ -0,0 +1,478 @@
+use splix_command::{CommandLine, SplixCommand};
+use splix_id::{PaneId, SessionId, WindowId};
+use splix_options::{OptionDefinition, OptionScope, OptionValue, Options};
+use splix_terminal::TerminalConfig;
+
+use crate::{ConfigFile, Splix, WindowSize};
+
+pub(crate) const DEFAULT_SHELL_OPTION: &str = "default-shell";
+pub(crate) const DEFAULT_TERMINAL_OPTION: &str = "default-terminal";
+pub(crate) const EVENT_CHANNEL_CAPACITY_OPTION: &str = "event-channel-capacity";
+pub(crate) const LOGIN_SHELL_OPTION: &str = "login-shell";
+pub(crate) const MODE_KEYS_OPTION: &str = "mode-keys";
+pub(crate) const MOUSE_OPTION: &str = "mouse";
+pub(crate) const PREFIX_OPTION: &str = "prefix";
+pub(crate) const PREFIX2_OPTION: &str = "prefix2";
+pub(crate) const REMAIN_ON_EXIT_OPTION: &str = "remain-on-exit";
+pub(crate) const REPEAT_TIME_OPTION: &str = "repeat-time";
+/// Formats expanded with the variables of the active pane around the windows of the status line.
+pub(crate) const STATUS_LEFT_OPTION: &str = "status-left";
+pub(crate) const STATUS_RIGHT_OPTION: &str = "status-right";
+pub(crate) const WINDOW_SIZE_OPTION: &str = "window-size";
+
+/// Where an option is set: for the whole server, or for a session, window or pane.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OptionLevel {
+    Server,
+    Session(SessionId),
+    Window(WindowId),
+    Pane(PaneId),
+}
+
+impl OptionLevel {
+    fn get_scope(&self) -> OptionScope {
+        match self {
+            Self::Server => OptionScope::Server,
+            Self::Session(_) => OptionScope::Session,
+            Self::Window(_) => OptionScope::Window,
+            Self::Pane(_) => OptionScope::Pane,
+        }
+    }
+
+    /// The level options not set at this one are inherited from.
+    fn get_parent(&self) -> Option<Self> {
+        match self {
+            Self::Server => None,
+            Self::Session(_) => Some(Self::Server),
+            Self::Window(window) => Some(Self::Session(window.get_session())),
+            Self::Pane(pane) => Some(Self::Window(pane.get_window())),
+        }
+    }
+}
+
+/// Options set with `set-option` and listed with `show-options`. Each option is looked up from
+/// the pane, window or session it applies to up to the server, and takes its default when
+/// nothing sets it.
+impl Splix {
+    pub(crate) fn get_server_option(&self, name: &str) -> OptionValue {
+        self.get_option(OptionLevel::Server, name)
+    }
+
+    pub(crate) fn get_session_option(&self, session: SessionId, name: &str) -> OptionValue {
+        self.get_option(OptionLevel::Session(session), name)
+    }
+
+    pub(crate) fn get_window_option(&self, window: WindowId, name: &str) -> OptionValue {
+        self.get_option(OptionLevel::Window(window), name)
+    }
+
+    pub(crate) fn get_pane_option(&self, pane: PaneId, name: &str) -> OptionValue {
+        self.get_option(OptionLevel::Pane(pane), name)
+    }
+
+    /// The server options `config_file` sets with `set-option`, skipping its other commands and
+    /// leaving mistakes for when it's run.
+    pub(crate) fn read_server_options(config_file: &ConfigFile) -> Options {
+        let mut options = Options::new();
+        for (_, line) in config_file.get_lines() {
+            let Ok(command_line) = line.parse::<CommandLine>() else {
+                continue;
+            };
+
+            if let Ok(SplixCommand::SetOption {
+                window: false,
+                pane: false,
+                unset: false,
+                target: None,
+                option,
+                value: Some(value),
+                ..
+            }) = SplixCommand::parse(command_line.get_arguments())
+                && let Ok(definition) = OptionDefinition::find(&option)
+                && definition.get_scope() == OptionScope::Server
+                && let Ok(value) = definition.parse(&value)
+            {
+                options.set(definition, value);
+            }
+        }
+
+        options
+    }
+
+    /// The configuration new panes start with, running the shell of the `default-shell` option,
+    /// as a login shell with `login-shell`, with the `TERM` of the `default-terminal` option.
+    pub(crate) fn get_terminal_config(&self) -> TerminalConfig {
+        let mut terminal_config = self.terminal_config.clone();
+
+        let default_shell = self.get_server_option(DEFAULT_SHELL_OPTION);
+        if let Some(default_shell) = default_shell.as_str()
+            && !default_shell.is_empty()
+        {
+            terminal_config = terminal_config.with_default_shell(default_shell);
+        }
+        if let Some(login_shell) = self.get_server_option(LOGIN_SHELL_OPTION).as_flag() {
+            terminal_config = terminal_config.with_login_shell(login_shell);
+        }
+
+        let default_terminal = self.get_server_option(DEFAULT_TERMINAL_OPTION);
+        if let Some(default_terminal) = default_terminal.as_str()
+            && !default_terminal.is_empty()
+        {
+            terminal_config = terminal_config.with_term(default_terminal);
+        }
+
+        terminal_config
+    }
+
+    /// How the session is sized for the clients attached to it.
+    pub(crate) fn get_window_size(&self, session: SessionId) -> WindowSize {
+        self.get_session_option(session, WINDOW_SIZE_OPTION)
+            .as_str()
+            .and_then(|window_size| window_size.parse().ok())
+            .unwrap_or_default()
+    }
+
+    /// Sets the option for the level `scope` picks, the one the option applies to by default, or
+    /// unsets it there without a value.
+    pub(crate) fn set_option(
+        &mut self,
+        scope: Option<OptionScope>,
+        target: Option<&str>,
+        option: &str,
+        value: Option<&str>,
+    ) -> splix_error::Result<()> {
+        let definition = OptionDefinition::find(option)?;
+        let level = self.resolve_option_level(definition, scope, target)?;
+        let value = value.map(|value| definition.parse(value)).transpose()?;
+
+        let options = self.get_level_options_mut(level)?;
+        match value {
+            Some(value) => options.set(definition, value),
+            None => {
+                options.unset(option);
+            }
+        }
+
+        if [
+            DEFAULT_SHELL_OPTION,
+            DEFAULT_TERMINAL_OPTION,
+            LOGIN_SHELL_OPTION,
+        ]
+        .contains(&option)
+        {
+            let terminal_config = self.get_terminal_config();
+            for session in &mut self.sessions {
+                session.set_terminal_config(terminal_config.clone());
+            }
+        }
+
+        if option == WINDOW_SIZE_OPTION {
+            let sessions: Vec<SessionId> = self
+                .sessions
+                .iter()
+                .map(|session| session.get_id())
+                .collect();
+            for session in sessions {
+                self.resize_session(session);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Lists the options set at a level as the name and value of each, quoted like in commands.
+    ///
+    /// The server lists every option, including the ones nothing sets. With `inherited`, other
+    /// levels also list what they inherit, marked with a `*` after the name.
+    pub(crate) fn show_options(
+        &self,
+        scope: Option<OptionScope>,
+        target: Option<&str>,
+        option: Option<&str>,
+        inherited: bool,
+        values_only: bool,
+    ) -> splix_error::Result<String> {
+        let (level, definitions) = match option {
+            Some(option) => {
+                let definition = OptionDefinition::find(option)?;
+                let level = self.resolve_option_level(definition, scope, target)?;
+                (level, vec![definition])
+            }
+            None => {
+                let scope =
+                    scope.unwrap_or_else(|| self.default_scope(OptionScope::Session, target));
+                let level = self.resolve_level(scope, target)?;
+                let definitions = OptionDefinition::get_all()
+                    .iter()
+                    .filter(|definition| definition.get_scope() >= level.get_scope())
+                    .collect();
+                (level, definitions)
+            }
+        };
+        let options = self.get_level_options(level)?;
+
+        let mut output = String::new();
+        for definition in definitions {
+            let name = definition.get_name();
+            let (value, name) = match options.get(name) {
+                Some(value) => (value.clone(), name.to_string()),
+                None if level == OptionLevel::Server => {
+                    (definition.get_default(), name.to_string())
+                }
+                None if inherited => (
+                    definition.resolve(self.get_inherited_options(level)),
+                    format!("{name}*"),
+                ),
+                None => continue,
+            };
+
+            if values_only {
+                output += &format!("{value}\n");
+            } else {
+                output += &format!("{}\n", CommandLine::new(vec![name, value.to_string()]));
+            }
+        }
+
+        Ok(output)
+    }
+
+    fn get_option(&self, level: OptionLevel, name: &str) -> OptionValue {
+        OptionDefinition::find(name)
+            .expect("options looked up by the server are defined")
+            .resolve(self.get_inherited_options(level))
+    }
+
+    /// Options can't be set for anything more specific than what they apply to, like a window
+    /// option for a single pane.
+    fn resolve_option_level(
+        &self,
+        definition: &OptionDefinition,
+        scope: Option<OptionScope>,
+        target: Option<&str>,
+    ) -> splix_error::Result<OptionLevel> {
+        let scope = scope.unwrap_or_else(|| self.default_scope(definition.get_scope(), target));
+        if scope > definition.get_scope() {
+            return Err(splix_error::Error::InvalidOptionScope {
+                option: definition.get_name().to_string(),
+                scope: scope.to_string(),
+            });
+        }
+
+        self.resolve_level(scope, target)
+    }
+
+    /// Without a session to apply to, like while the config file runs at startup, options without
+    /// a level or target apply to the whole server.
+    fn default_scope(&self, scope: OptionScope, target: Option<&str>) -> OptionScope {
+        if target.is_none() && self.sessions.is_empty() {
+            OptionScope::Server
+        } else {
+            scope
+        }
+    }
+
+    fn resolve_level(
+        &self,
+        scope: OptionScope,
+        target: Option<&str>,
+    ) -> splix_error::Result<OptionLevel> {
+        Ok(match scope {
+            OptionScope::Server => OptionLevel::Server,
+            OptionScope::Session => OptionLevel::Session(self.resolve_session(target)?),
+            OptionScope::Window => OptionLevel::Window(self.resolve_window(target)?),
+            OptionScope::Pane => OptionLevel::Pane(self.resolve_pane(target)?),
+        })
+    }
+
+    /// The options of `level` and of each level above it, from the most specific one up.
+    fn get_inherited_options(&self, level: OptionLevel) -> Vec<&Options> {
+        let mut options = Vec::new();
+        let mut level = Some(level);
+        while let Some(current) = level {
+            options.extend(self.get_level_options(current).ok());
+            level = current.get_parent();
+        }
+
+        options
+    }
+
+    fn get_level_options(&self, level: OptionLevel) -> splix_error::Result<&Options> {
+        match level {
+            OptionLevel::Server => Ok(&self.options),
+            OptionLevel::Session(session) => self
+                .get_session(session)
+                .map(|session| session.get_options())
+                .ok_or_else(|| splix_error::Error::SessionNotFound(session.to_string())),
+            OptionLevel::Window(window) => self
+                .get_session(window.get_session())
+                .and_then(|session| session.get_window(window))
+                .map(|window| window.get_options())
+                .ok_or_else(|| splix_error::Error::WindowNotFound(window.to_string())),
+            OptionLevel::Pane(pane) => self
+                .get_pane(pane)
+                .map(|pane| pane.get_options())
+                .ok_or_else(|| splix_error::Error::PaneNotFound(pane.to_string())),
+        }
+    }
+
+    fn get_level_options_mut(&mut self, level: OptionLevel) -> splix_error::Result<&mut Options> {
+        match level {
+            OptionLevel::Server => Ok(&mut self.options),
+            OptionLevel::Session(session) => self
+                .get_session_mut(session)
+                .map(|session| session.get_options_mut())
+                .ok_or_else(|| splix_error::Error::SessionNotFound(session.to_string())),
+            OptionLevel::Window(window) => self
+                .get_session_mut(window.get_session())
+                .and_then(|session| session.get_window_mut(window))
+                .map(|window| window.get_options_mut())
+                .ok_or_else(|| splix_error::Error::WindowNotFound(window.to_string())),
+            OptionLevel::Pane(pane) => self
+                .get_pane_mut(pane)
+                .map(|pane| pane.get_options_mut())
+                .ok_or_else(|| splix_error::Error::PaneNotFound(pane.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_event::PaneExitEvent;
+    use splix_options::OptionScope;
+    use splix_terminal::Command;
+
+    use super::{
+        DEFAULT_TERMINAL_OPTION, LOGIN_SHELL_OPTION, MODE_KEYS_OPTION, PREFIX_OPTION,
+        REMAIN_ON_EXIT_OPTION, WINDOW_SIZE_OPTION,
+    };
+    use crate::tests::{attach_test_client, test_server};
+
+    #[tokio::test]
+    async fn sets_options_for_the_server_without_sessions() {
+        let mut splix = test_server();
+        splix
+            .set_option(None, None, MODE_KEYS_OPTION, Some("vi"))
+            .unwrap();
+        assert_eq!(splix.get_server_option(MODE_KEYS_OPTION).to_string(), "vi");
+    }
+
+    #[tokio::test]
+    async fn sets_options_no_more_specific_than_their_scope() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+
+        assert!(matches!(
+            splix.set_option(Some(OptionScope::Pane), None, MODE_KEYS_OPTION, Some("vi")),
+            Err(splix_error::Error::InvalidOptionScope { .. })
+        ));
+        assert!(matches!(
+            splix.set_option(Some(OptionScope::Window), None, PREFIX_OPTION, Some("C-a")),
+            Err(splix_error::Error::InvalidOptionScope { .. })
+        ));
+
+        splix
+            .set_option(None, None, PREFIX_OPTION, Some("C-a"))
+            .unwrap();
+        assert_eq!(
+            splix.get_session_option(session, PREFIX_OPTION).to_string(),
+            "C-a"
+        );
+        assert_eq!(splix.get_server_option(PREFIX_OPTION).to_string(), "C-b");
+    }
+
+    #[tokio::test]
+    async fn shows_inherited_options_only_when_asked() {
+        let mut splix = test_server();
+        splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        splix
+            .set_option(
+                Some(OptionScope::Server),
+                None,
+                MODE_KEYS_OPTION,
+                Some("vi"),
+            )
+            .unwrap();
+
+        let shown = splix.show_options(None, None, None, false, false).unwrap();
+        assert_eq!(shown, "");
+        let shown = splix.show_options(None, None, None, true, false).unwrap();
+        assert!(shown.lines().any(|line| line == "mode-keys* vi"));
+    }
+
+    #[tokio::test]
+    async fn removes_exited_panes_with_remain_on_exit_off() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        let pane = splix.resolve_pane(None).unwrap();
+
+        splix.handle_pane_exit(&PaneExitEvent::new(pane, 0)).await;
+        assert!(splix.get_pane(pane).unwrap().is_exited());
+
+        splix
+            .set_option(None, None, REMAIN_ON_EXIT_OPTION, Some("off"))
+            .unwrap();
+        splix.handle_pane_exit(&PaneExitEvent::new(pane, 0)).await;
+        assert!(splix.get_session(session).is_none());
+    }
+
+    #[tokio::test]
+    async fn sizes_sessions_by_their_window_size_option() {
+        let mut splix = test_server();
+        let session = splix
+            .new_session(None, Command::new(vec![String::from("cat")]))
+            .unwrap();
+        attach_test_client(&mut splix, session, UVec2::new(80, 25), false);
+        attach_test_client(&mut splix, session, UVec2::new(120, 41), false);
+        assert_eq!(
+            splix.get_session(session).unwrap().get_dimensions(),
+            UVec2::new(80, 24)
+        );
+
+        splix
+            .set_option(None, None, WINDOW_SIZE_OPTION, Some("largest"))
+            .unwrap();
+        assert_eq!(
+            splix.get_session(session).unwrap().get_dimensions(),
+            UVec2::new(120, 40)
+        );
+        assert!(
+            splix
+                .set_option(None, None, WINDOW_SIZE_OPTION, Some("biggest"))
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn starts_panes_with_the_default_terminal() {
+        let mut splix = test_server();
+        let term = splix.get_terminal_config().get_term().to_string();
+
+        splix
+            .set_option(None, None, DEFAULT_TERMINAL_OPTION, Some("screen-256color"))
+            .unwrap();
+        assert_eq!(splix.get_terminal_config().get_term(), "screen-256color");
+
+        splix
+            .set_option(None, None, DEFAULT_TERMINAL_OPTION, None)
+            .unwrap();
+        assert_eq!(splix.get_terminal_config().get_term(), term);
+    }
+
+    #[tokio::test]
+    async fn starts_login_shells_with_login_shell_on() {
+        let mut splix = test_server();
+        assert!(!splix.get_terminal_config().is_login_shell());
+
+        splix
+            .set_option(None, None, LOGIN_SHELL_OPTION, Some("on"))
+            .unwrap();
+        assert!(splix.get_terminal_config().is_login_shell());
+    }
+}
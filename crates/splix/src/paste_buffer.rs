@@ -0,0 +1,30 @@
+/// Text kept by the server to be pasted into panes.
+pub struct PasteBuffer {
+    name: String,
+    data: String,
+    automatic: bool,
+}
+
+impl PasteBuffer {
+    pub fn new(name: String, data: String, automatic: bool) -> Self {
+        Self {
+            name,
+            data,
+            automatic,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_data(&self) -> &str {
+        &self.data
+    }
+
+    /// Whether the buffer was named by the server, which lets it delete the buffer to make room
+    /// for newer ones.
+    pub fn is_automatic(&self) -> bool {
+        self.automatic
+    }
+}
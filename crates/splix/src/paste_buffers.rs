@@ -0,0 +1,66 @@
+use crate::paste_buffer::PasteBuffer;
+
+/// The paste buffers of the server, most recent first.
+#[derive(Default)]
+pub struct PasteBuffers {
+    buffers: Vec<PasteBuffer>,
+    next_buffer_id: usize,
+}
+
+/// How many automatically named buffers are kept before the oldest ones are deleted.
+const AUTOMATIC_BUFFER_LIMIT: usize = 50;
+
+impl PasteBuffers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_buffers(&self) -> &[PasteBuffer] {
+        &self.buffers
+    }
+
+    /// Buffers without a name are named by the server. Returns the name of the buffer.
+    pub fn add(&mut self, name: Option<String>, data: String) -> String {
+        let automatic = name.is_none();
+        let name = name.unwrap_or_else(|| {
+            let name = format!("buffer{:04}", self.next_buffer_id);
+            self.next_buffer_id += 1;
+            name
+        });
+
+        self.buffers.retain(|buffer| buffer.get_name() != name);
+        self.buffers
+            .insert(0, PasteBuffer::new(name.clone(), data, automatic));
+
+        let mut automatic_buffers = 0;
+        self.buffers.retain(|buffer| {
+            automatic_buffers += buffer.is_automatic() as usize;
+            !buffer.is_automatic() || automatic_buffers <= AUTOMATIC_BUFFER_LIMIT
+        });
+
+        name
+    }
+
+    /// Without a name the most recent buffer is returned.
+    pub fn get(&self, name: Option<&str>) -> splix_error::Result<&PasteBuffer> {
+        let Some(name) = name else {
+            return self.buffers.first().ok_or(splix_error::Error::NoBuffers);
+        };
+
+        self.buffers
+            .iter()
+            .find(|buffer| buffer.get_name() == name)
+            .ok_or_else(|| splix_error::Error::BufferNotFound(name.to_string()))
+    }
+
+    pub fn remove(&mut self, name: Option<&str>) -> splix_error::Result<PasteBuffer> {
+        let name = self.get(name)?.get_name().to_string();
+        let index = self
+            .buffers
+            .iter()
+            .position(|buffer| buffer.get_name() == name)
+            .expect("the buffer was just found");
+
+        Ok(self.buffers.remove(index))
+    }
+}
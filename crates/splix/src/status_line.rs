@@ -0,0 +1,64 @@
+use std::ops::Range;
+
+use splix_format::{Format, FormatVariables};
+use splix_id::WindowId;
+use splix_options::{OptionDefinition, Options};
+use splix_session::Session;
+
+use crate::{
+    formats,
+    options::{STATUS_LEFT_OPTION, STATUS_RIGHT_OPTION},
+};
+
+/// The status line at the bottom of each client, listing the windows of its session between what
+/// the `status-left` and `status-right` options expand to, and remembering where each window is
+/// listed so a click can select it.
+pub struct StatusLine {
+    text: String,
+    windows: Vec<(Range<usize>, WindowId)>,
+}
+
+/// What each window is listed as, separated by spaces.
+const WINDOW_STATUS_FORMAT: &str = "#{window_index}:#{window_name}#{window_flags}";
+
+impl StatusLine {
+    /// `server_options` are the options the session inherits.
+    pub fn new(session: &Session, server_options: &Options, variables: &FormatVariables) -> Self {
+        let expand_option = |option| {
+            let format = OptionDefinition::find(option)
+                .expect("the status line options are defined")
+                .resolve([session.get_options(), server_options]);
+            Format::new(format.to_string()).expand(variables)
+        };
+
+        let mut text = expand_option(STATUS_LEFT_OPTION);
+        let mut windows = Vec::with_capacity(session.get_windows().len());
+
+        for (index, window) in session.get_windows().iter().enumerate() {
+            if index > 0 {
+                text.push(' ');
+            }
+
+            let start = text.chars().count();
+            text += &Format::new(WINDOW_STATUS_FORMAT)
+                .expand(&formats::window_variables(session, window));
+            windows.push((start..text.chars().count(), window.get_id()));
+        }
+
+        text += &expand_option(STATUS_RIGHT_OPTION);
+
+        Self { text, windows }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// The window listed at `column`.
+    pub fn find_window(&self, column: u32) -> Option<WindowId> {
+        self.windows
+            .iter()
+            .find(|(range, _)| range.contains(&(column as usize)))
+            .map(|(_, window)| *window)
+    }
+}
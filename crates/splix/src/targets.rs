@@ -0,0 +1,386 @@
+use splix_id::{
+    PaneDirection, PaneEdge, PaneId, PaneTarget, SessionId, SessionTarget, Target, WindowId,
+    WindowTarget,
+};
+use splix_layout::Geometry;
+use splix_session::Session;
+use splix_window::Window;
+
+use crate::Splix;
+
+/// Resolves the `-t` arguments of commands, with the active session, its active window and that
+/// window's active pane standing in for whatever is omitted.
+///
+/// See [`Target`] for the syntax. Names may be shortened to any start that matches only one
+/// session or window.
+impl Splix {
+    pub(crate) fn resolve_session(&self, target: Option<&str>) -> splix_error::Result<SessionId> {
+        let Some(target) = target.filter(|target| !target.is_empty()) else {
+            return self.current_session().ok_or(splix_error::Error::NoSessions);
+        };
+
+        let parsed = Target::parse_session(target)?;
+        let session = match (parsed.get_pane(), parsed.get_window()) {
+            (Some(pane), _) => self
+                .find_target_pane(pane)
+                .map(|pane| pane.get_window().get_session()),
+            (None, Some(window)) => self
+                .find_target_window(window)
+                .map(|window| window.get_session()),
+            (None, None) => match parsed.get_session() {
+                Some(session) => self.find_session(session),
+                None => self.current_session(),
+            },
+        };
+
+        session.ok_or_else(|| splix_error::Error::SessionNotFound(target.to_string()))
+    }
+
+    pub(crate) fn resolve_window(&self, target: Option<&str>) -> splix_error::Result<WindowId> {
+        let Some(target) = target.filter(|target| !target.is_empty()) else {
+            return self.resolve_target_window(&Target::default(), "");
+        };
+
+        let parsed = Target::parse_window(target)?;
+        if let Some(pane) = parsed.get_pane() {
+            return self
+                .find_target_pane(pane)
+                .map(|pane| pane.get_window())
+                .ok_or_else(|| splix_error::Error::WindowNotFound(target.to_string()));
+        }
+
+        self.resolve_target_window(&parsed, target)
+    }
+
+    pub(crate) fn resolve_pane(&self, target: Option<&str>) -> splix_error::Result<PaneId> {
+        let target = target.unwrap_or_default();
+        let parsed = Target::parse_pane(target)?;
+        let not_found = || splix_error::Error::PaneNotFound(target.to_string());
+
+        if let Some(pane @ PaneTarget::Id(_)) = parsed.get_pane() {
+            return self.find_target_pane(pane).ok_or_else(not_found);
+        }
+
+        let window = self.resolve_target_window(&parsed, target)?;
+        let window = self
+            .get_session(window.get_session())
+            .and_then(|session| session.get_window(window))
+            .ok_or_else(|| splix_error::Error::WindowNotFound(window.to_string()))?;
+
+        match parsed.get_pane() {
+            Some(pane) => Self::find_pane(window, pane).ok_or_else(not_found),
+            None => Ok(window.get_active_pane()),
+        }
+    }
+
+    /// The session of the client running the current command, or else the one last used.
+    fn current_session(&self) -> Option<SessionId> {
+        self.command_client
+            .and_then(|client| self.client_index(client))
+            .and_then(|index| self.clients[index].get_session())
+            .or(self.active_session)
+    }
+
+    /// Without a session part, a window that can't be found is looked up as a session instead,
+    /// standing for that session's active window.
+    fn resolve_target_window(&self, target: &Target, text: &str) -> splix_error::Result<WindowId> {
+        let not_found = || splix_error::Error::WindowNotFound(text.to_string());
+
+        if let Some(window @ WindowTarget::Id(_)) = target.get_window() {
+            return self.find_target_window(window).ok_or_else(not_found);
+        }
+
+        let session = match target.get_session() {
+            Some(session) => self
+                .find_session(session)
+                .ok_or_else(|| splix_error::Error::SessionNotFound(text.to_string()))?,
+            None => self
+                .current_session()
+                .ok_or(splix_error::Error::NoSessions)?,
+        };
+        let session = self.get_session(session).ok_or_else(not_found)?;
+
+        let Some(window) = target.get_window() else {
+            return Ok(session.get_active_window());
+        };
+
+        if let Some(window) = Self::find_window(session, window) {
+            return Ok(window);
+        }
+
+        let name = match window {
+            WindowTarget::Name(name) => name.clone(),
+            WindowTarget::Index(index) => index.to_string(),
+            _ => return Err(not_found()),
+        };
+        if target.get_session().is_none()
+            && let Some(session) = self.find_session(&SessionTarget::Name(name))
+            && let Some(session) = self.get_session(session)
+        {
+            return Ok(session.get_active_window());
+        }
+
+        Err(not_found())
+    }
+
+    fn find_session(&self, target: &SessionTarget) -> Option<SessionId> {
+        match target {
+            SessionTarget::Id(id) => self
+                .sessions
+                .iter()
+                .map(Session::get_id)
+                .find(|session| session.get() == *id),
+            SessionTarget::Name(name) => {
+                find_by_name(&self.sessions, name, Session::get_name).map(Session::get_id)
+            }
+        }
+    }
+
+    /// Looks for the window in every session, which only makes sense for IDs.
+    fn find_target_window(&self, target: &WindowTarget) -> Option<WindowId> {
+        self.sessions
+            .iter()
+            .find_map(|session| Self::find_window(session, target))
+    }
+
+    /// Looks for the pane in every window, which only makes sense for IDs.
+    fn find_target_pane(&self, target: PaneTarget) -> Option<PaneId> {
+        self.sessions
+            .iter()
+            .flat_map(Session::get_windows)
+            .find_map(|window| Self::find_pane(window, target))
+    }
+
+    /// Relative targets count from the active window and wrap around.
+    fn find_window(session: &Session, target: &WindowTarget) -> Option<WindowId> {
+        let windows = session.get_windows();
+        let active = windows
+            .iter()
+            .position(|window| window.get_id() == session.get_active_window())
+            .unwrap_or_default();
+
+        let window = match target {
+            WindowTarget::Id(id) => windows.iter().find(|window| window.get_id().get() == *id),
+            WindowTarget::Index(index) => {
+                windows.iter().find(|window| window.get_index() == *index)
+            }
+            WindowTarget::Name(name) => find_by_name(windows, name, Window::get_name),
+            WindowTarget::Start => windows.iter().min_by_key(|window| window.get_index()),
+            WindowTarget::End => windows.iter().max_by_key(|window| window.get_index()),
+            WindowTarget::Last => return session.get_last_window(),
+            WindowTarget::Next(count) => {
+                let count = count % windows.len().max(1);
+                windows.get((active + count) % windows.len().max(1))
+            }
+            WindowTarget::Previous(count) => {
+                let count = count % windows.len().max(1);
+                windows.get((active + windows.len() - count) % windows.len().max(1))
+            }
+        };
+
+        window.map(Window::get_id)
+    }
+
+    /// Indices and relative targets follow the order panes are laid out in, and edges and
+    /// neighbors their geometries.
+    fn find_pane(window: &Window, target: PaneTarget) -> Option<PaneId> {
+        let geometries = window.get_pane_geometries();
+        let panes: Vec<PaneId> = geometries.iter().map(|(pane, _)| *pane).collect();
+        let active = panes
+            .iter()
+            .position(|pane| *pane == window.get_active_pane())
+            .unwrap_or_default();
+
+        match target {
+            PaneTarget::Id(id) => panes.into_iter().find(|pane| pane.get() == id),
+            PaneTarget::Index(index) => panes.get(index).copied(),
+            PaneTarget::Last => window.get_last_pane(),
+            PaneTarget::Next(count) => {
+                let count = count % panes.len().max(1);
+                panes.get((active + count) % panes.len().max(1)).copied()
+            }
+            PaneTarget::Previous(count) => {
+                let count = count % panes.len().max(1);
+                panes
+                    .get((active + panes.len() - count) % panes.len().max(1))
+                    .copied()
+            }
+            PaneTarget::Edge(edge) => find_edge_pane(&geometries, edge),
+            PaneTarget::Neighbor(direction) => {
+                let (_, active) = geometries.get(active)?;
+                geometries
+                    .iter()
+                    .find(|(_, geometry)| is_neighbor(active, geometry, direction))
+                    .map(|(pane, _)| *pane)
+            }
+        }
+    }
+}
+
+/// An exact name wins over a name that merely starts with `name`, which has to be unique.
+fn find_by_name<'a, T>(items: &'a [T], name: &str, get_name: fn(&T) -> &str) -> Option<&'a T> {
+    if let Some(item) = items.iter().find(|item| get_name(item) == name) {
+        return Some(item);
+    }
+
+    let mut matches = items.iter().filter(|item| get_name(item).starts_with(name));
+    match (matches.next(), matches.next()) {
+        (Some(item), None) => Some(item),
+        _ => None,
+    }
+}
+
+fn find_edge_pane(geometries: &[(PaneId, Geometry)], edge: PaneEdge) -> Option<PaneId> {
+    // Distances from the window's edges, to be minimized in order.
+    let key = |geometry: &Geometry| {
+        let position = geometry.get_position().as_ivec2();
+        let end = (geometry.get_position() + geometry.get_dimensions()).as_ivec2();
+        match edge {
+            PaneEdge::Top => (position.y, position.x),
+            PaneEdge::Bottom => (-end.y, position.x),
+            PaneEdge::Left => (position.x, position.y),
+            PaneEdge::Right => (-end.x, position.y),
+            PaneEdge::TopLeft => (position.y, position.x),
+            PaneEdge::TopRight => (position.y, -end.x),
+            PaneEdge::BottomLeft => (-end.y, position.x),
+            PaneEdge::BottomRight => (-end.y, -end.x),
+        }
+    };
+
+    geometries
+        .iter()
+        .min_by_key(|(_, geometry)| key(geometry))
+        .map(|(pane, _)| *pane)
+}
+
+/// Whether `candidate` lies right next to `geometry` in `direction`, across the separator between
+/// them, and overlaps it along that side.
+fn is_neighbor(geometry: &Geometry, candidate: &Geometry, direction: PaneDirection) -> bool {
+    let start = geometry.get_position();
+    let end = start + geometry.get_dimensions();
+    let candidate_start = candidate.get_position();
+    let candidate_end = candidate_start + candidate.get_dimensions();
+
+    let overlaps_x = candidate_start.x < end.x && start.x < candidate_end.x;
+    let overlaps_y = candidate_start.y < end.y && start.y < candidate_end.y;
+
+    match direction {
+        PaneDirection::Up => overlaps_x && candidate_end.y + 1 == start.y,
+        PaneDirection::Down => overlaps_x && end.y + 1 == candidate_start.y,
+        PaneDirection::Left => overlaps_y && candidate_end.x + 1 == start.x,
+        PaneDirection::Right => overlaps_y && end.x + 1 == candidate_start.x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_id::{PaneId, SessionId, WindowId};
+    use splix_terminal::Command;
+
+    use crate::{Splix, tests::test_server};
+
+    /// Panes and windows of a session `work` with two windows, the first split into a left and a
+    /// right pane, next to a session `other`.
+    struct Fixture {
+        splix: Splix,
+        work: SessionId,
+        other: SessionId,
+        editor: WindowId,
+        logs: WindowId,
+        left: PaneId,
+        right: PaneId,
+    }
+
+    async fn fixture() -> Fixture {
+        let mut splix = test_server();
+        let work = splix
+            .new_session(
+                Some(String::from("work")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+        let other = splix
+            .new_session(
+                Some(String::from("other")),
+                Command::new(vec![String::from("cat")]),
+            )
+            .unwrap();
+
+        let left = splix.resolve_pane(None).unwrap();
+        let editor = left.get_window();
+        run(&mut splix, &["split-window", "-h", "cat"]).await;
+        let right = splix.resolve_pane(None).unwrap();
+        run(&mut splix, &["new-window", "-n", "logs", "cat"]).await;
+        let logs = splix.resolve_window(None).unwrap();
+        run(&mut splix, &["select-window", "-t", "work:0"]).await;
+
+        Fixture {
+            splix,
+            work,
+            other,
+            editor,
+            logs,
+            left,
+            right,
+        }
+    }
+
+    async fn run(splix: &mut Splix, arguments: &[&str]) {
+        let arguments: Vec<String> = arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        splix.run_command(&arguments).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn resolves_sessions_by_name_and_from_windows() {
+        let fixture = fixture().await;
+        let splix = &fixture.splix;
+
+        assert_eq!(splix.resolve_session(None).unwrap(), fixture.work);
+        assert_eq!(splix.resolve_session(Some("oth")).unwrap(), fixture.other);
+        assert_eq!(
+            splix
+                .resolve_session(Some(&fixture.logs.to_string()))
+                .unwrap(),
+            fixture.work
+        );
+        assert!(splix.resolve_session(Some("nowhere")).is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_windows_by_index_and_relative_to_the_active_one() {
+        let fixture = fixture().await;
+        let splix = &fixture.splix;
+
+        assert_eq!(splix.resolve_window(None).unwrap(), fixture.editor);
+        assert_eq!(splix.resolve_window(Some("work:1")).unwrap(), fixture.logs);
+        assert_eq!(splix.resolve_window(Some("lo")).unwrap(), fixture.logs);
+        assert_eq!(splix.resolve_window(Some("+")).unwrap(), fixture.logs);
+        assert_eq!(splix.resolve_window(Some("{next}")).unwrap(), fixture.logs);
+        assert_eq!(splix.resolve_window(Some(":+2")).unwrap(), fixture.editor);
+        assert_eq!(splix.resolve_window(Some("!")).unwrap(), fixture.logs);
+        assert!(splix.resolve_window(Some("work:5")).is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_panes_by_index_edge_and_relative_to_the_active_one() {
+        let fixture = fixture().await;
+        let splix = &fixture.splix;
+
+        assert_eq!(splix.resolve_pane(None).unwrap(), fixture.right);
+        assert_eq!(splix.resolve_pane(Some("work:0.0")).unwrap(), fixture.left);
+        assert_eq!(splix.resolve_pane(Some(".1")).unwrap(), fixture.right);
+        assert_eq!(splix.resolve_pane(Some("{left}")).unwrap(), fixture.left);
+        assert_eq!(splix.resolve_pane(Some("{left-of}")).unwrap(), fixture.left);
+        assert_eq!(splix.resolve_pane(Some("+")).unwrap(), fixture.left);
+        assert_eq!(splix.resolve_pane(Some("{next}")).unwrap(), fixture.left);
+        assert_eq!(splix.resolve_pane(Some("!")).unwrap(), fixture.left);
+        assert_eq!(
+            splix.resolve_pane(Some(&fixture.left.to_string())).unwrap(),
+            fixture.left
+        );
+        assert!(splix.resolve_pane(Some("work:0.2")).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use std::str::FromStr;
+
+/// How the size of a session is chosen when clients with different screen sizes are attached to
+/// it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WindowSize {
+    /// Fits every client, padding the screens of the larger ones.
+    #[default]
+    Smallest,
+    /// Fills the largest client, cropping the screens of the smaller ones.
+    Largest,
+    /// Follows the client that was used most recently.
+    Latest,
+}
+
+impl FromStr for WindowSize {
+    type Err = splix_error::Error;
+
+    fn from_str(s: &str) -> splix_error::Result<Self> {
+        match s {
+            "smallest" => Ok(Self::Smallest),
+            "largest" => Ok(Self::Largest),
+            "latest" => Ok(Self::Latest),
+            _ => Err(splix_error::Error::InvalidWindowSize(s.to_string())),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::ControlSequence;
+
+/// What a program asked its terminal to do, as decoded by the `AnsiParser`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnsiAction {
+    /// Display a character at the cursor.
+    Print(char),
+    /// Execute a C0 control character, like a line feed.
+    Execute(char),
+    /// A sequence introduced by `ESC`, like `ESC 7` saving the cursor.
+    EscapeSequence {
+        intermediates: String,
+        final_character: char,
+    },
+    ControlSequence(ControlSequence),
+}
@@ -11,3 +11,9 @@ impl AnsiEncoder {
         format!("{ANSI_ESCAPE_CODE_PREFIX}{escape_code}")
     }
 }
+
+impl Default for AnsiEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,192 @@
+use crate::{AnsiAction, ControlSequence};
+
+/// Decodes the output of programs into the actions they ask of their terminal, one character at
+/// a time, loosely following the state machine of DEC's VT500 series.
+#[derive(Clone, Debug, Default)]
+pub struct AnsiParser {
+    state: AnsiParserState,
+    private_marker: Option<char>,
+    parameters: Vec<Vec<u16>>,
+    /// The parameter being parsed, after the ones it's a subparameter of.
+    subparameters: Vec<u16>,
+    parameter: Option<u16>,
+    intermediates: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum AnsiParserState {
+    #[default]
+    Ground,
+    Escape,
+    ControlSequence,
+    /// Operating system commands and device control strings, which are skipped until they are
+    /// terminated.
+    ControlString,
+}
+
+const ESCAPE: char = '\x1b';
+const BELL: char = '\x07';
+
+impl AnsiParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the action completed by `c`, if any.
+    pub fn advance(&mut self, c: char) -> Option<AnsiAction> {
+        match (self.state, c) {
+            (_, ESCAPE) => {
+                self.enter(AnsiParserState::Escape);
+                None
+            }
+            (AnsiParserState::ControlString, BELL) => {
+                self.state = AnsiParserState::Ground;
+                None
+            }
+            (AnsiParserState::ControlString, _) => None,
+            // Control characters are executed even in the middle of sequences.
+            (_, '\x00'..='\x1f') => Some(AnsiAction::Execute(c)),
+            (AnsiParserState::Ground, '\x7f') => None,
+            (AnsiParserState::Ground, _) => Some(AnsiAction::Print(c)),
+            (AnsiParserState::Escape, '[') => {
+                self.enter(AnsiParserState::ControlSequence);
+                None
+            }
+            (AnsiParserState::Escape, ']' | 'P' | 'X' | '^' | '_') => {
+                self.enter(AnsiParserState::ControlString);
+                None
+            }
+            (AnsiParserState::Escape, '\x20'..='\x2f') => {
+                self.intermediates.push(c);
+                None
+            }
+            (AnsiParserState::Escape, _) => {
+                self.state = AnsiParserState::Ground;
+                Some(AnsiAction::EscapeSequence {
+                    intermediates: std::mem::take(&mut self.intermediates),
+                    final_character: c,
+                })
+            }
+            (AnsiParserState::ControlSequence, '<' | '=' | '>' | '?')
+                if self.parameters.is_empty()
+                    && self.subparameters.is_empty()
+                    && self.parameter.is_none() =>
+            {
+                self.private_marker = Some(c);
+                None
+            }
+            (AnsiParserState::ControlSequence, '0'..='9') => {
+                let digit = c as u16 - '0' as u16;
+                self.parameter = Some(
+                    self.parameter
+                        .unwrap_or(0)
+                        .saturating_mul(10)
+                        .saturating_add(digit),
+                );
+                None
+            }
+            // Subparameters, like those of `CSI 38 : 2 : : r : g : b m`, stay with their
+            // parameter.
+            (AnsiParserState::ControlSequence, ':') => {
+                self.subparameters.push(self.parameter.take().unwrap_or(0));
+                None
+            }
+            (AnsiParserState::ControlSequence, ';') => {
+                self.subparameters.push(self.parameter.take().unwrap_or(0));
+                self.parameters
+                    .push(std::mem::take(&mut self.subparameters));
+                None
+            }
+            (AnsiParserState::ControlSequence, '\x20'..='\x2f') => {
+                self.intermediates.push(c);
+                None
+            }
+            (AnsiParserState::ControlSequence, '\x40'..='\x7e') => {
+                self.state = AnsiParserState::Ground;
+                if self.parameter.is_some() || !self.subparameters.is_empty() {
+                    self.subparameters.push(self.parameter.take().unwrap_or(0));
+                    self.parameters
+                        .push(std::mem::take(&mut self.subparameters));
+                }
+
+                Some(AnsiAction::ControlSequence(ControlSequence::new(
+                    self.private_marker.take(),
+                    std::mem::take(&mut self.parameters),
+                    std::mem::take(&mut self.intermediates),
+                    c,
+                )))
+            }
+            (AnsiParserState::ControlSequence, _) => None,
+        }
+    }
+
+    fn enter(&mut self, state: AnsiParserState) {
+        self.state = state;
+        self.private_marker = None;
+        self.parameters.clear();
+        self.subparameters.clear();
+        self.parameter = None;
+        self.intermediates.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AnsiAction, AnsiParser, ControlSequence};
+
+    fn parse(input: &str) -> Vec<AnsiAction> {
+        let mut parser = AnsiParser::new();
+        input.chars().filter_map(|c| parser.advance(c)).collect()
+    }
+
+    #[test]
+    fn parses_control_sequences() {
+        assert_eq!(
+            parse("a\x1b[?1;49h\x1b[mb"),
+            vec![
+                AnsiAction::Print('a'),
+                AnsiAction::ControlSequence(ControlSequence::new(
+                    Some('?'),
+                    vec![vec![1], vec![49]],
+                    String::new(),
+                    'h'
+                )),
+                AnsiAction::ControlSequence(ControlSequence::new(
+                    None,
+                    Vec::new(),
+                    String::new(),
+                    'm'
+                )),
+                AnsiAction::Print('b'),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_subparameters_with_their_parameter() {
+        assert_eq!(
+            parse("\x1b[4:3;38:2::1:2:3;1m"),
+            vec![AnsiAction::ControlSequence(ControlSequence::new(
+                None,
+                vec![vec![4, 3], vec![38, 2, 0, 1, 2, 3], vec![1]],
+                String::new(),
+                'm'
+            ))]
+        );
+    }
+
+    #[test]
+    fn skips_operating_system_commands() {
+        assert_eq!(
+            parse("\x1b]0;title\x07a\x1b]2;title\x1b\\\r"),
+            vec![
+                AnsiAction::Print('a'),
+                AnsiAction::EscapeSequence {
+                    intermediates: String::new(),
+                    final_character: '\\'
+                },
+                AnsiAction::Execute('\r'),
+            ]
+        );
+    }
+}
@@ -0,0 +1,59 @@
+/// A control sequence introduced by `CSI`, like `CSI 1 ; 2 H` moving the cursor.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ControlSequence {
+    private_marker: Option<char>,
+    /// Each parameter followed by its subparameters, like the `2`, `0` and color components of
+    /// `38 : 2 : 0 : r : g : b`.
+    parameters: Vec<Vec<u16>>,
+    intermediates: String,
+    final_character: char,
+}
+
+impl ControlSequence {
+    pub fn new(
+        private_marker: Option<char>,
+        parameters: Vec<Vec<u16>>,
+        intermediates: String,
+        final_character: char,
+    ) -> Self {
+        Self {
+            private_marker,
+            parameters,
+            intermediates,
+            final_character,
+        }
+    }
+
+    /// Like the `?` of the DEC private modes.
+    pub fn get_private_marker(&self) -> Option<char> {
+        self.private_marker
+    }
+
+    /// Omitted parameters are 0. Subparameters are left out.
+    pub fn get_parameters(&self) -> impl Iterator<Item = u16> + '_ {
+        self.parameters
+            .iter()
+            .map(|parameter| parameter.first().copied().unwrap_or(0))
+    }
+
+    /// Each parameter followed by its subparameters, omitted ones being 0.
+    pub fn get_parameters_with_subparameters(&self) -> &[Vec<u16>] {
+        &self.parameters
+    }
+
+    /// The parameter at `index`, or `default` if it was omitted or is 0.
+    pub fn get_parameter(&self, index: usize, default: u16) -> u16 {
+        match self.get_parameters().nth(index) {
+            Some(0) | None => default,
+            Some(parameter) => parameter,
+        }
+    }
+
+    pub fn get_intermediates(&self) -> &str {
+        &self.intermediates
+    }
+
+    pub fn get_final_character(&self) -> char {
+        self.final_character
+    }
+}
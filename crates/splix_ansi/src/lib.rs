@@ -1,3 +1,9 @@
+mod ansi_action;
 mod ansi_encoder;
+mod ansi_parser;
+mod control_sequence;
 
+pub use ansi_action::AnsiAction;
 pub use ansi_encoder::AnsiEncoder;
+pub use ansi_parser::AnsiParser;
+pub use control_sequence::ControlSequence;
@@ -0,0 +1,22 @@
+use std::fmt;
+
+/// Why a client stopped displaying its session.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientExit {
+    Detached,
+    Exited,
+    LostServer,
+    /// The terminal the client runs in stopped sending input, like when it was closed.
+    LostTerminal,
+}
+
+impl fmt::Display for ClientExit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Detached => write!(f, "[detached]"),
+            Self::Exited => write!(f, "[exited]"),
+            Self::LostServer => write!(f, "[lost server]"),
+            Self::LostTerminal => write!(f, "[lost tty]"),
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::{io, path::Path, time::Duration};
+
+use tokio::{
+    net::{UnixStream, unix::OwnedWriteHalf},
+    sync::mpsc::{self, Receiver, Sender},
+    time,
+};
+
+use splix_protocol::{
+    ClientMessage, MessageReader, MessageWriter, PROTOCOL_VERSION, ServerMessage,
+    check_protocol_version,
+};
+
+use crate::ServerLauncher;
+
+/// A connection to a server that agreed to speak the client's protocol version.
+pub struct Connection {
+    message_writer: MessageWriter<OwnedWriteHalf>,
+    server_message_receiver: Receiver<ServerMessage>,
+}
+
+const SERVER_MESSAGE_CHANNEL_CAPACITY: usize = 64;
+
+/// How long the server may take to answer the greeting or an attachment.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl Connection {
+    /// Connects to the server listening on `socket_path`.
+    ///
+    /// A server is started with `server_launcher` if none is running, otherwise failing to
+    /// connect is an error.
+    pub async fn open(
+        socket_path: &Path,
+        server_launcher: Option<&ServerLauncher>,
+    ) -> splix_error::Result<Self> {
+        let stream = match (UnixStream::connect(socket_path).await, server_launcher) {
+            (Ok(stream), _) => stream,
+            (Err(_), Some(server_launcher)) => server_launcher.launch(socket_path).await?,
+            (Err(e), None)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+                ) =>
+            {
+                return Err(splix_error::Error::NoServer(socket_path.to_path_buf()));
+            }
+            (Err(e), None) => return Err(splix_error::Error::ConnectToServer(e)),
+        };
+
+        let (read_half, write_half) = stream.into_split();
+
+        let (server_message_sender, server_message_receiver): (
+            Sender<ServerMessage>,
+            Receiver<ServerMessage>,
+        ) = mpsc::channel(SERVER_MESSAGE_CHANNEL_CAPACITY);
+        tokio::spawn(async move {
+            let mut message_reader = MessageReader::new(read_half);
+            while let Ok(Some(message)) = message_reader.read::<ServerMessage>().await {
+                if server_message_sender.send(message).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut connection = Self {
+            message_writer: MessageWriter::new(write_half),
+            server_message_receiver,
+        };
+        connection.greet().await?;
+
+        Ok(connection)
+    }
+
+    pub async fn send(&mut self, message: &ClientMessage) -> splix_error::Result<()> {
+        self.message_writer.write(message).await
+    }
+
+    /// Returns `None` once the server closed the connection.
+    pub async fn receive(&mut self) -> Option<ServerMessage> {
+        self.server_message_receiver.recv().await
+    }
+
+    /// Like [`Self::receive`], for a reply the server should send right away. A server that's
+    /// stuck is an error rather than waited for forever.
+    pub async fn receive_reply(&mut self) -> splix_error::Result<Option<ServerMessage>> {
+        time::timeout(REPLY_TIMEOUT, self.receive())
+            .await
+            .map_err(|_| splix_error::Error::ServerTimeout)
+    }
+
+    /// Runs a command given as its arguments on the server, returning what it printed.
+    pub async fn run_command(&mut self, arguments: Vec<String>) -> splix_error::Result<String> {
+        self.send(&ClientMessage::Command(arguments)).await?;
+
+        loop {
+            match self.receive().await {
+                Some(ServerMessage::CommandResponse(response)) => {
+                    return response.map_err(splix_error::Error::Server);
+                }
+                Some(ServerMessage::Error(message)) => {
+                    return Err(splix_error::Error::Server(message));
+                }
+                Some(_) => {}
+                None => return Err(splix_error::Error::ConnectionClosed),
+            }
+        }
+    }
+
+    /// Makes sure the server speaks the same protocol version before sending it anything else.
+    async fn greet(&mut self) -> splix_error::Result<()> {
+        self.send(&ClientMessage::Hello {
+            version: PROTOCOL_VERSION,
+        })
+        .await?;
+
+        match self.receive_reply().await? {
+            Some(ServerMessage::Hello { version }) => check_protocol_version(version),
+            Some(ServerMessage::Error(message)) => Err(splix_error::Error::Server(message)),
+            _ => Err(splix_error::Error::ExpectedHello),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+use splix_protocol::{ClientMessage, ServerMessage};
+
+use crate::{ClientExit, Connection};
+
+/// A client in control mode, sending the lines read from stdin to the server as commands and
+/// printing the replies and notifications it gets back.
+pub struct ControlClient {
+    connection: Connection,
+    pending_message: Option<ServerMessage>,
+}
+
+impl ControlClient {
+    /// Attaches to `target`, or to the most recently used session.
+    pub async fn attach(
+        mut connection: Connection,
+        target: Option<String>,
+    ) -> splix_error::Result<Self> {
+        connection
+            .send(&ClientMessage::Attach {
+                target,
+                read_only: false,
+                control: true,
+            })
+            .await?;
+
+        let pending_message = connection.receive_reply().await?;
+        if let Some(ServerMessage::Error(message)) = pending_message {
+            return Err(splix_error::Error::Server(message));
+        }
+
+        Ok(Self {
+            connection,
+            pending_message,
+        })
+    }
+
+    /// Runs until the client is detached, the server exits or the connection to it is lost.
+    ///
+    /// The end of stdin detaches the client, like an empty line does.
+    pub async fn run(&mut self) -> splix_error::Result<ClientExit> {
+        if let Some(message) = self.pending_message.take()
+            && let Some(exit) = Self::handle_server_message(message)?
+        {
+            return Ok(exit);
+        }
+
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        let mut stdin_open = true;
+
+        loop {
+            tokio::select! {
+                line = lines.next_line(), if stdin_open => {
+                    let line = line.map_err(splix_error::Error::ReadControlCommand)?;
+                    stdin_open = line.is_some();
+                    self.connection
+                        .send(&ClientMessage::ControlCommand(line.unwrap_or_default()))
+                        .await?;
+                }
+                message = self.connection.receive() => match message {
+                    Some(message) => {
+                        if let Some(exit) = Self::handle_server_message(message)? {
+                            return Ok(exit);
+                        }
+                    }
+                    None => return Ok(ClientExit::LostServer),
+                },
+            }
+        }
+    }
+
+    fn handle_server_message(message: ServerMessage) -> splix_error::Result<Option<ClientExit>> {
+        match message {
+            ServerMessage::ControlOutput(output) => {
+                let mut stdout = io::stdout();
+                stdout
+                    .write_all(output.as_bytes())
+                    .and_then(|()| stdout.flush())
+                    .map_err(splix_error::Error::WriteControlOutput)?;
+                Ok(None)
+            }
+            ServerMessage::Exit => Ok(Some(ClientExit::Exited)),
+            ServerMessage::Detach => Ok(Some(ClientExit::Detached)),
+            _ => Ok(None),
+        }
+    }
+}
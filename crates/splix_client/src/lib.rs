@@ -0,0 +1,184 @@
+mod client_exit;
+mod connection;
+mod control_client;
+mod server_launcher;
+
+use glam::UVec2;
+use terminal_size::{Height, Width};
+use tokio::{
+    signal::unix::{self, SignalKind},
+    sync::mpsc::{self, Receiver, Sender},
+};
+
+pub use client_exit::ClientExit;
+pub use connection::Connection;
+pub use control_client::ControlClient;
+pub use server_launcher::ServerLauncher;
+use splix_event::KeyEvent;
+use splix_frame::Frame;
+use splix_input::InputReceiver;
+use splix_protocol::{ClientMessage, ServerMessage};
+use splix_renderer::Renderer;
+use splix_termios::Termios;
+
+/// The thin front end running in the user's terminal, displaying what the server sends it.
+pub struct Client {
+    termios: Termios,
+    input_receiver: InputReceiver,
+    key_receiver: Receiver<Vec<KeyEvent>>,
+    connection: Connection,
+    pending_message: Option<ServerMessage>,
+    frame: Option<Frame>,
+    renderer: Renderer,
+}
+
+const KEY_CHANNEL_CAPACITY: usize = 1024;
+
+impl Client {
+    /// Attaches to `target`, or to the most recently used session. Everything but detaching is
+    /// ignored for `read_only` clients.
+    pub async fn attach(
+        mut connection: Connection,
+        target: Option<String>,
+        read_only: bool,
+    ) -> splix_error::Result<Self> {
+        let screen_dimensions = Self::retrieve_screen_dimensions()?;
+
+        connection
+            .send(&ClientMessage::Resize(screen_dimensions))
+            .await?;
+        connection
+            .send(&ClientMessage::Attach {
+                target,
+                read_only,
+                control: false,
+            })
+            .await?;
+
+        // Refusals are reported before the terminal is switched into raw mode.
+        let pending_message = connection.receive_reply().await?;
+        if let Some(ServerMessage::Error(message)) = pending_message {
+            return Err(splix_error::Error::Server(message));
+        }
+
+        let termios = Termios::new()?;
+        let (key_sender, key_receiver): (Sender<Vec<KeyEvent>>, Receiver<Vec<KeyEvent>>) =
+            mpsc::channel(KEY_CHANNEL_CAPACITY);
+        let input_receiver = InputReceiver::new(key_sender);
+
+        Ok(Self {
+            termios,
+            input_receiver,
+            key_receiver,
+            connection,
+            pending_message,
+            frame: None,
+            renderer: Renderer::new(screen_dimensions),
+        })
+    }
+
+    /// Runs until the client is detached, the server exits or the connection to it is lost.
+    pub async fn run(&mut self) -> splix_error::Result<ClientExit> {
+        let mut window_changes =
+            unix::signal(SignalKind::window_change()).map_err(splix_error::Error::HandleSignal)?;
+
+        if let Some(message) = self.pending_message.take()
+            && let Some(exit) = self.handle_server_message(message)
+        {
+            return Ok(exit);
+        }
+
+        loop {
+            tokio::select! {
+                Some(key_events) = self.key_receiver.recv() => self.handle_keys(&key_events).await?,
+                message = self.connection.receive() => match message {
+                    Some(message) => {
+                        if let Some(exit) = self.handle_server_message(message) {
+                            return Ok(exit);
+                        }
+                    }
+                    None => return Ok(ClientExit::LostServer),
+                },
+                Some(()) = window_changes.recv() => self.handle_window_change().await?,
+                result = self.input_receiver.closed() => {
+                    result?;
+                    return Ok(ClientExit::LostTerminal);
+                }
+            }
+        }
+    }
+
+    /// Returns why the client should stop, if the message ends the attachment.
+    fn handle_server_message(&mut self, message: ServerMessage) -> Option<ClientExit> {
+        match message {
+            ServerMessage::Frame(frame) => {
+                self.draw_frame(frame);
+                None
+            }
+            ServerMessage::FrameUpdate(update) => {
+                // Updates are relative to a full frame, which the server always sends first.
+                if let Some(mut frame) = self.frame.take() {
+                    frame.apply(&update);
+                    self.draw_frame(frame);
+                }
+                None
+            }
+            ServerMessage::SetTitle(title) => {
+                self.renderer.set_title(&title);
+                None
+            }
+            ServerMessage::SetMouseTracking(tracking) => {
+                // The mouse is only a convenience, so the attachment goes on without it.
+                self.termios.set_mouse_tracking(tracking).ok();
+                None
+            }
+            ServerMessage::Exit => Some(ClientExit::Exited),
+            ServerMessage::Detach => Some(ClientExit::Detached),
+            // Errors after attaching don't end the attachment.
+            ServerMessage::Error(_) => None,
+            ServerMessage::Hello { .. }
+            | ServerMessage::CommandResponse(_)
+            | ServerMessage::ControlOutput(_) => None,
+        }
+    }
+
+    fn draw_frame(&mut self, frame: Frame) {
+        self.renderer.begin_frame();
+        self.renderer.draw_frame(&frame);
+        self.renderer.end_frame();
+
+        self.frame = Some(frame);
+    }
+
+    fn retrieve_screen_dimensions() -> splix_error::Result<UVec2> {
+        if let Some((Width(terminal_width), Height(terminal_height))) =
+            terminal_size::terminal_size()
+        {
+            Ok(UVec2::new(terminal_width as u32, terminal_height as u32))
+        } else {
+            Err(splix_error::Error::RetrieveTerminalSize)
+        }
+    }
+
+    /// Keys are sent as the bytes they were typed as, which the server decodes again against
+    /// the key bindings of the client. Only the client can tell when the rest of an escape
+    /// sequence isn't coming, so it decodes first to send whole keys in each message.
+    async fn handle_keys(&mut self, key_events: &[KeyEvent]) -> splix_error::Result<()> {
+        let input = key_events
+            .iter()
+            .flat_map(|key_event| key_event.get_bytes())
+            .copied()
+            .collect();
+
+        self.connection.send(&ClientMessage::Input(input)).await
+    }
+
+    async fn handle_window_change(&mut self) -> splix_error::Result<()> {
+        let screen_dimensions = Self::retrieve_screen_dimensions()?;
+        self.renderer.resize(screen_dimensions);
+
+        self.connection
+            .send(&ClientMessage::Resize(screen_dimensions))
+            .await
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    io,
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Command, Stdio},
+    time::Duration,
+};
+
+use nix::unistd;
+use tokio::{net::UnixStream, time};
+
+/// Starts a server in the background when there is none to connect to.
+pub struct ServerLauncher {
+    command: Command,
+}
+
+const CONNECT_ATTEMPTS: u32 = 50;
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+impl ServerLauncher {
+    /// `command` should run a server in the foreground, it is detached from the terminal here.
+    pub fn new(command: Command) -> Self {
+        Self { command }
+    }
+
+    pub async fn launch(&self, socket_path: &Path) -> splix_error::Result<UnixStream> {
+        self.spawn()?;
+
+        let mut last_error = None;
+        for _ in 0..CONNECT_ATTEMPTS {
+            match UnixStream::connect(socket_path).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => last_error = Some(e),
+            }
+
+            time::sleep(CONNECT_RETRY_INTERVAL).await;
+        }
+
+        Err(splix_error::Error::ConnectToServer(
+            last_error.unwrap_or_else(|| io::Error::from(io::ErrorKind::TimedOut)),
+        ))
+    }
+
+    fn spawn(&self) -> splix_error::Result<()> {
+        let mut command = Command::new(self.command.get_program());
+        command
+            .args(self.command.get_args())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        // Start a new session, so the server outlives the terminal it was started from.
+        unsafe {
+            command.pre_exec(|| unistd::setsid().map(|_| ()).map_err(io::Error::from));
+        }
+
+        // The server keeps running on its own, it is never waited for.
+        command
+            .spawn()
+            .map(|_| ())
+            .map_err(splix_error::Error::StartServer)
+    }
+}
@@ -0,0 +1,141 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// A command written out as a single line, like in control mode, split into its arguments.
+///
+/// Arguments are separated by whitespace. Single quotes keep everything up to the next one as is,
+/// while double quotes and unquoted text let a backslash escape the next character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandLine {
+    arguments: Vec<String>,
+}
+
+impl CommandLine {
+    pub fn new(arguments: Vec<String>) -> Self {
+        Self { arguments }
+    }
+
+    pub fn get_arguments(&self) -> &[String] {
+        &self.arguments
+    }
+
+    pub fn into_arguments(self) -> Vec<String> {
+        self.arguments
+    }
+}
+
+impl FromStr for CommandLine {
+    type Err = splix_error::Error;
+
+    fn from_str(line: &str) -> splix_error::Result<Self> {
+        let mut arguments = Vec::new();
+        let mut argument: Option<String> = None;
+        let mut quote = None;
+        let mut chars = line.chars();
+
+        while let Some(c) = chars.next() {
+            match (quote, c) {
+                (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+                (Some('\''), c) => argument.get_or_insert_default().push(c),
+                (_, '\\') => {
+                    let escaped = chars.next().unwrap_or('\\');
+                    argument.get_or_insert_default().push(escaped);
+                }
+                (Some(_), c) => argument.get_or_insert_default().push(c),
+                (None, '\'' | '"') => {
+                    quote = Some(c);
+                    argument.get_or_insert_default();
+                }
+                (None, c) if c.is_whitespace() => arguments.extend(argument.take()),
+                (None, c) => argument.get_or_insert_default().push(c),
+            }
+        }
+
+        if quote.is_some() {
+            return Err(splix_error::Error::UnterminatedQuote(line.to_string()));
+        }
+        arguments.extend(argument);
+
+        Ok(Self { arguments })
+    }
+}
+
+/// Quotes the arguments that need it, so that the line parses back into the same arguments.
+impl Display for CommandLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (index, argument) in self.arguments.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?;
+            }
+
+            let plain = !argument.is_empty()
+                && !argument
+                    .chars()
+                    .any(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\'));
+            if plain {
+                f.write_str(argument)?;
+            } else if !argument.contains('\'') {
+                write!(f, "'{argument}'")?;
+            } else {
+                let escaped = argument.replace('\\', "\\\\").replace('"', "\\\"");
+                write!(f, "\"{escaped}\"")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CommandLine;
+
+    fn split(line: &str) -> Vec<String> {
+        line.parse::<CommandLine>().unwrap().into_arguments()
+    }
+
+    #[test]
+    fn splits_at_whitespace() {
+        assert_eq!(
+            split("  list-windows\t-t work  "),
+            ["list-windows", "-t", "work"]
+        );
+        assert!(split("").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_text_together() {
+        assert_eq!(
+            split(r#"lsw -F '#{window_name} \n' -t "my \"work\"""#),
+            ["lsw", "-F", r"#{window_name} \n", "-t", r#"my "work""#]
+        );
+        assert_eq!(split(r#"rename '' a\ b"#), ["rename", "", "a b"]);
+    }
+
+    #[test]
+    fn quotes_arguments_when_written() {
+        let arguments = [
+            "send-keys",
+            "-t",
+            "{up-of}",
+            "a b",
+            "",
+            r"it's \",
+            "\"",
+            "#{x}",
+        ];
+        let line = CommandLine::new(arguments.iter().map(|a| a.to_string()).collect());
+        assert_eq!(
+            line.to_string(),
+            r#"send-keys -t {up-of} 'a b' '' "it's \\" '"' #{x}"#
+        );
+        assert_eq!(split(&line.to_string()), arguments);
+    }
+
+    #[test]
+    fn rejects_unterminated_quotes() {
+        assert!("send-keys 'abc".parse::<CommandLine>().is_err());
+    }
+}
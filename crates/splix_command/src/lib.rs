@@ -0,0 +1,8 @@
+mod command_line;
+mod line_number;
+mod splix_command;
+
+pub use command_line::CommandLine;
+pub use line_number::LineNumber;
+
+pub use splix_command::SplixCommand;
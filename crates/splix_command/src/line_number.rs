@@ -0,0 +1,24 @@
+use std::str::FromStr;
+
+/// A line of a pane, counted from the top of its screen with history lines being negative, or
+/// `-` for the start of the history or the end of the screen.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineNumber {
+    Line(i64),
+    Edge,
+}
+
+impl FromStr for LineNumber {
+    type Err = splix_error::Error;
+
+    fn from_str(line_number: &str) -> splix_error::Result<Self> {
+        if line_number == "-" {
+            return Ok(Self::Edge);
+        }
+
+        line_number
+            .parse()
+            .map(Self::Line)
+            .map_err(|_| splix_error::Error::InvalidLineNumber(line_number.to_string()))
+    }
+}
@@ -0,0 +1,508 @@
+use std::path::PathBuf;
+
+use clap::{ArgAction, Parser, Subcommand};
+use splix_key::Key;
+
+use crate::LineNumber;
+
+/// A command run by the server, on behalf of the command line or of an attached client.
+#[derive(Clone, Debug, PartialEq, Eq, Subcommand)]
+pub enum SplixCommand {
+    /// Create a new session
+    #[command(alias = "new")]
+    NewSession {
+        /// Don't attach to the new session
+        #[arg(short = 'd')]
+        detached: bool,
+        /// Print the ID of the new session
+        #[arg(short = 'P')]
+        print: bool,
+        /// Name of the new session
+        #[arg(short = 's', value_name = "session-name")]
+        name: Option<String>,
+        /// Working directory of the new session
+        #[arg(short = 'c', value_name = "start-directory")]
+        start_directory: Option<PathBuf>,
+        /// Program to run instead of the shell
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Create a new window
+    #[command(alias = "neww")]
+    NewWindow {
+        /// Don't make the new window the active one
+        #[arg(short = 'd')]
+        detached: bool,
+        /// Print the ID of the new window
+        #[arg(short = 'P')]
+        print: bool,
+        /// What to print instead of the ID
+        #[arg(short = 'F', value_name = "format", requires = "print")]
+        format: Option<String>,
+        /// Name of the new window
+        #[arg(short = 'n', value_name = "window-name")]
+        name: Option<String>,
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+        /// Working directory of the new window
+        #[arg(short = 'c', value_name = "start-directory")]
+        start_directory: Option<PathBuf>,
+        /// Program to run instead of the shell
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Split a pane into two
+    #[command(alias = "splitw", disable_help_flag = true)]
+    SplitWindow {
+        /// Place the new pane beside the split one instead of below it
+        #[arg(short = 'h')]
+        horizontal: bool,
+        /// Place the new pane below the split one, which is the default
+        #[arg(short = 'v', conflicts_with = "horizontal")]
+        vertical: bool,
+        /// Don't make the new pane the active one
+        #[arg(short = 'd')]
+        detached: bool,
+        /// Print the ID of the new pane
+        #[arg(short = 'P')]
+        print: bool,
+        /// What to print instead of the ID
+        #[arg(short = 'F', value_name = "format", requires = "print")]
+        format: Option<String>,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+        /// Working directory of the new pane, the split pane's by default
+        #[arg(short = 'c', value_name = "start-directory")]
+        start_directory: Option<PathBuf>,
+        /// Program to run instead of the shell
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+        #[arg(long, action = ArgAction::Help)]
+        help: Option<bool>,
+    },
+    /// Detach the current client, or every client attached to a session
+    #[command(alias = "detach")]
+    DetachClient {
+        #[arg(short = 's', value_name = "target-session")]
+        target: Option<String>,
+    },
+    /// List sessions
+    #[command(alias = "ls")]
+    ListSessions {
+        /// How to print each session
+        #[arg(short = 'F', value_name = "format")]
+        format: Option<String>,
+    },
+    /// List the windows of a session
+    #[command(alias = "lsw")]
+    ListWindows {
+        /// How to print each window
+        #[arg(short = 'F', value_name = "format")]
+        format: Option<String>,
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+    },
+    /// List the panes of a window
+    #[command(alias = "lsp")]
+    ListPanes {
+        /// How to print each pane
+        #[arg(short = 'F', value_name = "format")]
+        format: Option<String>,
+        #[arg(short = 't', value_name = "target-window")]
+        target: Option<String>,
+    },
+    /// Kill a session and everything running in it
+    KillSession {
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+    },
+    /// Kill a window and everything running in it
+    #[command(alias = "killw")]
+    KillWindow {
+        #[arg(short = 't', value_name = "target-window")]
+        target: Option<String>,
+    },
+    /// Kill a pane and the program running in it
+    #[command(alias = "killp")]
+    KillPane {
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// Restart the program of a pane that exited, or of any pane with -k
+    #[command(alias = "respawnp")]
+    RespawnPane {
+        /// Kill the program if it's still running
+        #[arg(short = 'k')]
+        kill: bool,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+        /// Program to run instead of the one the pane ran before
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    /// Kill the server with all of its sessions
+    KillServer,
+    /// Rename a session
+    #[command(alias = "rename")]
+    RenameSession {
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+        new_name: String,
+    },
+    /// Rename a window
+    #[command(alias = "renamew")]
+    RenameWindow {
+        #[arg(short = 't', value_name = "target-window")]
+        target: Option<String>,
+        new_name: String,
+    },
+    /// Make a window the active one of its session
+    #[command(alias = "selectw")]
+    SelectWindow {
+        #[arg(short = 't', value_name = "target-window")]
+        target: Option<String>,
+    },
+    /// Make a pane the active one of its window
+    #[command(alias = "selectp")]
+    SelectPane {
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// Type keys into a pane
+    #[command(alias = "send")]
+    SendKeys {
+        /// Send the keys as literal text instead of looking up key names
+        #[arg(short = 'l')]
+        literal: bool,
+        /// Run the first key as a copy mode command, like cancel or page-up
+        #[arg(short = 'X', conflicts_with = "literal")]
+        copy_mode_command: bool,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+        /// Key names like C-c, M-x, Enter, Up or F5, and text sent as is
+        #[arg(allow_hyphen_values = true)]
+        keys: Vec<String>,
+    },
+    /// Scroll back through the history of a pane
+    CopyMode {
+        /// Leave copy mode when scrolling back down to the bottom
+        #[arg(short = 'e')]
+        exit_at_bottom: bool,
+        /// Scroll up a page right away
+        #[arg(short = 'u')]
+        page_up: bool,
+        /// Leave copy mode instead
+        #[arg(short = 'q')]
+        quit: bool,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// Send the prefix key to a pane
+    SendPrefix {
+        /// Send the secondary prefix key instead
+        #[arg(short = '2')]
+        secondary: bool,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// Save the text of a pane into a paste buffer
+    #[command(alias = "capturep")]
+    CapturePane {
+        /// Print the text instead of saving it
+        #[arg(short = 'p')]
+        print: bool,
+        /// Keep the style of the text as escape sequences
+        #[arg(short = 'e')]
+        escapes: bool,
+        /// Join lines that were wrapped because they didn't fit
+        #[arg(short = 'J')]
+        join: bool,
+        /// First line to capture, negative for history lines and `-` for the oldest one
+        #[arg(short = 'S', value_name = "start-line", allow_hyphen_values = true)]
+        start: Option<LineNumber>,
+        /// Last line to capture, `-` for the last line of the screen
+        #[arg(short = 'E', value_name = "end-line", allow_hyphen_values = true)]
+        end: Option<LineNumber>,
+        #[arg(short = 'b', value_name = "buffer-name")]
+        buffer: Option<String>,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// List paste buffers
+    #[command(alias = "lsb")]
+    ListBuffers {
+        /// How to print each buffer
+        #[arg(short = 'F', value_name = "format")]
+        format: Option<String>,
+    },
+    /// Print a paste buffer, the most recent one by default
+    #[command(alias = "showb")]
+    ShowBuffer {
+        #[arg(short = 'b', value_name = "buffer-name")]
+        buffer: Option<String>,
+    },
+    /// Type a paste buffer into a pane, the most recent one by default
+    #[command(alias = "pasteb")]
+    PasteBuffer {
+        /// Delete the buffer afterwards
+        #[arg(short = 'd')]
+        delete: bool,
+        #[arg(short = 'b', value_name = "buffer-name")]
+        buffer: Option<String>,
+        #[arg(short = 't', value_name = "target-pane")]
+        target: Option<String>,
+    },
+    /// Delete a paste buffer, the most recent one by default
+    #[command(alias = "deleteb")]
+    DeleteBuffer {
+        #[arg(short = 'b', value_name = "buffer-name")]
+        buffer: Option<String>,
+    },
+    /// Serve JSON-RPC requests on a socket, printing its path
+    OpenJsonSocket {
+        /// Where to create the socket, next to the server socket by default
+        socket_path: Option<PathBuf>,
+    },
+    /// Stop serving JSON-RPC requests, disconnecting the tools using them
+    CloseJsonSocket,
+    /// Bind a key to a command
+    #[command(alias = "bind")]
+    BindKey {
+        /// Bind the key in the root table, without pressing the prefix first
+        #[arg(short = 'n', conflicts_with = "table")]
+        root: bool,
+        /// Let the key be pressed again without the prefix for a while
+        #[arg(short = 'r')]
+        repeat: bool,
+        /// Table to bind the key in, the prefix table by default
+        #[arg(short = 'T', value_name = "key-table")]
+        table: Option<String>,
+        key: Key,
+        #[arg(
+            trailing_var_arg = true,
+            allow_hyphen_values = true,
+            required = true,
+            value_name = "command"
+        )]
+        command: Vec<String>,
+    },
+    /// Remove the binding of a key
+    #[command(alias = "unbind")]
+    UnbindKey {
+        /// Remove every binding of the table
+        #[arg(short = 'a', conflicts_with = "key")]
+        all: bool,
+        /// Unbind the key from the root table
+        #[arg(short = 'n', conflicts_with = "table")]
+        root: bool,
+        /// Table to unbind the key from, the prefix table by default
+        #[arg(short = 'T', value_name = "key-table")]
+        table: Option<String>,
+        #[arg(required_unless_present = "all")]
+        key: Option<Key>,
+    },
+    /// List key bindings
+    #[command(alias = "lsk")]
+    ListKeys {
+        /// Only list the bindings of this table
+        #[arg(short = 'T', value_name = "key-table")]
+        table: Option<String>,
+    },
+    /// Switch the current client to another session or key table
+    #[command(alias = "switchc")]
+    SwitchClient {
+        /// Look up the next key in this table
+        #[arg(short = 'T', value_name = "key-table")]
+        table: Option<String>,
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+    },
+    /// Set an option for a session, window or pane, or for the whole server
+    #[command(aliases = ["set", "set-window-option", "setw"])]
+    SetOption {
+        /// Set the option for the whole server, which everything inherits it from
+        #[arg(short = 'g')]
+        global: bool,
+        /// Set the option for the target window and the panes in it
+        #[arg(short = 'w', conflicts_with_all = ["global", "pane"])]
+        window: bool,
+        /// Set the option for the target pane alone
+        #[arg(short = 'p', conflicts_with = "global")]
+        pane: bool,
+        /// Stop setting the option, inheriting it again or going back to its default
+        #[arg(short = 'u')]
+        unset: bool,
+        /// Session, window or pane to set the option for, depending on what the option applies
+        /// to unless told otherwise
+        #[arg(short = 't', value_name = "target", conflicts_with = "global")]
+        target: Option<String>,
+        option: String,
+        #[arg(required_unless_present = "unset", conflicts_with = "unset")]
+        value: Option<String>,
+    },
+    /// Show the options set for a session, window or pane, or for the whole server
+    #[command(alias = "show")]
+    ShowOptions {
+        /// Show the options of the whole server, including the ones nothing sets
+        #[arg(short = 'g')]
+        global: bool,
+        /// Show the options of the target window
+        #[arg(short = 'w', conflicts_with_all = ["global", "pane"])]
+        window: bool,
+        /// Show the options of the target pane
+        #[arg(short = 'p', conflicts_with = "global")]
+        pane: bool,
+        /// Also show the options inherited from above, marked with a `*`
+        #[arg(short = 'A')]
+        inherited: bool,
+        /// Only print the values
+        #[arg(short = 'v')]
+        values_only: bool,
+        #[arg(short = 't', value_name = "target", conflicts_with = "global")]
+        target: Option<String>,
+        /// Only show this option
+        option: Option<String>,
+    },
+    /// Run the commands of a file, one per line
+    #[command(alias = "source")]
+    SourceFile { path: PathBuf },
+    /// List the messages shown to clients, like the errors of the config file
+    #[command(alias = "showmsgs")]
+    ShowMessages,
+}
+
+#[derive(Parser)]
+#[command(name = "splix", no_binary_name = true, args_override_self = true)]
+struct SplixCommandParser {
+    #[command(subcommand)]
+    command: SplixCommand,
+}
+
+impl SplixCommand {
+    /// Parses a command given as its arguments, starting with the command's name.
+    pub fn parse(arguments: &[String]) -> splix_error::Result<Self> {
+        SplixCommandParser::try_parse_from(arguments)
+            .map(|parser| parser.command)
+            .map_err(|e| splix_error::Error::ParseCommand(e.render().to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{LineNumber, SplixCommand};
+
+    fn arguments(arguments: &[&str]) -> Vec<String> {
+        arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn parses_trailing_command() {
+        assert_eq!(
+            SplixCommand::parse(&arguments(&[
+                "new-session",
+                "-d",
+                "-s",
+                "work",
+                "-c",
+                "/tmp",
+                "vim",
+                "-R",
+            ]))
+            .unwrap(),
+            SplixCommand::NewSession {
+                detached: true,
+                print: false,
+                name: Some(String::from("work")),
+                start_directory: Some(PathBuf::from("/tmp")),
+                command: arguments(&["vim", "-R"]),
+            }
+        );
+    }
+
+    #[test]
+    fn h_splits_horizontally() {
+        assert!(matches!(
+            SplixCommand::parse(&arguments(&["splitw", "-h", "-t", "work:0.1"])).unwrap(),
+            SplixCommand::SplitWindow {
+                horizontal: true,
+                target: Some(target),
+                ..
+            } if target == "work:0.1"
+        ));
+    }
+
+    #[test]
+    fn parses_capture_line_numbers() {
+        assert!(matches!(
+            SplixCommand::parse(&arguments(&["capture-pane", "-p", "-S", "-", "-E", "-3"]))
+                .unwrap(),
+            SplixCommand::CapturePane {
+                print: true,
+                start: Some(LineNumber::Edge),
+                end: Some(LineNumber::Line(-3)),
+                ..
+            }
+        ));
+        assert!(SplixCommand::parse(&arguments(&["capturep", "-S", "top"])).is_err());
+    }
+
+    #[test]
+    fn format_requires_print() {
+        assert!(SplixCommand::parse(&arguments(&["neww", "-F", "#{window_id}"])).is_err());
+        assert!(SplixCommand::parse(&arguments(&["neww", "-P", "-F", "#{window_id}"])).is_ok());
+    }
+
+    #[test]
+    fn parses_bound_command() {
+        assert_eq!(
+            SplixCommand::parse(&arguments(&[
+                "bind",
+                "-r",
+                "Up",
+                "select-pane",
+                "-t",
+                "{up-of}"
+            ]))
+            .unwrap(),
+            SplixCommand::BindKey {
+                root: false,
+                repeat: true,
+                table: None,
+                key: "Up".parse().unwrap(),
+                command: arguments(&["select-pane", "-t", "{up-of}"]),
+            }
+        );
+        assert!(SplixCommand::parse(&arguments(&["bind", "C-q"])).is_err());
+        assert!(SplixCommand::parse(&arguments(&["bind", "Hyper-q", "detach"])).is_err());
+        assert!(SplixCommand::parse(&arguments(&["unbind"])).is_err());
+        assert!(SplixCommand::parse(&arguments(&["unbind", "-a", "-T", "custom"])).is_ok());
+    }
+
+    #[test]
+    fn parses_option_levels() {
+        assert!(matches!(
+            SplixCommand::parse(&arguments(&["setw", "-g", "mode-keys", "vi"])).unwrap(),
+            SplixCommand::SetOption {
+                global: true,
+                window: false,
+                value: Some(value),
+                ..
+            } if value == "vi"
+        ));
+        assert!(
+            SplixCommand::parse(&arguments(&["set", "-g", "-p", "remain-on-exit", "off"])).is_err()
+        );
+        assert!(SplixCommand::parse(&arguments(&["set", "-u", "prefix", "C-a"])).is_err());
+        assert!(SplixCommand::parse(&arguments(&["show", "-A", "-w", "-t", "work:1"])).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_commands() {
+        assert!(SplixCommand::parse(&arguments(&["frobnicate"])).is_err());
+    }
+}
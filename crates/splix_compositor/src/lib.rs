@@ -0,0 +1,171 @@
+use glam::UVec2;
+use splix_frame::Frame;
+use splix_id::PaneId;
+use splix_layout::Geometry;
+use splix_pane::Pane;
+use splix_window::Window;
+
+/// Lays the contents of a window out on a client's screen, above a status line.
+pub struct Compositor;
+
+/// Fills the parts of a client's screen that lie outside of a smaller window.
+const PADDING_CHARACTER: char = '·';
+const VERTICAL_SEPARATOR_CHARACTER: char = '│';
+const HORIZONTAL_SEPARATOR_CHARACTER: char = '─';
+const STATUS_LINE_HEIGHT: u32 = 1;
+
+impl Compositor {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The space left for windows on a screen of the given dimensions.
+    pub fn get_window_dimensions(&self, screen_dimensions: UVec2) -> UVec2 {
+        UVec2::new(
+            screen_dimensions.x,
+            screen_dimensions
+                .y
+                .saturating_sub(STATUS_LINE_HEIGHT)
+                .max(1),
+        )
+    }
+
+    /// The row of the status line on a screen of the given dimensions, if it has one.
+    pub fn get_status_line_row(&self, screen_dimensions: UVec2) -> Option<u32> {
+        (screen_dimensions.y > STATUS_LINE_HEIGHT).then(|| screen_dimensions.y - STATUS_LINE_HEIGHT)
+    }
+
+    /// Windows larger than the screen are cropped, smaller ones are padded.
+    pub fn compose(&self, window: &Window, dimensions: UVec2, status: &str) -> Frame {
+        let mut frame = Frame::new(dimensions);
+        let pane_geometries = window.get_pane_geometries();
+
+        self.draw_padding(&mut frame, window.get_dimensions());
+        self.draw_separators(&mut frame, window.get_dimensions(), &pane_geometries);
+        for (pane, geometry) in pane_geometries.iter() {
+            if let Some(pane) = window.get_pane(*pane) {
+                self.draw_pane(&mut frame, pane, geometry);
+                self.draw_copy_mode_position(&mut frame, pane, geometry);
+            }
+        }
+        self.draw_status_line(&mut frame, status);
+
+        frame
+    }
+
+    fn draw_padding(&self, frame: &mut Frame, window_dimensions: UVec2) {
+        let dimensions = self.get_window_dimensions(frame.get_dimensions());
+
+        for y in 0..dimensions.y {
+            for x in 0..dimensions.x {
+                if x >= window_dimensions.x || y >= window_dimensions.y {
+                    frame.set_cell(UVec2::new(x, y), PADDING_CHARACTER);
+                }
+            }
+        }
+    }
+
+    /// Every cell of the window not covered by a pane separates panes placed side by side, or
+    /// stacked ones.
+    fn draw_separators(
+        &self,
+        frame: &mut Frame,
+        window_dimensions: UVec2,
+        pane_geometries: &[(PaneId, Geometry)],
+    ) {
+        let dimensions = self
+            .get_window_dimensions(frame.get_dimensions())
+            .min(window_dimensions);
+        let is_covered = |x: u32, y: u32| {
+            pane_geometries.iter().any(|(_, geometry)| {
+                let position = UVec2::new(x, y);
+                position.cmpge(geometry.get_position()).all()
+                    && position
+                        .cmplt(geometry.get_position() + geometry.get_dimensions())
+                        .all()
+            })
+        };
+
+        for y in 0..dimensions.y {
+            for x in 0..dimensions.x {
+                if is_covered(x, y) {
+                    continue;
+                }
+
+                let between_side_by_side_panes =
+                    (x > 0 && is_covered(x - 1, y)) || is_covered(x + 1, y);
+                let separator = if between_side_by_side_panes {
+                    VERTICAL_SEPARATOR_CHARACTER
+                } else {
+                    HORIZONTAL_SEPARATOR_CHARACTER
+                };
+
+                frame.set_cell(UVec2::new(x, y), separator);
+            }
+        }
+    }
+
+    fn draw_pane(&self, frame: &mut Frame, pane: &Pane, geometry: &Geometry) {
+        let window_dimensions = self.get_window_dimensions(frame.get_dimensions());
+
+        for (y, line) in pane.get_visible_lines().iter().enumerate() {
+            if (y as u32) >= geometry.get_dimensions().y {
+                break;
+            }
+
+            for (x, cell) in line.get_cells().iter().enumerate() {
+                if (x as u32) >= geometry.get_dimensions().x {
+                    break;
+                }
+
+                let position = geometry.get_position() + UVec2::new(x as u32, y as u32);
+                if position.cmplt(window_dimensions).all() {
+                    frame.set_cell(position, cell.get_character());
+                }
+            }
+        }
+    }
+
+    /// Panes in copy mode show how far they are scrolled up in their top right corner, like
+    /// `[12/300]`.
+    fn draw_copy_mode_position(&self, frame: &mut Frame, pane: &Pane, geometry: &Geometry) {
+        let Some(copy_mode) = pane.get_copy_mode() else {
+            return;
+        };
+
+        let position = format!(
+            "[{}/{}]",
+            copy_mode.get_scroll_offset(),
+            pane.get_grid().get_history().len()
+        );
+        let window_dimensions = self.get_window_dimensions(frame.get_dimensions());
+        let width = position.chars().count() as u32;
+        let start = geometry.get_position().x + geometry.get_dimensions().x.saturating_sub(width);
+
+        for (index, c) in position.chars().enumerate() {
+            let position = UVec2::new(start + index as u32, geometry.get_position().y);
+            if position.cmplt(window_dimensions).all() {
+                frame.set_cell(position, c);
+            }
+        }
+    }
+
+    /// Screens too small to fit a status line below the window don't get one.
+    fn draw_status_line(&self, frame: &mut Frame, status: &str) {
+        let Some(y) = self.get_status_line_row(frame.get_dimensions()) else {
+            return;
+        };
+        let dimensions = frame.get_dimensions();
+
+        let mut status = status.chars();
+        for x in 0..dimensions.x {
+            frame.set_cell(UVec2::new(x, y), status.next().unwrap_or(' '));
+        }
+    }
+}
+
+impl Default for Compositor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
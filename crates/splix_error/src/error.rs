@@ -1,4 +1,4 @@
-use std::io;
+use std::{ffi::NulError, io, path::PathBuf, process::ExitStatus};
 
 use nix::errno::Errno;
 
@@ -16,11 +16,32 @@ pub enum Error {
     #[error("enter alternate terminal screen")]
     EnterAlternateTerminalScreen(#[source] io::Error),
 
+    #[error("enable mouse reporting")]
+    EnableMouseReporting(#[source] io::Error),
+
+    #[error("enable bracketed paste")]
+    EnableBracketedPaste(#[source] io::Error),
+
+    #[error("enable keyboard reporting")]
+    EnableKeyboardReporting(#[source] io::Error),
+
+    #[error("command contains a NUL byte")]
+    InvalidCommandString(#[source] NulError),
+
     #[error("failed forking a new child process in a PTY")]
     ForkChildProcessInPty(#[source] Errno),
 
-    #[error("failed spawning a terminal child process - WTF???")]
-    TerminalSpawnChild,
+    #[error("failed registering a PTY with the async runtime")]
+    RegisterPty(#[source] io::Error),
+
+    #[error("failed resolving a shell to execute")]
+    ResolveShell,
+
+    #[error("failed changing the working directory of a terminal child process")]
+    ChangeWorkingDirectory(#[source] Errno),
+
+    #[error("failed executing a command in a terminal child process")]
+    ExecuteCommand(#[source] Errno),
 
     #[error("failed reading from terminal")]
     ReadFromTerminal(#[source] io::Error),
@@ -28,9 +49,159 @@ pub enum Error {
     #[error("failed writing to terminal")]
     WriteToTerminal(#[source] io::Error),
 
+    #[error("failed resizing the terminal")]
+    ResizeTerminal(#[source] Errno),
+
     #[error("failed sending pane update")]
     SendPaneUpdate,
 
+    #[error("failed sending keys")]
+    SendKeys,
+
     #[error("failed retrieving the terminal size")]
     RetrieveTerminalSize,
+
+    #[error("failed creating the runtime directory")]
+    CreateRuntimeDirectory(#[source] io::Error),
+
+    #[error("runtime directory {0} must be a directory owned and only accessible by the user")]
+    InsecureRuntimeDirectory(PathBuf),
+
+    #[error("a server is already running on {0}")]
+    ServerAlreadyRunning(PathBuf),
+
+    #[error("{0} exists and isn't a socket")]
+    NotASocket(PathBuf),
+
+    #[error("failed binding the server socket")]
+    BindSocket(#[source] io::Error),
+
+    #[error("failed starting the server")]
+    StartServer(#[source] io::Error),
+
+    #[error("failed connecting to the server")]
+    ConnectToServer(#[source] io::Error),
+
+    #[error("no server running on {}", .0.display())]
+    NoServer(PathBuf),
+
+    #[error("the server closed the connection")]
+    ConnectionClosed,
+
+    #[error("the server didn't answer in time")]
+    ServerTimeout,
+
+    #[error("failed reading a message")]
+    ReadMessage(#[source] io::Error),
+
+    #[error("failed writing a message")]
+    WriteMessage(#[source] io::Error),
+
+    #[error("failed decoding a message")]
+    DecodeMessage,
+
+    #[error(
+        "protocol version {found} doesn't match version {expected}, restart the server after \
+         upgrading splix"
+    )]
+    ProtocolVersionMismatch { expected: u32, found: u32 },
+
+    #[error("expected a hello message")]
+    ExpectedHello,
+
+    #[error("{0}")]
+    ParseCommand(String),
+
+    #[error("unterminated quote: {0}")]
+    UnterminatedQuote(String),
+
+    #[error("duplicate session: {0}")]
+    DuplicateSession(String),
+
+    #[error("no sessions")]
+    NoSessions,
+
+    #[error("invalid line number: {0}")]
+    InvalidLineNumber(String),
+
+    #[error("no buffer {0}")]
+    BufferNotFound(String),
+
+    #[error("no buffers")]
+    NoBuffers,
+
+    #[error("unknown key: {0}")]
+    InvalidKey(String),
+
+    #[error("invalid window size: {0}, expected smallest, largest or latest")]
+    InvalidWindowSize(String),
+
+    #[error("invalid target: {0}")]
+    InvalidTarget(String),
+
+    #[error("can't find session {0}")]
+    SessionNotFound(String),
+
+    #[error("can't find window {0}")]
+    WindowNotFound(String),
+
+    #[error("can't find pane {0}")]
+    PaneNotFound(String),
+
+    #[error("pane {0} still active")]
+    PaneStillActive(String),
+
+    #[error("pane {0} is not in copy mode")]
+    NotInCopyMode(String),
+
+    #[error("unknown copy mode command: {0}")]
+    UnknownCopyModeCommand(String),
+
+    #[error("can't find key table {0}")]
+    KeyTableNotFound(String),
+
+    #[error("no current client")]
+    NoCurrentClient,
+
+    #[error("unknown option: {0}")]
+    UnknownOption(String),
+
+    #[error("invalid value for option {option}: {value}")]
+    InvalidOptionValue { option: String, value: String },
+
+    #[error("option {option} can't be set per {scope}")]
+    InvalidOptionScope { option: String, scope: String },
+
+    #[error("failed reading {}", .0.display())]
+    ReadConfigFile(PathBuf, #[source] io::Error),
+
+    #[error("{}", .0.join("\n"))]
+    ConfigFile(Vec<String>),
+
+    #[error("{0}")]
+    Server(String),
+
+    #[error("failed reading a control mode command")]
+    ReadControlCommand(#[source] io::Error),
+
+    #[error("failed writing control mode output")]
+    WriteControlOutput(#[source] io::Error),
+
+    #[error("failed handling a signal")]
+    HandleSignal(#[source] io::Error),
+
+    #[error("failed resolving the home directory")]
+    ResolveHomeDirectory,
+
+    #[error("failed creating the terminfo directory")]
+    CreateTerminfoDirectory(#[source] io::Error),
+
+    #[error("failed writing the terminfo source")]
+    WriteTerminfoSource(#[source] io::Error),
+
+    #[error("failed executing tic")]
+    ExecuteTic(#[source] io::Error),
+
+    #[error("failed compiling the terminfo entry, tic exited with {0}")]
+    CompileTerminfo(ExitStatus),
 }
@@ -0,0 +1,47 @@
+use tokio::sync::mpsc::Sender;
+
+use splix_id::ClientId;
+use splix_protocol::{ClientMessage, ServerMessage};
+
+#[derive(Debug)]
+pub struct ClientConnectEvent {
+    client: ClientId,
+    message_sender: Sender<ServerMessage>,
+}
+
+impl ClientConnectEvent {
+    pub fn new(client: ClientId, message_sender: Sender<ServerMessage>) -> Self {
+        Self {
+            client,
+            message_sender,
+        }
+    }
+
+    pub fn get_client(&self) -> ClientId {
+        self.client
+    }
+
+    pub fn get_message_sender(&self) -> &Sender<ServerMessage> {
+        &self.message_sender
+    }
+}
+
+#[derive(Debug)]
+pub struct ClientMessageEvent {
+    client: ClientId,
+    message: ClientMessage,
+}
+
+impl ClientMessageEvent {
+    pub fn new(client: ClientId, message: ClientMessage) -> Self {
+        Self { client, message }
+    }
+
+    pub fn get_client(&self) -> ClientId {
+        self.client
+    }
+
+    pub fn get_message(&self) -> &ClientMessage {
+        &self.message
+    }
+}
@@ -0,0 +1,51 @@
+use tokio::sync::mpsc::Sender;
+
+use splix_id::ClientId;
+use splix_rpc::RpcRequest;
+
+#[derive(Debug)]
+pub struct JsonConnectEvent {
+    connection: ClientId,
+    line_sender: Sender<String>,
+}
+
+impl JsonConnectEvent {
+    pub fn new(connection: ClientId, line_sender: Sender<String>) -> Self {
+        Self {
+            connection,
+            line_sender,
+        }
+    }
+
+    pub fn get_connection(&self) -> ClientId {
+        self.connection
+    }
+
+    /// Sends lines of JSON to the connection.
+    pub fn get_line_sender(&self) -> &Sender<String> {
+        &self.line_sender
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonRequestEvent {
+    connection: ClientId,
+    request: RpcRequest,
+}
+
+impl JsonRequestEvent {
+    pub fn new(connection: ClientId, request: RpcRequest) -> Self {
+        Self {
+            connection,
+            request,
+        }
+    }
+
+    pub fn get_connection(&self) -> ClientId {
+        self.connection
+    }
+
+    pub fn get_request(&self) -> &RpcRequest {
+        &self.request
+    }
+}
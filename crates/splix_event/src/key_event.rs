@@ -0,0 +1,73 @@
+use splix_key::{Key, MouseEvent};
+
+use crate::Paste;
+
+/// A key read from the user's terminal, a mouse event it reported or part of a bracketed paste,
+/// along with the bytes it was sent as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyEvent {
+    key: Option<Key>,
+    mouse: Option<MouseEvent>,
+    paste: Option<Paste>,
+    enhanced: bool,
+    bytes: Vec<u8>,
+}
+
+impl KeyEvent {
+    pub fn new(key: Option<Key>, bytes: Vec<u8>) -> Self {
+        Self {
+            key,
+            mouse: None,
+            paste: None,
+            enhanced: false,
+            bytes,
+        }
+    }
+
+    pub fn with_mouse(self, mouse: MouseEvent) -> Self {
+        Self {
+            mouse: Some(mouse),
+            ..self
+        }
+    }
+
+    pub fn with_paste(self, paste: Paste) -> Self {
+        Self {
+            paste: Some(paste),
+            ..self
+        }
+    }
+
+    pub fn with_enhanced(self) -> Self {
+        Self {
+            enhanced: true,
+            ..self
+        }
+    }
+
+    /// `None` for sequences that don't stand for a key splix knows, like mouse reports or
+    /// invalid UTF-8, which are still passed on as they are.
+    pub fn get_key(&self) -> Option<Key> {
+        self.key
+    }
+
+    pub fn get_mouse(&self) -> Option<MouseEvent> {
+        self.mouse
+    }
+
+    pub fn get_paste(&self) -> Option<Paste> {
+        self.paste
+    }
+
+    /// Whether the key came in the sequences of the kitty keyboard protocol or modifyOtherKeys,
+    /// which programs that didn't ask for them can't take as they are. Also set for such
+    /// sequences that didn't decode into a key.
+    pub fn is_enhanced(&self) -> bool {
+        self.enhanced
+    }
+
+    /// Exactly what the terminal sent, to be forwarded to panes unchanged.
+    pub fn get_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
@@ -1,9 +1,26 @@
-mod pane_update_event;
+mod client_event;
+mod json_event;
+mod key_event;
+mod pane_event;
+mod paste;
 
-pub use pane_update_event::{GridUpdate, PaneUpdateEvent};
+pub use client_event::{ClientConnectEvent, ClientMessageEvent};
+pub use json_event::{JsonConnectEvent, JsonRequestEvent};
+pub use key_event::KeyEvent;
+pub use pane_event::{GridUpdate, PaneExitEvent, PaneUpdateEvent};
+pub use paste::Paste;
+
+use splix_id::ClientId;
 
 #[derive(Debug)]
 pub enum Event {
     PaneUpdate(PaneUpdateEvent),
-    Input(u8),
+    PaneExit(PaneExitEvent),
+    ClientConnect(ClientConnectEvent),
+    ClientMessage(ClientMessageEvent),
+    ClientDisconnect(ClientId),
+    /// Connections to the JSON socket are numbered separately from clients.
+    JsonConnect(JsonConnectEvent),
+    JsonRequest(JsonRequestEvent),
+    JsonDisconnect(ClientId),
 }
@@ -0,0 +1,59 @@
+use splix_id::PaneId;
+
+#[derive(Debug)]
+pub enum GridUpdate {
+    AppendChar(char),
+    NewLine,
+}
+
+/// Output of the program in a pane. The generation tells programs a pane was respawned with
+/// apart, as the output of the previous one may still be on its way.
+#[derive(Debug)]
+pub struct PaneUpdateEvent {
+    pane: PaneId,
+    generation: u64,
+    grid_update: GridUpdate,
+}
+
+impl PaneUpdateEvent {
+    pub fn new(pane: PaneId, generation: u64, grid_update: GridUpdate) -> Self {
+        Self {
+            pane,
+            generation,
+            grid_update,
+        }
+    }
+
+    pub fn get_pane(&self) -> PaneId {
+        self.pane
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn get_grid_update(&self) -> &GridUpdate {
+        &self.grid_update
+    }
+}
+
+/// The program in a pane exited, which only counts for the generation of the pane it ran in.
+#[derive(Debug)]
+pub struct PaneExitEvent {
+    pane: PaneId,
+    generation: u64,
+}
+
+impl PaneExitEvent {
+    pub fn new(pane: PaneId, generation: u64) -> Self {
+        Self { pane, generation }
+    }
+
+    pub fn get_pane(&self) -> PaneId {
+        self.pane
+    }
+
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+}
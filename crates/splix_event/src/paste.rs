@@ -0,0 +1,10 @@
+/// The part of a bracketed paste a key event holds, which is typed into the pane as it is rather
+/// than looked up in key tables.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Paste {
+    /// The marker sent before the pasted text.
+    Start,
+    Text,
+    /// The marker sent after the pasted text.
+    End,
+}
@@ -0,0 +1,308 @@
+use std::cmp::Ordering;
+
+use crate::{FormatVariables, modifier::Modifier};
+
+/// A template like `#{session_name}:#{window_index}`, expanded against a set of variables.
+///
+/// - `#{name}` expands to a variable, or to nothing if it isn't set.
+/// - `#S`, `#I`, `#W`, `#F`, `#P` and `#D` are short for the session name, the window index, name
+///   and flags, and the pane index and ID.
+/// - `#{?condition,then,else}` expands `then` if the condition is neither empty nor `0`, where the
+///   condition is a variable name or a format itself.
+/// - `#{==:a,b}`, `!=`, `<`, `>`, `<=` and `>=` compare two formats, as numbers if both are, and
+///   `||` and `&&` combine two conditions, all expanding to `1` or `0`.
+/// - `#{=N:name}` truncates a variable to `N` characters, keeping the end if `N` is negative, and
+///   `#{pN:name}` pads it to `N` characters, on the left if `N` is negative.
+/// - `##`, `#,` and `#}` stand for the character itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Format {
+    template: String,
+}
+
+const MAXIMUM_PAD_WIDTH: u64 = u16::MAX as u64;
+
+impl Format {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+        }
+    }
+
+    pub fn get_template(&self) -> &str {
+        &self.template
+    }
+
+    pub fn expand(&self, variables: &FormatVariables) -> String {
+        expand(&self.template, variables)
+    }
+}
+
+fn expand(template: &str, variables: &FormatVariables) -> String {
+    let mut output = String::new();
+    let mut rest = template;
+
+    while let Some(position) = rest.find('#') {
+        output.push_str(&rest[..position]);
+        rest = &rest[position + 1..];
+
+        match rest.chars().next() {
+            Some('{') => match find_closing_brace(&rest[1..]) {
+                Some(end) => {
+                    output.push_str(&expand_expression(&rest[1..end + 1], variables));
+                    rest = &rest[end + 2..];
+                }
+                // Unterminated expressions are kept as they are.
+                None => output.push('#'),
+            },
+            Some(character @ ('#' | ',' | '}')) => {
+                output.push(character);
+                rest = &rest[1..];
+            }
+            Some(character) if let Some(name) = shorthand_variable(character) => {
+                output.push_str(variables.get(name).unwrap_or_default());
+                rest = &rest[1..];
+            }
+            _ => output.push('#'),
+        }
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Expands what's between `#{` and `}`.
+fn expand_expression(expression: &str, variables: &FormatVariables) -> String {
+    if let Some(arguments) = expression.strip_prefix('?') {
+        let arguments = split_arguments(arguments);
+        let branch = match evaluate_condition(arguments[0], variables) {
+            true => arguments.get(1),
+            false => arguments.get(2),
+        };
+
+        return branch
+            .map(|branch| expand(branch, variables))
+            .unwrap_or_default();
+    }
+
+    if let Some((modifier, argument)) = expression.split_once(':')
+        && let Some(modifier) = Modifier::parse(modifier)
+    {
+        return apply_modifier(modifier, argument, variables);
+    }
+
+    variables.get(expression).unwrap_or_default().to_string()
+}
+
+fn apply_modifier(modifier: Modifier, argument: &str, variables: &FormatVariables) -> String {
+    match modifier {
+        Modifier::Truncate(width) => {
+            let value: Vec<char> = evaluate_operand(argument, variables).chars().collect();
+            let kept = (width.unsigned_abs() as usize).min(value.len());
+
+            match width < 0 {
+                true => value[value.len() - kept..].iter().collect(),
+                false => value[..kept].iter().collect(),
+            }
+        }
+        Modifier::Pad(width) => {
+            let value = evaluate_operand(argument, variables);
+            // Nothing is ever shown that wide, and larger widths would only exhaust memory.
+            let padded_width = width.unsigned_abs().min(MAXIMUM_PAD_WIDTH) as usize;
+            let padding = " ".repeat(padded_width.saturating_sub(value.chars().count()));
+
+            match width < 0 {
+                true => padding + &value,
+                false => value + &padding,
+            }
+        }
+        _ => {
+            let arguments = split_arguments(argument);
+            let [left, right] = arguments[..] else {
+                return String::new();
+            };
+
+            let result = match modifier {
+                Modifier::Or => {
+                    evaluate_condition(left, variables) || evaluate_condition(right, variables)
+                }
+                Modifier::And => {
+                    evaluate_condition(left, variables) && evaluate_condition(right, variables)
+                }
+                _ => {
+                    let ordering = compare(&expand(left, variables), &expand(right, variables));
+                    match modifier {
+                        Modifier::Equal => ordering.is_eq(),
+                        Modifier::NotEqual => ordering.is_ne(),
+                        Modifier::Less => ordering.is_lt(),
+                        Modifier::Greater => ordering.is_gt(),
+                        Modifier::LessOrEqual => ordering.is_le(),
+                        _ => ordering.is_ge(),
+                    }
+                }
+            };
+
+            String::from(if result { "1" } else { "0" })
+        }
+    }
+}
+
+/// Operands are variable names, unless they're formats themselves.
+fn evaluate_operand(operand: &str, variables: &FormatVariables) -> String {
+    match operand.contains('#') {
+        true => expand(operand, variables),
+        false => variables.get(operand).unwrap_or_default().to_string(),
+    }
+}
+
+fn evaluate_condition(condition: &str, variables: &FormatVariables) -> bool {
+    let value = evaluate_operand(condition, variables);
+    !value.is_empty() && value != "0"
+}
+
+/// Numbers compare by value, anything else as text.
+fn compare(left: &str, right: &str) -> Ordering {
+    match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => left.partial_cmp(&right).unwrap_or(Ordering::Equal),
+        _ => left.cmp(right),
+    }
+}
+
+/// Splits at the commas that aren't escaped or part of a nested expression.
+fn split_arguments(arguments: &str) -> Vec<&str> {
+    let mut split = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let bytes = arguments.as_bytes();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'#' if bytes.get(index + 1) == Some(&b'{') => {
+                depth += 1;
+                index += 1;
+            }
+            b'#' => index += 1,
+            b'}' if depth > 0 => depth -= 1,
+            b',' if depth == 0 => {
+                split.push(&arguments[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    split.push(&arguments[start..]);
+    split
+}
+
+/// Returns the position of the `}` closing an expression that started right before `text`.
+fn find_closing_brace(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 0;
+    let mut index = 0;
+
+    while index < bytes.len() {
+        match bytes[index] {
+            b'#' if bytes.get(index + 1) == Some(&b'{') => {
+                depth += 1;
+                index += 1;
+            }
+            b'#' => index += 1,
+            b'}' if depth == 0 => return Some(index),
+            b'}' => depth -= 1,
+            _ => {}
+        }
+        index += 1;
+    }
+
+    None
+}
+
+fn shorthand_variable(character: char) -> Option<&'static str> {
+    let name = match character {
+        'S' => "session_name",
+        'I' => "window_index",
+        'W' => "window_name",
+        'F' => "window_flags",
+        'P' => "pane_index",
+        'D' => "pane_id",
+        _ => return None,
+    };
+
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Format, FormatVariables};
+
+    fn variables() -> FormatVariables {
+        FormatVariables::new()
+            .with_variable("session_name", "work")
+            .with_variable("window_index", 2)
+            .with_variable("window_name", "editor")
+            .with_variable("pane_id", "%7")
+            .with_flag("pane_active", true)
+            .with_flag("pane_dead", false)
+    }
+
+    fn expand(template: &str) -> String {
+        Format::new(template).expand(&variables())
+    }
+
+    #[test]
+    fn expands_variables() {
+        assert_eq!(expand("#{session_name}:#{window_index}"), "work:2");
+        assert_eq!(expand("#S:#I.#W #D"), "work:2.editor %7");
+        assert_eq!(expand("[#{unknown}]"), "[]");
+    }
+
+    #[test]
+    fn keeps_escapes_and_stray_characters() {
+        assert_eq!(expand("## #, #} #x #"), "# , } #x #");
+        assert_eq!(expand("#{session_name"), "#{session_name");
+    }
+
+    #[test]
+    fn expands_conditionals() {
+        assert_eq!(expand("#{?pane_active,*,-}"), "*");
+        assert_eq!(expand("#{?pane_dead,dead,alive}"), "alive");
+        assert_eq!(expand("#{?unknown,set}"), "");
+        assert_eq!(
+            expand("#{?pane_active,#{window_name}#,#{pane_id},}"),
+            "editor,%7"
+        );
+        assert_eq!(
+            expand("#{?#{==:#{session_name},work},at work,elsewhere}"),
+            "at work"
+        );
+    }
+
+    #[test]
+    fn compares() {
+        assert_eq!(expand("#{==:#{session_name},work}"), "1");
+        assert_eq!(expand("#{!=:#S,work}"), "0");
+        assert_eq!(expand("#{<:#{window_index},10}"), "1");
+        assert_eq!(expand("#{<:abc,abd}"), "1");
+        assert_eq!(expand("#{>=:2,2.0}"), "1");
+        assert_eq!(expand("#{||:pane_dead,pane_active}"), "1");
+        assert_eq!(expand("#{&&:pane_dead,pane_active}"), "0");
+        assert_eq!(expand("#{==:only one}"), "");
+    }
+
+    #[test]
+    fn truncates_and_pads() {
+        assert_eq!(expand("#{=3:window_name}"), "edi");
+        assert_eq!(expand("#{=-3:window_name}"), "tor");
+        assert_eq!(expand("#{=10:window_name}"), "editor");
+        assert_eq!(expand("[#{p8:session_name}]"), "[work    ]");
+        assert_eq!(expand("[#{p-8:session_name}]"), "[    work]");
+        assert_eq!(expand("[#{p2:session_name}]"), "[work]");
+        assert_eq!(
+            expand("#{p9223372036854775807:session_name}").len(),
+            u16::MAX as usize
+        );
+        assert_eq!(expand("#{=2:#{session_name}#{window_index}}"), "wo");
+    }
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+/// The values `#{name}` expands to in a [`Format`](crate::Format).
+#[derive(Clone, Debug, Default)]
+pub struct FormatVariables {
+    variables: HashMap<String, String>,
+}
+
+impl FormatVariables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_variable(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.variables.insert(name.into(), value.to_string());
+        self
+    }
+
+    /// Flags are `1` when set and `0` otherwise, like in comparisons.
+    pub fn with_flag(self, name: impl Into<String>, flag: bool) -> Self {
+        self.with_variable(name, if flag { "1" } else { "0" })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.variables.get(name).map(String::as_str)
+    }
+}
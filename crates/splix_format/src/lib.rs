@@ -0,0 +1,6 @@
+mod format;
+mod format_variables;
+mod modifier;
+
+pub use format::Format;
+pub use format_variables::FormatVariables;
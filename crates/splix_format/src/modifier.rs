@@ -0,0 +1,42 @@
+/// What comes before the colon in `#{==:a,b}`, `#{=10:name}` or `#{p-5:name}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Modifier {
+    Equal,
+    NotEqual,
+    Less,
+    Greater,
+    LessOrEqual,
+    GreaterOrEqual,
+    Or,
+    And,
+    /// Keeps that many characters from the start, or from the end if negative.
+    Truncate(i64),
+    /// Pads with spaces on the right to that width, or on the left if negative.
+    Pad(i64),
+}
+
+impl Modifier {
+    pub(crate) fn parse(modifier: &str) -> Option<Self> {
+        let modifier = match modifier {
+            "==" => Self::Equal,
+            "!=" => Self::NotEqual,
+            "<" => Self::Less,
+            ">" => Self::Greater,
+            "<=" => Self::LessOrEqual,
+            ">=" => Self::GreaterOrEqual,
+            "||" => Self::Or,
+            "&&" => Self::And,
+            _ => {
+                if let Some(width) = modifier.strip_prefix('=') {
+                    Self::Truncate(width.parse().ok()?)
+                } else if let Some(width) = modifier.strip_prefix('p') {
+                    Self::Pad(width.parse().ok()?)
+                } else {
+                    return None;
+                }
+            }
+        };
+
+        Some(modifier)
+    }
+}
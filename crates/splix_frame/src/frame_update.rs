@@ -0,0 +1,24 @@
+/// The lines of a frame that changed since the previous frame sent to a client.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameUpdate {
+    lines: Vec<(u32, Vec<char>)>,
+}
+
+impl FrameUpdate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_line(mut self, y: u32, line: Vec<char>) -> Self {
+        self.lines.push((y, line));
+        self
+    }
+
+    pub fn get_lines(&self) -> &[(u32, Vec<char>)] {
+        &self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+}
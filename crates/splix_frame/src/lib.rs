@@ -0,0 +1,120 @@
+mod frame_update;
+
+use glam::UVec2;
+
+pub use frame_update::FrameUpdate;
+
+/// The characters covering a client's screen.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    dimensions: UVec2,
+    cells: Vec<char>,
+}
+
+impl Frame {
+    pub fn new(dimensions: UVec2) -> Self {
+        Self {
+            dimensions,
+            cells: vec![' '; dimensions.x as usize * dimensions.y as usize],
+        }
+    }
+
+    /// Returns `None` if the number of cells doesn't match the dimensions.
+    pub fn from_cells(dimensions: UVec2, cells: Vec<char>) -> Option<Self> {
+        ((dimensions.x as usize).checked_mul(dimensions.y as usize) == Some(cells.len()))
+            .then_some(Self { dimensions, cells })
+    }
+
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+
+    pub fn get_cells(&self) -> &[char] {
+        &self.cells
+    }
+
+    pub fn get_line(&self, y: u32) -> &[char] {
+        let start = self.index_from_position(UVec2::new(0, y));
+        &self.cells[start..start + self.dimensions.x as usize]
+    }
+
+    /// Positions outside of the frame are ignored.
+    pub fn set_cell(&mut self, position: UVec2, character: char) {
+        if position.x >= self.dimensions.x || position.y >= self.dimensions.y {
+            return;
+        }
+
+        let index = self.index_from_position(position);
+        self.cells[index] = character;
+    }
+
+    /// Returns the lines that differ from `previous`, or `None` if the frames have different
+    /// dimensions and can't be compared line by line.
+    pub fn diff(&self, previous: &Frame) -> Option<FrameUpdate> {
+        if self.dimensions != previous.dimensions {
+            return None;
+        }
+
+        Some(
+            (0..self.dimensions.y)
+                .filter(|y| self.get_line(*y) != previous.get_line(*y))
+                .fold(FrameUpdate::new(), |update, y| {
+                    update.with_line(y, self.get_line(y).to_vec())
+                }),
+        )
+    }
+
+    /// Lines that don't fit the frame are clipped, like cells passed to `set_cell`.
+    pub fn apply(&mut self, update: &FrameUpdate) {
+        for (y, line) in update.get_lines().iter() {
+            for (x, character) in line.iter().enumerate() {
+                self.set_cell(UVec2::new(x as u32, *y), *character);
+            }
+        }
+    }
+
+    fn index_from_position(&self, position: UVec2) -> usize {
+        self.dimensions.x as usize * position.y as usize + position.x as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use super::{Frame, FrameUpdate};
+
+    #[test]
+    fn diff_contains_only_changed_lines() {
+        let previous = Frame::new(UVec2::new(3, 3));
+        let mut frame = previous.clone();
+        frame.set_cell(UVec2::new(1, 1), 'x');
+
+        assert_eq!(
+            frame.diff(&previous),
+            Some(FrameUpdate::new().with_line(1, vec![' ', 'x', ' ']))
+        );
+        assert_eq!(frame.diff(&frame), Some(FrameUpdate::new()));
+    }
+
+    #[test]
+    fn diff_of_resized_frame_is_none() {
+        let previous = Frame::new(UVec2::new(3, 3));
+        let frame = Frame::new(UVec2::new(4, 3));
+
+        assert_eq!(frame.diff(&previous), None);
+    }
+
+    #[test]
+    fn applying_diff_reproduces_frame() {
+        let previous = Frame::new(UVec2::new(3, 2));
+        let mut frame = previous.clone();
+        frame.set_cell(UVec2::new(0, 0), 'a');
+        frame.set_cell(UVec2::new(2, 1), 'b');
+
+        let mut updated = previous.clone();
+        updated.apply(&frame.diff(&previous).unwrap());
+
+        assert_eq!(updated, frame);
+    }
+}
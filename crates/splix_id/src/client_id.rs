@@ -0,0 +1,22 @@
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientId {
+    id: usize,
+}
+
+impl ClientId {
+    pub fn new(id: usize) -> Self {
+        Self { id }
+    }
+
+    pub fn get(&self) -> usize {
+        self.id
+    }
+}
+
+impl fmt::Display for ClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "client-{}", self.id)
+    }
+}
@@ -0,0 +1,35 @@
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{PaneId, SessionId, WindowId};
+
+/// Hands out IDs that are unique across the whole server, so `$N`, `@N` and `%N` address the
+/// same session, window or pane from anywhere.
+///
+/// Clones share their counters.
+#[derive(Clone, Debug, Default)]
+pub struct IdAllocator {
+    next_session_id: Arc<AtomicUsize>,
+    next_window_id: Arc<AtomicUsize>,
+    next_pane_id: Arc<AtomicUsize>,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn allocate_session_id(&self) -> SessionId {
+        SessionId::new(self.next_session_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn allocate_window_id(&self, session: SessionId) -> WindowId {
+        WindowId::new(self.next_window_id.fetch_add(1, Ordering::Relaxed), session)
+    }
+
+    pub fn allocate_pane_id(&self, window: WindowId) -> PaneId {
+        PaneId::new(self.next_pane_id.fetch_add(1, Ordering::Relaxed), window)
+    }
+}
@@ -1,7 +1,23 @@
+mod client_id;
+mod id_allocator;
+mod pane_direction;
+mod pane_edge;
 mod pane_id;
+mod pane_target;
 mod session_id;
+mod session_target;
+mod target;
 mod window_id;
+mod window_target;
 
+pub use client_id::ClientId;
+pub use id_allocator::IdAllocator;
+pub use pane_direction::PaneDirection;
+pub use pane_edge::PaneEdge;
 pub use pane_id::PaneId;
+pub use pane_target::PaneTarget;
 pub use session_id::SessionId;
+pub use session_target::SessionTarget;
+pub use target::Target;
 pub use window_id::WindowId;
+pub use window_target::WindowTarget;
@@ -0,0 +1,8 @@
+/// Where a neighboring pane is found, relative to another one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaneDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
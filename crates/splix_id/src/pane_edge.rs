@@ -0,0 +1,12 @@
+/// Which side of a window a pane is found at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaneEdge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
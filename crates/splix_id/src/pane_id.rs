@@ -1,6 +1,8 @@
+use std::fmt;
+
 use crate::WindowId;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct PaneId {
     id: usize,
     window: WindowId,
@@ -19,3 +21,9 @@ impl PaneId {
         self.window
     }
 }
+
+impl fmt::Display for PaneId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "%{}", self.id)
+    }
+}
@@ -0,0 +1,58 @@
+use crate::{PaneDirection, PaneEdge};
+
+/// The pane part of a target.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaneTarget {
+    /// `%N`
+    Id(usize),
+    /// The position of the pane in its window, from the top left.
+    Index(usize),
+    /// `{last}` or `!`, the previously active pane.
+    Last,
+    /// `{next}` or `+`, or `+N` to skip panes, counting from the active pane.
+    Next(usize),
+    /// `{previous}` or `-`, or `-N` to skip panes, counting from the active pane.
+    Previous(usize),
+    /// `{top}`, `{bottom}`, `{left}`, `{right}` and the corners like `{top-left}`.
+    Edge(PaneEdge),
+    /// `{up-of}`, `{down-of}`, `{left-of}` and `{right-of}`, the neighbors of the active pane.
+    Neighbor(PaneDirection),
+}
+
+impl PaneTarget {
+    pub fn parse(target: &str) -> splix_error::Result<Option<Self>> {
+        let pane_target = match target {
+            "" => return Ok(None),
+            "{last}" | "!" => Self::Last,
+            "{next}" | "+" => Self::Next(1),
+            "{previous}" | "-" => Self::Previous(1),
+            "{top}" => Self::Edge(PaneEdge::Top),
+            "{bottom}" => Self::Edge(PaneEdge::Bottom),
+            "{left}" => Self::Edge(PaneEdge::Left),
+            "{right}" => Self::Edge(PaneEdge::Right),
+            "{top-left}" => Self::Edge(PaneEdge::TopLeft),
+            "{top-right}" => Self::Edge(PaneEdge::TopRight),
+            "{bottom-left}" => Self::Edge(PaneEdge::BottomLeft),
+            "{bottom-right}" => Self::Edge(PaneEdge::BottomRight),
+            "{up-of}" => Self::Neighbor(PaneDirection::Up),
+            "{down-of}" => Self::Neighbor(PaneDirection::Down),
+            "{left-of}" => Self::Neighbor(PaneDirection::Left),
+            "{right-of}" => Self::Neighbor(PaneDirection::Right),
+            _ => {
+                if let Some(Ok(id)) = target.strip_prefix('%').map(str::parse) {
+                    Self::Id(id)
+                } else if let Some(Ok(offset)) = target.strip_prefix('+').map(str::parse) {
+                    Self::Next(offset)
+                } else if let Some(Ok(offset)) = target.strip_prefix('-').map(str::parse) {
+                    Self::Previous(offset)
+                } else if let Ok(index) = target.parse() {
+                    Self::Index(index)
+                } else {
+                    return Err(splix_error::Error::InvalidTarget(target.to_string()));
+                }
+            }
+        };
+
+        Ok(Some(pane_target))
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, Debug)]
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct SessionId {
     id: usize,
 }
@@ -12,3 +14,9 @@ impl SessionId {
         self.id
     }
 }
+
+impl fmt::Display for SessionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "${}", self.id)
+    }
+}
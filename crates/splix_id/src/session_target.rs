@@ -0,0 +1,21 @@
+/// The session part of a target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SessionTarget {
+    /// `$N`
+    Id(usize),
+    /// A session's name, or the start of it.
+    Name(String),
+}
+
+impl SessionTarget {
+    pub fn parse(target: &str) -> Option<Self> {
+        if target.is_empty() {
+            return None;
+        }
+
+        match target.strip_prefix('$').map(str::parse) {
+            Some(Ok(id)) => Some(Self::Id(id)),
+            _ => Some(Self::Name(target.to_string())),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{PaneTarget, SessionTarget, WindowTarget};
+
+/// What the `-t` argument of a command addresses, written as `session:window.pane`.
+///
+/// Every part may be omitted, leaving the choice to whoever resolves the target, usually the
+/// active session, window or pane. IDs like `$1`, `@3` and `%7` address a session, window or
+/// pane on their own.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Target {
+    session: Option<SessionTarget>,
+    window: Option<WindowTarget>,
+    pane: Option<PaneTarget>,
+}
+
+impl Target {
+    pub fn new(
+        session: Option<SessionTarget>,
+        window: Option<WindowTarget>,
+        pane: Option<PaneTarget>,
+    ) -> Self {
+        Self {
+            session,
+            window,
+            pane,
+        }
+    }
+
+    /// Parses the target of a command acting on a session, where anything after a colon is
+    /// ignored.
+    pub fn parse_session(target: &str) -> splix_error::Result<Self> {
+        if let Some(target) = Self::parse_id(target)? {
+            return Ok(target);
+        }
+
+        let session = target
+            .split_once(':')
+            .map_or(target, |(session, _)| session);
+        Ok(Self::new(SessionTarget::parse(session), None, None))
+    }
+
+    /// Parses the target of a command acting on a window, which is a window of the active session
+    /// unless a session is given before a colon.
+    pub fn parse_window(target: &str) -> splix_error::Result<Self> {
+        if let Some(target) = Self::parse_id(target)? {
+            return Ok(target);
+        }
+
+        let (session, window) = match target.split_once(':') {
+            Some((session, window)) => (SessionTarget::parse(session), window),
+            None => (None, target),
+        };
+
+        Ok(Self::new(session, WindowTarget::parse(window)?, None))
+    }
+
+    /// Parses the target of a command acting on a pane, which is a pane of the window before a
+    /// period.
+    ///
+    /// Without a colon or period, targets naming panes relative to the active one, like `!` or
+    /// `{left}`, are taken as panes and anything else as a window.
+    pub fn parse_pane(target: &str) -> splix_error::Result<Self> {
+        if let Some(target) = Self::parse_id(target)? {
+            return Ok(target);
+        }
+
+        let (session, rest) = match target.split_once(':') {
+            Some((session, rest)) => (SessionTarget::parse(session), Some(rest)),
+            None => (None, None),
+        };
+
+        let (window, pane) = match (rest, target.rsplit_once('.')) {
+            (Some(rest), _) => rest.rsplit_once('.').unwrap_or((rest, "")),
+            (None, Some((window, pane))) => (window, pane),
+            (None, None) => match PaneTarget::parse(target) {
+                Ok(Some(pane)) if !matches!(pane, PaneTarget::Index(_)) => {
+                    return Ok(Self::new(None, None, Some(pane)));
+                }
+                _ => (target, ""),
+            },
+        };
+
+        Ok(Self::new(
+            session,
+            WindowTarget::parse(window)?,
+            PaneTarget::parse(pane)?,
+        ))
+    }
+
+    pub fn get_session(&self) -> Option<&SessionTarget> {
+        self.session.as_ref()
+    }
+
+    pub fn get_window(&self) -> Option<&WindowTarget> {
+        self.window.as_ref()
+    }
+
+    pub fn get_pane(&self) -> Option<PaneTarget> {
+        self.pane
+    }
+
+    /// `$N`, `@N` and `%N` are unique across the server, so they need no other part.
+    fn parse_id(target: &str) -> splix_error::Result<Option<Self>> {
+        if target.contains([':', '.']) {
+            return Ok(None);
+        }
+
+        let target = match target.chars().next() {
+            Some('$') if target.len() > 1 => Self::new(SessionTarget::parse(target), None, None),
+            Some('@') => match WindowTarget::parse(target)? {
+                Some(window @ WindowTarget::Id(_)) => Self::new(None, Some(window), None),
+                _ => return Err(splix_error::Error::InvalidTarget(target.to_string())),
+            },
+            Some('%') => Self::new(None, None, PaneTarget::parse(target)?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(target))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PaneDirection, PaneEdge, PaneTarget, SessionTarget, Target, WindowTarget};
+
+    fn name(name: &str) -> String {
+        name.to_string()
+    }
+
+    #[test]
+    fn parses_ids_on_their_own() {
+        assert_eq!(
+            Target::parse_pane("%7").unwrap(),
+            Target::new(None, None, Some(PaneTarget::Id(7)))
+        );
+        assert_eq!(
+            Target::parse_pane("@3").unwrap(),
+            Target::new(None, Some(WindowTarget::Id(3)), None)
+        );
+        assert_eq!(
+            Target::parse_window("$1").unwrap(),
+            Target::new(Some(SessionTarget::Id(1)), None, None)
+        );
+        assert_eq!(
+            Target::parse_session("%2").unwrap(),
+            Target::new(None, None, Some(PaneTarget::Id(2)))
+        );
+        assert!(Target::parse_window("@x").is_err());
+    }
+
+    #[test]
+    fn parses_session_targets() {
+        assert_eq!(Target::parse_session("").unwrap(), Target::default());
+        assert_eq!(
+            Target::parse_session("work").unwrap(),
+            Target::new(Some(SessionTarget::Name(name("work"))), None, None)
+        );
+        assert_eq!(
+            Target::parse_session("work:2").unwrap(),
+            Target::new(Some(SessionTarget::Name(name("work"))), None, None)
+        );
+        assert_eq!(
+            Target::parse_session("$x").unwrap(),
+            Target::new(Some(SessionTarget::Name(name("$x"))), None, None)
+        );
+    }
+
+    #[test]
+    fn parses_window_targets() {
+        assert_eq!(
+            Target::parse_window("work:2").unwrap(),
+            Target::new(
+                Some(SessionTarget::Name(name("work"))),
+                Some(WindowTarget::Index(2)),
+                None
+            )
+        );
+        assert_eq!(
+            Target::parse_window("$0:editor").unwrap(),
+            Target::new(
+                Some(SessionTarget::Id(0)),
+                Some(WindowTarget::Name(name("editor"))),
+                None
+            )
+        );
+        assert_eq!(
+            Target::parse_window("work:").unwrap(),
+            Target::new(Some(SessionTarget::Name(name("work"))), None, None)
+        );
+        assert_eq!(
+            Target::parse_window(":@4").unwrap(),
+            Target::new(None, Some(WindowTarget::Id(4)), None)
+        );
+        assert_eq!(
+            Target::parse_window("editor").unwrap(),
+            Target::new(None, Some(WindowTarget::Name(name("editor"))), None)
+        );
+    }
+
+    #[test]
+    fn parses_relative_windows() {
+        for (target, window) in [
+            ("{start}", WindowTarget::Start),
+            ("^", WindowTarget::Start),
+            ("{end}", WindowTarget::End),
+            (":$", WindowTarget::End),
+            ("!", WindowTarget::Last),
+            ("{last}", WindowTarget::Last),
+            ("+", WindowTarget::Next(1)),
+            ("{next}", WindowTarget::Next(1)),
+            ("+3", WindowTarget::Next(3)),
+            ("-", WindowTarget::Previous(1)),
+            ("{previous}", WindowTarget::Previous(1)),
+            ("-2", WindowTarget::Previous(2)),
+        ] {
+            assert_eq!(
+                Target::parse_window(target).unwrap().get_window(),
+                Some(&window),
+                "{target}"
+            );
+        }
+
+        assert!(Target::parse_window("{bogus}").is_err());
+    }
+
+    #[test]
+    fn parses_pane_targets() {
+        assert_eq!(
+            Target::parse_pane("work:2.1").unwrap(),
+            Target::new(
+                Some(SessionTarget::Name(name("work"))),
+                Some(WindowTarget::Index(2)),
+                Some(PaneTarget::Index(1))
+            )
+        );
+        assert_eq!(
+            Target::parse_pane("editor.{left}").unwrap(),
+            Target::new(
+                None,
+                Some(WindowTarget::Name(name("editor"))),
+                Some(PaneTarget::Edge(PaneEdge::Left))
+            )
+        );
+        assert_eq!(
+            Target::parse_pane("work:.+").unwrap(),
+            Target::new(
+                Some(SessionTarget::Name(name("work"))),
+                None,
+                Some(PaneTarget::Next(1))
+            )
+        );
+        assert_eq!(
+            Target::parse_pane("work:my.window")
+                .unwrap_err()
+                .to_string(),
+            "invalid target: window"
+        );
+        assert_eq!(
+            Target::parse_pane("2").unwrap(),
+            Target::new(None, Some(WindowTarget::Index(2)), None)
+        );
+    }
+
+    #[test]
+    fn parses_relative_panes() {
+        for (target, pane) in [
+            ("!", PaneTarget::Last),
+            ("{last}", PaneTarget::Last),
+            ("+", PaneTarget::Next(1)),
+            ("-2", PaneTarget::Previous(2)),
+            ("{top-right}", PaneTarget::Edge(PaneEdge::TopRight)),
+            ("{up-of}", PaneTarget::Neighbor(PaneDirection::Up)),
+            (".{right-of}", PaneTarget::Neighbor(PaneDirection::Right)),
+        ] {
+            assert_eq!(
+                Target::parse_pane(target).unwrap(),
+                Target::new(None, None, Some(pane)),
+                "{target}"
+            );
+        }
+
+        assert!(Target::parse_pane("1.{sideways}").is_err());
+    }
+}
@@ -1,6 +1,8 @@
+use std::fmt;
+
 use crate::SessionId;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct WindowId {
     id: usize,
     session: SessionId,
@@ -19,3 +21,9 @@ impl WindowId {
         self.session
     }
 }
+
+impl fmt::Display for WindowId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.id)
+    }
+}
@@ -0,0 +1,49 @@
+/// The window part of a target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowTarget {
+    /// `@N`
+    Id(usize),
+    Index(usize),
+    Name(String),
+    /// `{start}` or `^`, the window with the lowest index.
+    Start,
+    /// `{end}` or `$`, the window with the highest index.
+    End,
+    /// `{last}` or `!`, the previously active window.
+    Last,
+    /// `{next}` or `+`, or `+N` to skip windows, counting from the active window.
+    Next(usize),
+    /// `{previous}` or `-`, or `-N` to skip windows, counting from the active window.
+    Previous(usize),
+}
+
+impl WindowTarget {
+    pub fn parse(target: &str) -> splix_error::Result<Option<Self>> {
+        let window_target = match target {
+            "" => return Ok(None),
+            "{start}" | "^" => Self::Start,
+            "{end}" | "$" => Self::End,
+            "{last}" | "!" => Self::Last,
+            "{next}" | "+" => Self::Next(1),
+            "{previous}" | "-" => Self::Previous(1),
+            _ if target.starts_with('{') => {
+                return Err(splix_error::Error::InvalidTarget(target.to_string()));
+            }
+            _ => {
+                if let Some(Ok(id)) = target.strip_prefix('@').map(str::parse) {
+                    Self::Id(id)
+                } else if let Some(Ok(offset)) = target.strip_prefix('+').map(str::parse) {
+                    Self::Next(offset)
+                } else if let Some(Ok(offset)) = target.strip_prefix('-').map(str::parse) {
+                    Self::Previous(offset)
+                } else if let Ok(index) = target.parse() {
+                    Self::Index(index)
+                } else {
+                    Self::Name(target.to_string())
+                }
+            }
+        };
+
+        Ok(Some(window_target))
+    }
+}
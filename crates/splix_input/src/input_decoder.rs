@@ -0,0 +1,479 @@
+use splix_event::{KeyEvent, Paste};
+use splix_key::{Key, KeyCode, Modifiers, MouseEvent};
+
+/// Splits the bytes read from the user's terminal into keys, assembling escape sequences and
+/// UTF-8 characters that may arrive in pieces.
+///
+/// A sequence that was only started stays pending until more input arrives or the decoder is
+/// flushed, which is what tells an escape key apart from the start of e.g. a cursor key.
+///
+/// Between the markers of a bracketed paste, bytes are passed on as pasted text without being
+/// decoded, however long the terminal takes to send them.
+#[derive(Debug, Default)]
+pub struct InputDecoder {
+    pending: Vec<u8>,
+    pasting: bool,
+}
+
+/// What the bytes at the start of the input were decoded into.
+enum Decoded {
+    Key(Key),
+    /// Keys sent in the sequences of the kitty keyboard protocol or modifyOtherKeys.
+    EnhancedKey(Key),
+    Mouse(MouseEvent),
+    PasteStart,
+    /// Sequences that are passed on without being understood.
+    Unknown,
+    /// Sequences of the kitty keyboard protocol or modifyOtherKeys that aren't understood, like
+    /// key releases or kitty's functional keys.
+    UnknownEnhanced,
+}
+
+const ESCAPE: u8 = 0x1b;
+
+/// The event type of key releases in the kitty keyboard protocol.
+const KITTY_RELEASE_EVENT: u32 = 3;
+
+/// The private use code points kitty sends for keys without characters, like those of the
+/// keypad.
+const KITTY_FUNCTIONAL_KEYS: std::ops::RangeInclusive<u32> = 57344..=63743;
+
+/// What the terminal sends after pasted text, once asked to bracket it.
+const PASTE_END: &[u8] = b"\x1b[201~";
+
+impl InputDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the decoder waits for the rest of a sequence, which includes the rest of the end
+    /// marker of a paste.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn push(&mut self, input: &[u8]) -> Vec<KeyEvent> {
+        self.pending.extend_from_slice(input);
+        self.decode(false)
+    }
+
+    /// Gives up on waiting for the rest of a sequence, e.g. once the escape time ran out. An
+    /// escape followed by the start of a sequence is taken as the escape modifying the key
+    /// typed after it. A paste only ends with its end marker, so the start of one is still
+    /// waited for.
+    pub fn flush(&mut self) -> Vec<KeyEvent> {
+        self.decode(true)
+    }
+
+    fn decode(&mut self, flush: bool) -> Vec<KeyEvent> {
+        let mut events = Vec::new();
+        loop {
+            if self.pasting {
+                match self.decode_paste() {
+                    Some(event) => events.push(event),
+                    None => break,
+                }
+                continue;
+            }
+
+            let Some((length, decoded)) = Self::decode_key(&self.pending, flush) else {
+                break;
+            };
+            let bytes = self.pending.drain(..length).collect();
+            events.push(match decoded {
+                Decoded::Key(key) => KeyEvent::new(Some(key), bytes),
+                Decoded::EnhancedKey(key) => KeyEvent::new(Some(key), bytes).with_enhanced(),
+                Decoded::Mouse(mouse) => KeyEvent::new(None, bytes).with_mouse(mouse),
+                Decoded::PasteStart => {
+                    self.pasting = true;
+                    KeyEvent::new(None, bytes).with_paste(Paste::Start)
+                }
+                Decoded::Unknown => KeyEvent::new(None, bytes),
+                Decoded::UnknownEnhanced => KeyEvent::new(None, bytes).with_enhanced(),
+            });
+        }
+
+        events
+    }
+
+    /// Passes on the pasted text up to the end marker, holding back what may be its start.
+    fn decode_paste(&mut self) -> Option<KeyEvent> {
+        let end = self
+            .pending
+            .windows(PASTE_END.len())
+            .position(|window| window == PASTE_END);
+        if end == Some(0) {
+            self.pasting = false;
+            let bytes = self.pending.drain(..PASTE_END.len()).collect();
+            return Some(KeyEvent::new(None, bytes).with_paste(Paste::End));
+        }
+
+        let length = end.unwrap_or_else(|| {
+            let partial_end = (1..PASTE_END.len())
+                .rev()
+                .find(|length| self.pending.ends_with(&PASTE_END[..*length]))
+                .unwrap_or(0);
+            self.pending.len() - partial_end
+        });
+        if length == 0 {
+            return None;
+        }
+
+        let bytes = self.pending.drain(..length).collect();
+        Some(KeyEvent::new(None, bytes).with_paste(Paste::Text))
+    }
+
+    /// Returns how many bytes the first key takes up, or `None` if more are needed to tell.
+    ///
+    /// An `ESC` before another key is Meta, once: a second one is the Escape key itself.
+    fn decode_key(input: &[u8], flush: bool) -> Option<(usize, Decoded)> {
+        let [ESCAPE, rest @ ..] = input else {
+            return Self::decode_unprefixed_key(input, flush);
+        };
+        if matches!(rest, [] | [b'[', ..] | [b'O', ..]) {
+            return Self::decode_unprefixed_key(input, flush);
+        }
+
+        let (length, decoded) = Self::decode_unprefixed_key(rest, flush)?;
+        let decoded = match decoded {
+            Decoded::Key(key) => Decoded::Key(Self::with_meta(key)),
+            Decoded::EnhancedKey(key) => Decoded::EnhancedKey(Self::with_meta(key)),
+            decoded => decoded,
+        };
+        Some((length + 1, decoded))
+    }
+
+    fn decode_unprefixed_key(input: &[u8], flush: bool) -> Option<(usize, Decoded)> {
+        match input {
+            [] => None,
+            [ESCAPE] => flush.then(|| (1, Self::byte_key(ESCAPE))),
+            [ESCAPE, b'[', rest @ ..] => match Self::decode_csi(rest) {
+                Some((length, decoded)) => Some((length + 2, decoded)),
+                None => flush.then(|| (2, Decoded::Key(Self::with_meta(Self::char_key('['))))),
+            },
+            [ESCAPE, b'O'] => {
+                flush.then(|| (2, Decoded::Key(Self::with_meta(Self::char_key('O')))))
+            }
+            [ESCAPE, b'O', final_byte, ..] => Some((3, Self::decode_ss3(*final_byte))),
+            [ESCAPE, ..] => Some((1, Self::byte_key(ESCAPE))),
+            [byte, ..] if byte.is_ascii() => Some((1, Self::byte_key(*byte))),
+            [lead, rest @ ..] => Self::decode_utf8(*lead, rest, flush),
+        }
+    }
+
+    /// Returns how many bytes follow `ESC [` up to and including the final byte, or `None` if
+    /// it didn't arrive yet.
+    fn decode_csi(input: &[u8]) -> Option<(usize, Decoded)> {
+        for (index, byte) in input.iter().enumerate() {
+            match byte {
+                // Parameters and intermediate bytes.
+                0x20..=0x3f => {}
+                0x40..=0x7e => {
+                    let parameters = &input[..index];
+                    let decoded = match parameters {
+                        [b'<', parameters @ ..] => Self::sgr_mouse(parameters, *byte),
+                        b"200" if *byte == b'~' => Some(Decoded::PasteStart),
+                        parameters if *byte == b'u' => Some(
+                            Self::kitty_key(parameters)
+                                .map_or(Decoded::UnknownEnhanced, Decoded::EnhancedKey),
+                        ),
+                        [b'2', b'7', b';', parameters @ ..] if *byte == b'~' => Some(
+                            Self::modify_other_keys_key(parameters)
+                                .map_or(Decoded::UnknownEnhanced, Decoded::EnhancedKey),
+                        ),
+                        parameters => Self::csi_key(parameters, *byte).map(Decoded::Key),
+                    };
+                    return Some((index + 1, decoded.unwrap_or(Decoded::Unknown)));
+                }
+                // Anything else ends the sequence early and is decoded by itself.
+                _ => return Some((index, Decoded::Unknown)),
+            }
+        }
+
+        None
+    }
+
+    /// Mouse reports in the SGR format the outer terminal is asked for, like `ESC [ < 0;3;2 M`.
+    fn sgr_mouse(parameters: &[u8], final_byte: u8) -> Option<Decoded> {
+        let release = match final_byte {
+            b'M' => false,
+            b'm' => true,
+            _ => return None,
+        };
+        let numbers = std::str::from_utf8(parameters)
+            .ok()?
+            .split(';')
+            .map(|number| number.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        let [code, column, row] = numbers[..] else {
+            return None;
+        };
+
+        MouseEvent::from_sgr(code, column, row, release).map(Decoded::Mouse)
+    }
+
+    /// Keys of the kitty keyboard protocol, like `CSI 59 ; 5 u` for `C-;`, with alternate keys
+    /// after colons and what the key types after another semicolon left out. Releases aren't
+    /// asked for, so aren't taken for keys.
+    fn kitty_key(parameters: &[u8]) -> Option<Key> {
+        let parameters = std::str::from_utf8(parameters).ok()?;
+        let mut fields = parameters
+            .split(';')
+            .map(|field| field.split(':').map(|number| number.parse::<u32>().ok()));
+
+        let code_point = fields.next()?.next().flatten()?;
+        let (modifiers, event_type) = match fields.next() {
+            Some(mut field) => (
+                field.next().flatten().unwrap_or(1),
+                field.next().flatten().unwrap_or(1),
+            ),
+            None => (1, 1),
+        };
+        if event_type == KITTY_RELEASE_EVENT {
+            return None;
+        }
+
+        Self::code_point_key(code_point, modifiers)
+    }
+
+    /// Keys xterm sends with modifyOtherKeys, like `CSI 27 ; 5 ; 59 ~` for `C-;`, after the
+    /// leading `27;`.
+    fn modify_other_keys_key(parameters: &[u8]) -> Option<Key> {
+        let parameters = std::str::from_utf8(parameters).ok()?;
+        let (modifiers, code_point) = parameters.split_once(';')?;
+        Self::code_point_key(code_point.parse().ok()?, modifiers.parse().ok()?)
+    }
+
+    /// Letters shifted into capitals are taken as the letter with shift, the way kitty sends
+    /// them. Kitty's own code points for keys without characters aren't known.
+    fn code_point_key(code_point: u32, modifiers: u32) -> Option<Key> {
+        let mut modifiers = Modifiers::from_parameter(u8::try_from(modifiers).ok()?);
+        let code = match char::from_u32(code_point)? {
+            '\t' => KeyCode::Tab,
+            '\r' => KeyCode::Enter,
+            '\x1b' => KeyCode::Escape,
+            ' ' => KeyCode::Space,
+            '\x7f' => KeyCode::Backspace,
+            c if c.is_ascii_uppercase() => {
+                modifiers = modifiers.with_shift();
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            c if c.is_control() || KITTY_FUNCTIONAL_KEYS.contains(&code_point) => return None,
+            c => KeyCode::Char(c),
+        };
+
+        Some(Key::new(code, modifiers))
+    }
+
+    /// Cursor and function keys, following xterm, with a second parameter for modifiers like
+    /// `ESC [ 1 ; 5 A` for `C-Up`.
+    fn csi_key(parameters: &[u8], final_byte: u8) -> Option<Key> {
+        let parameters = std::str::from_utf8(parameters).ok()?;
+        let mut numbers = parameters.split(';').map(|number| {
+            if number.is_empty() {
+                Some(1)
+            } else {
+                number.parse::<u8>().ok()
+            }
+        });
+        let first = numbers.next().flatten()?;
+        let modifiers = Modifiers::from_parameter(numbers.next().flatten().unwrap_or(1));
+
+        let code = match final_byte {
+            b'Z' => KeyCode::BackTab,
+            b'~' => match first {
+                1 | 7 => KeyCode::Home,
+                2 => KeyCode::Insert,
+                3 => KeyCode::Delete,
+                4 | 8 => KeyCode::End,
+                5 => KeyCode::PageUp,
+                6 => KeyCode::PageDown,
+                11..=15 => KeyCode::Function(first - 10),
+                17..=21 => KeyCode::Function(first - 11),
+                23 | 24 => KeyCode::Function(first - 12),
+                _ => return None,
+            },
+            final_byte => Self::cursor_code(final_byte)?,
+        };
+
+        Some(Key::new(code, modifiers))
+    }
+
+    fn decode_ss3(final_byte: u8) -> Decoded {
+        match Self::cursor_code(final_byte) {
+            Some(code) => Decoded::Key(Key::new(code, Modifiers::NONE)),
+            None => Decoded::Unknown,
+        }
+    }
+
+    /// The final bytes cursor keys and `F1` to `F4` share between CSI and SS3 sequences.
+    fn cursor_code(final_byte: u8) -> Option<KeyCode> {
+        let code = match final_byte {
+            b'A' => KeyCode::Up,
+            b'B' => KeyCode::Down,
+            b'C' => KeyCode::Right,
+            b'D' => KeyCode::Left,
+            b'H' => KeyCode::Home,
+            b'F' => KeyCode::End,
+            b'P'..=b'S' => KeyCode::Function(final_byte - b'P' + 1),
+            _ => return None,
+        };
+
+        Some(code)
+    }
+
+    /// Bytes that can't start a character, and characters cut short, are passed on by
+    /// themselves.
+    fn decode_utf8(lead: u8, rest: &[u8], flush: bool) -> Option<(usize, Decoded)> {
+        let length = match lead {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => return Some((1, Decoded::Unknown)),
+        };
+
+        let continuation = &rest[..rest.len().min(length - 1)];
+        if continuation
+            .iter()
+            .any(|byte| !(0x80..=0xbf).contains(byte))
+        {
+            return Some((1, Decoded::Unknown));
+        }
+        if continuation.len() < length - 1 {
+            return flush.then_some((1 + continuation.len(), Decoded::Unknown));
+        }
+
+        let bytes = [&[lead], continuation].concat();
+        let decoded = match std::str::from_utf8(&bytes)
+            .ok()
+            .and_then(|text| text.chars().next())
+        {
+            Some(c) => Decoded::Key(Self::char_key(c)),
+            None => Decoded::Unknown,
+        };
+        Some((length, decoded))
+    }
+
+    fn byte_key(byte: u8) -> Decoded {
+        match Key::from_byte(byte) {
+            Some(key) => Decoded::Key(key),
+            None => Decoded::Unknown,
+        }
+    }
+
+    fn char_key(c: char) -> Key {
+        Key::new(KeyCode::Char(c), Modifiers::NONE)
+    }
+
+    fn with_meta(key: Key) -> Key {
+        Key::new(key.get_code(), key.get_modifiers().with_meta())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_event::Paste;
+    use splix_key::Key;
+
+    use super::InputDecoder;
+
+    fn keys(input: &[u8]) -> Vec<String> {
+        let mut decoder = InputDecoder::new();
+        let mut events = decoder.push(input);
+        events.extend(decoder.flush());
+
+        events
+            .iter()
+            .map(|event| match (event.get_key(), event.get_mouse()) {
+                (Some(key), _) => key.to_string(),
+                (None, Some(mouse)) => format!("{:?}", mouse.get_action()),
+                (None, None) => format!("{:?}", event.get_bytes()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn decodes_sequences() {
+        assert_eq!(keys(b"a\x02\r"), ["a", "C-b", "Enter"]);
+        assert_eq!(keys(b"\x1b[A\x1bOB\x1b[1;5C"), ["Up", "Down", "C-Right"]);
+        assert_eq!(keys(b"\x1b[15~\x1b[24;2~\x1bOP"), ["F5", "S-F12", "F1"]);
+        assert_eq!(keys(b"\x1bx\x1b\x1b[D"), ["M-x", "M-Left"]);
+        assert_eq!(keys(b"\x1b\x1b\x1bx"), ["M-Escape", "M-x"]);
+        assert_eq!(keys("é€".as_bytes()), ["é", "€"]);
+        assert_eq!(
+            keys(b"\x1b[<0;1;2M\x1b[<32;2;2M\x1b[<0;2;2m"),
+            ["Press", "Drag", "Release"]
+        );
+        assert_eq!(keys(b"\x1b[<1;2M"), ["[27, 91, 60, 49, 59, 50, 77]"]);
+        assert_eq!(keys(b"\xff\xc3"), ["[255]", "[195]"]);
+    }
+
+    #[test]
+    fn decodes_enhanced_keys() {
+        assert_eq!(
+            keys(b"\x1b[59;5u\x1b[97;6u\x1b[27u\x1b[13;3u\x1b[97;5:3u"),
+            [
+                "C-;",
+                "C-S-a",
+                "Escape",
+                "M-Enter",
+                "[27, 91, 57, 55, 59, 53, 58, 51, 117]"
+            ]
+        );
+        assert_eq!(
+            keys(b"\x1b[27;5;59~\x1b[27;6;65~\x1b[27;3;120~"),
+            ["C-;", "C-S-a", "M-x"]
+        );
+
+        let mut decoder = InputDecoder::new();
+        assert!(decoder.push(b"\x1b[59;5u")[0].is_enhanced());
+        assert!(decoder.push(b"\x1b[57399u")[0].is_enhanced());
+        assert!(!decoder.push(b"\x02")[0].is_enhanced());
+    }
+
+    #[test]
+    fn waits_for_the_rest_of_a_sequence() {
+        let mut decoder = InputDecoder::new();
+        assert!(decoder.push(b"\x1b").is_empty());
+        assert!(decoder.push(b"[1;").is_empty());
+        assert!(decoder.is_pending());
+
+        let events = decoder.push(b"3A");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].get_key(), Some("M-Up".parse::<Key>().unwrap()));
+        assert_eq!(events[0].get_bytes(), b"\x1b[1;3A");
+
+        assert!(decoder.push(&"é".as_bytes()[..1]).is_empty());
+        assert_eq!(
+            decoder.push(&"é".as_bytes()[1..])[0].get_bytes(),
+            "é".as_bytes()
+        );
+    }
+
+    #[test]
+    fn passes_pastes_on_undecoded() {
+        let mut decoder = InputDecoder::new();
+        let events = decoder.push(b"a\x1b[200~\x02x\x1b[20");
+        assert_eq!(events[0].get_key(), "a".parse().ok());
+        assert_eq!(events[1].get_paste(), Some(Paste::Start));
+        assert_eq!(events[2].get_paste(), Some(Paste::Text));
+        assert_eq!(events[2].get_bytes(), b"\x02x");
+        assert!(decoder.flush().is_empty());
+        assert!(decoder.is_pending());
+
+        let events = decoder.push(b"1~\x02");
+        assert_eq!(events[0].get_paste(), Some(Paste::End));
+        assert_eq!(events[1].get_key(), "C-b".parse().ok());
+        assert!(!decoder.is_pending());
+    }
+
+    #[test]
+    fn flushing_ends_a_lone_escape() {
+        let mut decoder = InputDecoder::new();
+        assert!(decoder.push(b"\x1b").is_empty());
+        assert_eq!(decoder.flush()[0].get_key(), "Escape".parse().ok());
+        assert!(!decoder.is_pending());
+
+        assert_eq!(keys(b"\x1b["), ["M-["]);
+    }
+}
@@ -1,29 +1,66 @@
+use std::time::Duration;
+
 use tokio::{
     io::{self, AsyncReadExt},
     sync::mpsc::Sender,
+    task::JoinHandle,
 };
 
-use splix_event::Event;
+use splix_event::KeyEvent;
+
+use crate::InputDecoder;
 
-pub struct InputReceiver;
+pub struct InputReceiver {
+    receive_task: JoinHandle<splix_error::Result<()>>,
+}
+
+const READ_BUFFER_SIZE: usize = 4096;
 
 impl InputReceiver {
-    pub fn new(event_sender: Sender<Event>) -> Self {
-        tokio::spawn(async move {
-            Self::receive(event_sender).await;
-        });
+    /// How long to wait for the rest of an escape sequence before taking what arrived as typed.
+    pub const ESCAPE_TIME: Duration = Duration::from_millis(10);
 
-        Self
+    /// The keys decoded from each read of the terminal are sent together, so that e.g. pasted
+    /// text travels on as one.
+    pub fn new(key_sender: Sender<Vec<KeyEvent>>) -> Self {
+        Self {
+            receive_task: tokio::spawn(Self::receive(key_sender)),
+        }
     }
 
-    async fn receive(event_sender: Sender<Event>) {
+    /// Waits until stdin is closed, or fails with why it couldn't be read.
+    pub async fn closed(&mut self) -> splix_error::Result<()> {
+        (&mut self.receive_task).await.unwrap_or(Ok(()))
+    }
+
+    async fn receive(key_sender: Sender<Vec<KeyEvent>>) -> splix_error::Result<()> {
         let mut stdin = io::stdin();
+        let mut read_buffer = [0u8; READ_BUFFER_SIZE];
+        let mut decoder = InputDecoder::new();
 
         loop {
-            event_sender
-                .send(Event::Input(stdin.read_u8().await.unwrap()))
-                .await
-                .unwrap();
+            let read = if decoder.is_pending() {
+                tokio::time::timeout(Self::ESCAPE_TIME, stdin.read(&mut read_buffer))
+                    .await
+                    .ok()
+            } else {
+                Some(stdin.read(&mut read_buffer).await)
+            };
+
+            let events = match read {
+                Some(length) => match length.map_err(splix_error::Error::ReadFromTerminal)? {
+                    0 => return Ok(()),
+                    length => decoder.push(&read_buffer[..length]),
+                },
+                None => decoder.flush(),
+            };
+
+            if !events.is_empty() {
+                key_sender
+                    .send(events)
+                    .await
+                    .map_err(|_| splix_error::Error::SendKeys)?;
+            }
         }
     }
 }
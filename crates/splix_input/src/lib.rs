@@ -1,3 +1,5 @@
+mod input_decoder;
 mod input_receiver;
 
+pub use input_decoder::InputDecoder;
 pub use input_receiver::InputReceiver;
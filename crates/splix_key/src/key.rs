@@ -0,0 +1,372 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{KeyCode, KeyboardModes, Modifiers};
+
+/// A key pressed together with modifiers, named like `C-c`, `M-x`, `Enter` or `S-F5`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: Modifiers,
+}
+
+const ESCAPE: u8 = 0x1b;
+const DELETE: u8 = 0x7f;
+
+impl Key {
+    pub fn new(code: KeyCode, modifiers: Modifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    /// The key a terminal sends as a single byte, like `C-b` for `0x02`. Bytes of longer
+    /// sequences, like the rest of a UTF-8 character, aren't keys by themselves.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        let control = |c: char| Self::new(KeyCode::Char(c), Modifiers::NONE.with_control());
+
+        let key = match byte {
+            0 => Self::new(KeyCode::Space, Modifiers::NONE.with_control()),
+            b'\t' => Self::new(KeyCode::Tab, Modifiers::NONE),
+            b'\r' => Self::new(KeyCode::Enter, Modifiers::NONE),
+            ESCAPE => Self::new(KeyCode::Escape, Modifiers::NONE),
+            0x01..=0x1a => control((b'a' + byte - 1) as char),
+            0x1c..=0x1f => control((b'\\' + byte - 0x1c) as char),
+            b' ' => Self::new(KeyCode::Space, Modifiers::NONE),
+            DELETE => Self::new(KeyCode::Backspace, Modifiers::NONE),
+            0x21..=0x7e => Self::new(KeyCode::Char(byte as char), Modifiers::NONE),
+            _ => return None,
+        };
+
+        Some(key)
+    }
+
+    pub fn get_code(&self) -> KeyCode {
+        self.code
+    }
+
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// The bytes a terminal sends for the key, following xterm, or the kitty keyboard protocol
+    /// and modifyOtherKeys if the program asked for them.
+    pub fn encode(&self, keyboard_modes: KeyboardModes) -> Vec<u8> {
+        if let Some(bytes) = self.encode_enhanced(keyboard_modes) {
+            return bytes;
+        }
+
+        match self.code {
+            KeyCode::Char(c) => self.encode_char(c),
+            KeyCode::Space => self.encode_char(' '),
+            KeyCode::Enter => self.with_escape_prefix(vec![b'\r']),
+            KeyCode::Tab if self.modifiers.is_shift() => Self::encode_csi(b"", b'Z'),
+            KeyCode::Tab => self.with_escape_prefix(vec![b'\t']),
+            KeyCode::BackTab => Self::encode_csi(b"", b'Z'),
+            KeyCode::Escape => self.with_escape_prefix(vec![ESCAPE]),
+            KeyCode::Backspace => self.with_escape_prefix(vec![DELETE]),
+            KeyCode::Up => self.encode_cursor(b'A', keyboard_modes),
+            KeyCode::Down => self.encode_cursor(b'B', keyboard_modes),
+            KeyCode::Right => self.encode_cursor(b'C', keyboard_modes),
+            KeyCode::Left => self.encode_cursor(b'D', keyboard_modes),
+            KeyCode::Home => self.encode_cursor(b'H', keyboard_modes),
+            KeyCode::End => self.encode_cursor(b'F', keyboard_modes),
+            KeyCode::Insert => self.encode_tilde(2),
+            KeyCode::Delete => self.encode_tilde(3),
+            KeyCode::PageUp => self.encode_tilde(5),
+            KeyCode::PageDown => self.encode_tilde(6),
+            KeyCode::Function(number @ 1..=4) => {
+                // F1 to F4 are sent like cursor keys in application mode.
+                let final_byte = b'P' + number - 1;
+                if self.modifiers.is_empty() {
+                    vec![ESCAPE, b'O', final_byte]
+                } else {
+                    self.encode_modified_csi(1, final_byte)
+                }
+            }
+            KeyCode::Function(number) => {
+                let code = match number {
+                    5 => 15,
+                    6..=10 => number + 11,
+                    _ => number + 12,
+                };
+                self.encode_tilde(code)
+            }
+        }
+    }
+
+    /// Keys typing characters, and enter, tab, escape and backspace, whose traditional
+    /// encodings lose modifiers. Other keys keep xterm's sequences, which already carry them.
+    fn encode_enhanced(&self, keyboard_modes: KeyboardModes) -> Option<Vec<u8>> {
+        let (code_point, modifiers) = self.get_code_point()?;
+        let typing_text = matches!(self.code, KeyCode::Char(_) | KeyCode::Space)
+            && (modifiers.is_empty() || modifiers == Modifiers::NONE.with_shift());
+
+        let kitty_flags = keyboard_modes.get_kitty_flags();
+        if kitty_flags & KeyboardModes::REPORT_ALL_KEYS != 0
+            || (kitty_flags & KeyboardModes::DISAMBIGUATE_ESCAPE_CODES != 0
+                && (self.code == KeyCode::Escape || !(typing_text || modifiers.is_empty())))
+        {
+            let parameters = if modifiers.is_empty() {
+                code_point.to_string()
+            } else {
+                format!("{code_point};{}", modifiers.get_parameter())
+            };
+            return Some(Self::encode_csi(parameters.as_bytes(), b'u'));
+        }
+
+        let modifiers_lost = match self.code {
+            KeyCode::Char(c) => {
+                modifiers.is_control()
+                    && (modifiers.is_shift() || Self::get_control_character(c).is_none())
+            }
+            KeyCode::Space => modifiers.is_control() && modifiers.is_shift(),
+            KeyCode::Tab | KeyCode::BackTab => modifiers.is_control(),
+            _ => modifiers.is_control() || modifiers.is_shift(),
+        };
+        let modify = match keyboard_modes.get_modify_other_keys() {
+            0 => false,
+            1 => modifiers_lost,
+            _ => !(typing_text || modifiers.is_empty()),
+        };
+        if !modify {
+            return None;
+        }
+
+        // modifyOtherKeys sends the shifted character.
+        let code_point = match self.code {
+            KeyCode::Char(c) if modifiers.is_shift() => c.to_ascii_uppercase() as u32,
+            _ => code_point,
+        };
+        let parameters = format!("27;{};{code_point}", modifiers.get_parameter());
+        Some(Self::encode_csi(parameters.as_bytes(), b'~'))
+    }
+
+    /// The code point of keys sent as `CSI u` sequences, which is the unshifted character for
+    /// letters, with shift added to the modifiers instead.
+    fn get_code_point(&self) -> Option<(u32, Modifiers)> {
+        let code_point = match self.code {
+            KeyCode::Char(c) if c.is_ascii_uppercase() => {
+                return Some((c.to_ascii_lowercase() as u32, self.modifiers.with_shift()));
+            }
+            KeyCode::Char(c) => c as u32,
+            KeyCode::Space => ' ' as u32,
+            KeyCode::Enter => '\r' as u32,
+            KeyCode::Tab => '\t' as u32,
+            KeyCode::BackTab => return Some(('\t' as u32, self.modifiers.with_shift())),
+            KeyCode::Escape => ESCAPE as u32,
+            KeyCode::Backspace => DELETE as u32,
+            _ => return None,
+        };
+
+        Some((code_point, self.modifiers))
+    }
+
+    /// Keys typing characters are turned into control characters with `C-` and prefixed with
+    /// an escape with `M-`.
+    fn encode_char(&self, c: char) -> Vec<u8> {
+        let c = if self.modifiers.is_shift() {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        };
+
+        let control_character = self
+            .modifiers
+            .is_control()
+            .then(|| Self::get_control_character(c))
+            .flatten();
+        let bytes = match control_character {
+            Some(control_character) => vec![control_character],
+            None => c.to_string().into_bytes(),
+        };
+
+        self.with_escape_prefix(bytes)
+    }
+
+    fn get_control_character(c: char) -> Option<u8> {
+        match c.to_ascii_lowercase() {
+            c @ 'a'..='z' => Some(c as u8 - b'a' + 1),
+            '@' | ' ' => Some(0),
+            '[' => Some(0x1b),
+            '\\' => Some(0x1c),
+            ']' => Some(0x1d),
+            '^' => Some(0x1e),
+            '_' => Some(0x1f),
+            '?' => Some(DELETE),
+            _ => None,
+        }
+    }
+
+    fn with_escape_prefix(&self, bytes: Vec<u8>) -> Vec<u8> {
+        if self.modifiers.is_meta() {
+            [vec![ESCAPE], bytes].concat()
+        } else {
+            bytes
+        }
+    }
+
+    fn encode_cursor(&self, final_byte: u8, keyboard_modes: KeyboardModes) -> Vec<u8> {
+        if !self.modifiers.is_empty() {
+            self.encode_modified_csi(1, final_byte)
+        } else if keyboard_modes.is_application_cursor() {
+            vec![ESCAPE, b'O', final_byte]
+        } else {
+            Self::encode_csi(b"", final_byte)
+        }
+    }
+
+    fn encode_tilde(&self, code: u8) -> Vec<u8> {
+        if self.modifiers.is_empty() {
+            Self::encode_csi(code.to_string().as_bytes(), b'~')
+        } else {
+            self.encode_modified_csi(code, b'~')
+        }
+    }
+
+    fn encode_modified_csi(&self, code: u8, final_byte: u8) -> Vec<u8> {
+        let parameters = format!("{code};{}", self.modifiers.get_parameter());
+        Self::encode_csi(parameters.as_bytes(), final_byte)
+    }
+
+    fn encode_csi(parameters: &[u8], final_byte: u8) -> Vec<u8> {
+        [&[ESCAPE, b'['], parameters, &[final_byte]].concat()
+    }
+}
+
+/// Modifiers are given as `C-`, `M-` and `S-` prefixes in any order, or `^` for control.
+impl FromStr for Key {
+    type Err = splix_error::Error;
+
+    fn from_str(name: &str) -> splix_error::Result<Self> {
+        let invalid_key = || splix_error::Error::InvalidKey(name.to_string());
+
+        let mut modifiers = Modifiers::NONE;
+        let mut rest = name;
+        loop {
+            if let Some(control_rest) = rest.strip_prefix('^')
+                && !control_rest.is_empty()
+            {
+                modifiers = modifiers.with_control();
+                rest = control_rest;
+                continue;
+            }
+
+            let mut chars = rest.chars();
+            match (chars.next(), chars.next()) {
+                // A lone `-` after a prefix is the key, as in `M--`.
+                (Some(modifier), Some('-')) if rest.len() > 2 => {
+                    modifiers = match modifier {
+                        'C' | 'c' => modifiers.with_control(),
+                        'M' | 'm' => modifiers.with_meta(),
+                        'S' | 's' => modifiers.with_shift(),
+                        _ => return Err(invalid_key()),
+                    };
+                    rest = &rest[2..];
+                }
+                _ => break,
+            }
+        }
+
+        let code = KeyCode::from_name(rest).ok_or_else(invalid_key)?;
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl Display for Key {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.modifiers, self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Key, KeyboardModes};
+
+    fn encode(name: &str) -> Vec<u8> {
+        name.parse::<Key>().unwrap().encode(KeyboardModes::new())
+    }
+
+    #[test]
+    fn encodes_modified_characters() {
+        assert_eq!(encode("C-c"), b"\x03");
+        assert_eq!(encode("^C"), b"\x03");
+        assert_eq!(encode("M-x"), b"\x1bx");
+        assert_eq!(encode("C-M-a"), b"\x1b\x01");
+        assert_eq!(encode("M--"), b"\x1b-");
+        assert_eq!(encode("Enter"), b"\r");
+    }
+
+    #[test]
+    fn encodes_special_keys() {
+        assert_eq!(encode("F1"), b"\x1bOP");
+        assert_eq!(encode("F5"), b"\x1b[15~");
+        assert_eq!(encode("F12"), b"\x1b[24~");
+        assert_eq!(encode("S-F5"), b"\x1b[15;2~");
+        assert_eq!(encode("C-Up"), b"\x1b[1;5A");
+        assert_eq!(encode("NPage"), b"\x1b[6~");
+    }
+
+    #[test]
+    fn cursor_keys_follow_application_cursor_mode() {
+        let mut keyboard_modes = KeyboardModes::new();
+        assert_eq!(
+            "Up".parse::<Key>().unwrap().encode(keyboard_modes),
+            b"\x1b[A"
+        );
+
+        keyboard_modes.set_application_cursor(true);
+        assert_eq!(
+            "Up".parse::<Key>().unwrap().encode(keyboard_modes),
+            b"\x1bOA"
+        );
+        assert_eq!(
+            "M-Left".parse::<Key>().unwrap().encode(keyboard_modes),
+            b"\x1b[1;3D"
+        );
+    }
+
+    #[test]
+    fn encodes_keys_as_programs_ask() {
+        let encode_with = |name: &str, kitty_flags, modify_other_keys| {
+            let mut keyboard_modes = KeyboardModes::new();
+            keyboard_modes.set_kitty_flags(kitty_flags);
+            keyboard_modes.set_modify_other_keys(modify_other_keys);
+            name.parse::<Key>().unwrap().encode(keyboard_modes)
+        };
+
+        assert_eq!(encode_with("C-;", 1, 0), b"\x1b[59;5u");
+        assert_eq!(encode_with("C-S-a", 1, 0), b"\x1b[97;6u");
+        assert_eq!(encode_with("Escape", 1, 0), b"\x1b[27u");
+        assert_eq!(encode_with("A", 1, 0), b"A");
+        assert_eq!(encode_with("Enter", 1, 0), b"\r");
+        assert_eq!(encode_with("C-Up", 1, 0), b"\x1b[1;5A");
+        assert_eq!(encode_with("a", 8, 0), b"\x1b[97u");
+
+        assert_eq!(encode_with("C-a", 0, 1), b"\x01");
+        assert_eq!(encode_with("C-;", 0, 1), b"\x1b[27;5;59~");
+        assert_eq!(encode_with("C-S-a", 0, 1), b"\x1b[27;6;65~");
+        assert_eq!(encode_with("C-a", 0, 2), b"\x1b[27;5;97~");
+        assert_eq!(encode_with("M-x", 0, 2), b"\x1b[27;3;120~");
+        assert_eq!(encode_with("C-;", 0, 0), b";");
+    }
+
+    #[test]
+    fn decodes_single_bytes() {
+        for name in [
+            "C-b", "C-a", "C-Space", "C-]", "a", "%", "Enter", "Tab", "BSpace",
+        ] {
+            let key: Key = name.parse().unwrap();
+            assert_eq!(Key::from_byte(encode(name)[0]), Some(key), "{name}");
+        }
+        assert_eq!(Key::from_byte(0xc3), None);
+    }
+
+    #[test]
+    fn rejects_unknown_names() {
+        assert!("hello".parse::<Key>().is_err());
+        assert!("X-a".parse::<Key>().is_err());
+        assert!("F13".parse::<Key>().is_err());
+        assert_eq!("c-m-pgup".parse::<Key>().unwrap().to_string(), "C-M-PageUp");
+    }
+}
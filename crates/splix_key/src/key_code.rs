@@ -0,0 +1,92 @@
+use std::fmt::{self, Display, Formatter};
+
+/// A key on the keyboard, regardless of the modifiers held down with it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum KeyCode {
+    Char(char),
+    Enter,
+    Tab,
+    BackTab,
+    Escape,
+    Space,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Insert,
+    Delete,
+    /// `F1` to `F12`.
+    Function(u8),
+}
+
+/// Names of keys that aren't characters, with the first name of each key being its canonical
+/// one.
+const NAMED_KEY_CODES: &[(&str, KeyCode)] = &[
+    ("Enter", KeyCode::Enter),
+    ("Tab", KeyCode::Tab),
+    ("BTab", KeyCode::BackTab),
+    ("Escape", KeyCode::Escape),
+    ("Space", KeyCode::Space),
+    ("BSpace", KeyCode::Backspace),
+    ("Up", KeyCode::Up),
+    ("Down", KeyCode::Down),
+    ("Left", KeyCode::Left),
+    ("Right", KeyCode::Right),
+    ("Home", KeyCode::Home),
+    ("End", KeyCode::End),
+    ("PageUp", KeyCode::PageUp),
+    ("PgUp", KeyCode::PageUp),
+    ("PPage", KeyCode::PageUp),
+    ("PageDown", KeyCode::PageDown),
+    ("PgDn", KeyCode::PageDown),
+    ("NPage", KeyCode::PageDown),
+    ("Insert", KeyCode::Insert),
+    ("IC", KeyCode::Insert),
+    ("Delete", KeyCode::Delete),
+    ("DC", KeyCode::Delete),
+];
+
+const FUNCTION_KEY_COUNT: u8 = 12;
+
+impl KeyCode {
+    /// Looks up a key by its name, ignoring case, or by the character it types.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Some(Self::Char(c));
+        }
+
+        if let Some((_, key_code)) = NAMED_KEY_CODES
+            .iter()
+            .find(|(key_name, _)| key_name.eq_ignore_ascii_case(name))
+        {
+            return Some(*key_code);
+        }
+
+        name.strip_prefix(['F', 'f'])
+            .and_then(|number| number.parse().ok())
+            .filter(|number| (1..=FUNCTION_KEY_COUNT).contains(number))
+            .map(Self::Function)
+    }
+}
+
+impl Display for KeyCode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Char(c) => write!(f, "{c}"),
+            Self::Function(number) => write!(f, "F{number}"),
+            key_code => {
+                let (name, _) = NAMED_KEY_CODES
+                    .iter()
+                    .find(|(_, named_key_code)| named_key_code == key_code)
+                    .expect("every key code but characters and function keys is named");
+                f.write_str(name)
+            }
+        }
+    }
+}
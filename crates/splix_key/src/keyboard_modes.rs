@@ -0,0 +1,74 @@
+/// The modes a program running in a pane switched its keyboard into, which change the sequences
+/// keys are sent as.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeyboardModes {
+    application_cursor: bool,
+    bracketed_paste: bool,
+    kitty_flags: u8,
+    modify_other_keys: u8,
+}
+
+impl KeyboardModes {
+    /// The progressive enhancement of the kitty keyboard protocol sending modified keys and
+    /// escape as `CSI u` sequences.
+    pub const DISAMBIGUATE_ESCAPE_CODES: u8 = 1;
+    /// The progressive enhancement sending every key as a `CSI u` sequence, even unmodified ones.
+    pub const REPORT_ALL_KEYS: u8 = 8;
+    /// Reporting key releases and alternate keys isn't possible without the terminal of the
+    /// client sending them, so those flags are left out.
+    const SUPPORTED_KITTY_FLAGS: u8 = Self::DISAMBIGUATE_ESCAPE_CODES | Self::REPORT_ALL_KEYS;
+
+    /// The highest level of xterm's modifyOtherKeys.
+    const MAX_MODIFY_OTHER_KEYS: u8 = 2;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether unmodified cursor keys are sent as `SS3` sequences instead of `CSI` ones (DECCKM).
+    pub fn is_application_cursor(&self) -> bool {
+        self.application_cursor
+    }
+
+    pub fn set_application_cursor(&mut self, application_cursor: bool) {
+        self.application_cursor = application_cursor;
+    }
+
+    /// Whether pasted text is sent between `CSI 200 ~` and `CSI 201 ~` markers, telling it apart
+    /// from typed text.
+    pub fn is_bracketed_paste(&self) -> bool {
+        self.bracketed_paste
+    }
+
+    pub fn set_bracketed_paste(&mut self, bracketed_paste: bool) {
+        self.bracketed_paste = bracketed_paste;
+    }
+
+    /// The kitty keyboard protocol flags in effect, as set with `CSI = flags u` or pushed with
+    /// `CSI > flags u`.
+    pub fn get_kitty_flags(&self) -> u8 {
+        self.kitty_flags
+    }
+
+    /// Flags splix can't honor are dropped, so that programs querying them learn what they get.
+    pub fn set_kitty_flags(&mut self, kitty_flags: u8) {
+        self.kitty_flags = kitty_flags & Self::SUPPORTED_KITTY_FLAGS;
+    }
+
+    /// The level of xterm's modifyOtherKeys (`CSI > 4 ; level m`): 1 sends keys with modifiers
+    /// that would otherwise get lost as `CSI 27 ; modifiers ; code ~`, 2 sends all modified keys
+    /// that way.
+    pub fn get_modify_other_keys(&self) -> u8 {
+        self.modify_other_keys
+    }
+
+    pub fn set_modify_other_keys(&mut self, modify_other_keys: u8) {
+        self.modify_other_keys = modify_other_keys.min(Self::MAX_MODIFY_OTHER_KEYS);
+    }
+
+    /// Whether keys are sent in any of the sequences that tell more modifiers apart than the
+    /// traditional ones.
+    pub fn is_enhanced(&self) -> bool {
+        self.kitty_flags != 0 || self.modify_other_keys != 0
+    }
+}
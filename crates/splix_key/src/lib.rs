@@ -0,0 +1,21 @@
+mod key;
+mod key_code;
+mod keyboard_modes;
+mod modifiers;
+mod mouse_action;
+mod mouse_button;
+mod mouse_encoding;
+mod mouse_event;
+mod mouse_modes;
+mod mouse_tracking;
+
+pub use key::Key;
+pub use key_code::KeyCode;
+pub use keyboard_modes::KeyboardModes;
+pub use modifiers::Modifiers;
+pub use mouse_action::MouseAction;
+pub use mouse_button::MouseButton;
+pub use mouse_encoding::MouseEncoding;
+pub use mouse_event::MouseEvent;
+pub use mouse_modes::MouseModes;
+pub use mouse_tracking::MouseTracking;
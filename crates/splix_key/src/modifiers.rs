@@ -0,0 +1,84 @@
+use std::fmt::{self, Display, Formatter};
+
+/// The modifier keys held down while pressing a key.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
+    control: bool,
+    meta: bool,
+    shift: bool,
+}
+
+impl Modifiers {
+    pub const NONE: Self = Self {
+        control: false,
+        meta: false,
+        shift: false,
+    };
+
+    pub fn with_control(self) -> Self {
+        Self {
+            control: true,
+            ..self
+        }
+    }
+
+    pub fn with_meta(self) -> Self {
+        Self { meta: true, ..self }
+    }
+
+    pub fn with_shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+
+    pub fn is_control(&self) -> bool {
+        self.control
+    }
+
+    pub fn is_meta(&self) -> bool {
+        self.meta
+    }
+
+    pub fn is_shift(&self) -> bool {
+        self.shift
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// The modifier parameter of xterm's escape sequences for modified keys.
+    pub fn get_parameter(&self) -> u8 {
+        1 + self.shift as u8 + 2 * self.meta as u8 + 4 * self.control as u8
+    }
+
+    /// The modifiers a parameter of xterm's escape sequences stands for, ignoring those it has
+    /// no names for.
+    pub fn from_parameter(parameter: u8) -> Self {
+        let bits = parameter.saturating_sub(1);
+        Self {
+            shift: bits & 1 != 0,
+            meta: bits & 2 != 0,
+            control: bits & 4 != 0,
+        }
+    }
+}
+
+/// Written the way key names are prefixed, like `C-M-`.
+impl Display for Modifiers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.control {
+            f.write_str("C-")?;
+        }
+        if self.meta {
+            f.write_str("M-")?;
+        }
+        if self.shift {
+            f.write_str("S-")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+/// What happened to the mouse button of a mouse event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseAction {
+    Press,
+    Release,
+    /// The mouse moved with the button held down.
+    Drag,
+    /// The mouse moved with no button held down.
+    Move,
+}
@@ -0,0 +1,44 @@
+/// The mouse button a mouse event is about, with the wheel turning up or down counted as
+/// buttons like terminals do.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    /// No button, for the mouse moving with all buttons released.
+    None,
+    WheelUp,
+    WheelDown,
+}
+
+impl MouseButton {
+    /// The button as numbered in the button code of mouse reports, without modifiers.
+    pub fn from_code(code: u32) -> Option<Self> {
+        let button = match code {
+            0 => Self::Left,
+            1 => Self::Middle,
+            2 => Self::Right,
+            3 => Self::None,
+            64 => Self::WheelUp,
+            65 => Self::WheelDown,
+            _ => return None,
+        };
+
+        Some(button)
+    }
+
+    pub fn get_code(&self) -> u32 {
+        match self {
+            Self::Left => 0,
+            Self::Middle => 1,
+            Self::Right => 2,
+            Self::None => 3,
+            Self::WheelUp => 64,
+            Self::WheelDown => 65,
+        }
+    }
+
+    pub fn is_wheel(&self) -> bool {
+        matches!(self, Self::WheelUp | Self::WheelDown)
+    }
+}
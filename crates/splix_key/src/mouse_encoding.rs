@@ -0,0 +1,30 @@
+/// How a program running in a pane asked mouse events to be encoded, from the private modes it
+/// set last.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MouseEncoding {
+    /// The original X10 encoding, with each value sent as a single byte, which can't report
+    /// positions past column 223.
+    #[default]
+    Default,
+    /// Like the default encoding but with values sent as UTF-8 characters, reaching up to column
+    /// 2015 (mode 1005).
+    Utf8,
+    /// Decimal values, with releases telling which button was released (mode 1006).
+    Sgr,
+    /// Decimal values in place of the default encoding's bytes (mode 1015).
+    Urxvt,
+}
+
+impl MouseEncoding {
+    /// The encoding a private mode switches on, if it is one of the mouse encoding modes.
+    pub fn from_mode(mode: u16) -> Option<Self> {
+        let encoding = match mode {
+            1005 => Self::Utf8,
+            1006 => Self::Sgr,
+            1015 => Self::Urxvt,
+            _ => return None,
+        };
+
+        Some(encoding)
+    }
+}
@@ -0,0 +1,262 @@
+use glam::UVec2;
+
+use crate::{Modifiers, MouseAction, MouseButton, MouseEncoding, MouseModes, MouseTracking};
+
+/// A mouse button pressed or released, the wheel turned or the mouse moved, at a position
+/// counted from zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MouseEvent {
+    action: MouseAction,
+    button: MouseButton,
+    position: UVec2,
+    modifiers: Modifiers,
+}
+
+const SHIFT_BIT: u32 = 4;
+const META_BIT: u32 = 8;
+const CONTROL_BIT: u32 = 16;
+const MODIFIER_BITS: u32 = SHIFT_BIT | META_BIT | CONTROL_BIT;
+const MOTION_BIT: u32 = 32;
+
+/// The button code of X10 reports for releasing any button.
+const RELEASE_CODE: u32 = 3;
+
+/// X10 reports send the button code and position as single bytes after this offset.
+const X10_OFFSET: u32 = 32;
+
+/// The largest value UTF-8 reports send, which still takes two bytes.
+const MAX_UTF8_VALUE: u32 = 0x7ff;
+
+impl MouseEvent {
+    pub fn new(
+        action: MouseAction,
+        button: MouseButton,
+        position: UVec2,
+        modifiers: Modifiers,
+    ) -> Self {
+        Self {
+            action,
+            button,
+            position,
+            modifiers,
+        }
+    }
+
+    /// Decodes the parameters of an SGR report like `CSI < 0 ; 12 ; 5 M`, where the final
+    /// character is `m` for releases.
+    pub fn from_sgr(code: u32, column: u32, row: u32, release: bool) -> Option<Self> {
+        let button = MouseButton::from_code(code & !(MODIFIER_BITS | MOTION_BIT))?;
+        let action = match (release, code & MOTION_BIT != 0) {
+            (true, _) => MouseAction::Release,
+            (false, true) if button == MouseButton::None => MouseAction::Move,
+            (false, true) => MouseAction::Drag,
+            (false, false) => MouseAction::Press,
+        };
+
+        let mut modifiers = Modifiers::NONE;
+        if code & SHIFT_BIT != 0 {
+            modifiers = modifiers.with_shift();
+        }
+        if code & META_BIT != 0 {
+            modifiers = modifiers.with_meta();
+        }
+        if code & CONTROL_BIT != 0 {
+            modifiers = modifiers.with_control();
+        }
+
+        let position = UVec2::new(column.saturating_sub(1), row.saturating_sub(1));
+        Some(Self::new(action, button, position, modifiers))
+    }
+
+    pub fn get_action(&self) -> MouseAction {
+        self.action
+    }
+
+    pub fn get_button(&self) -> MouseButton {
+        self.button
+    }
+
+    pub fn get_position(&self) -> UVec2 {
+        self.position
+    }
+
+    pub fn get_modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// The same event at another position, like relative to a pane instead of the screen.
+    pub fn with_position(self, position: UVec2) -> Self {
+        Self { position, ..self }
+    }
+
+    /// The bytes a terminal sends for the event, or `None` if the program didn't ask for it or
+    /// the position can't be encoded.
+    pub fn encode(&self, mouse_modes: MouseModes) -> Option<Vec<u8>> {
+        let tracking = mouse_modes.get_tracking();
+        if !tracking.reports(self.action, self.button) {
+            return None;
+        }
+
+        // X10 tracking leaves out modifiers.
+        let code = match tracking {
+            MouseTracking::X10 => self.get_code() & !MODIFIER_BITS,
+            _ => self.get_code(),
+        };
+        let (column, row) = (self.position.x + 1, self.position.y + 1);
+        if mouse_modes.get_encoding() == MouseEncoding::Sgr {
+            let final_character = match self.action {
+                MouseAction::Release => 'm',
+                _ => 'M',
+            };
+            return Some(format!("\x1b[<{code};{column};{row}{final_character}").into_bytes());
+        }
+
+        // The other encodings don't tell which button was released.
+        let code = match self.action {
+            MouseAction::Release => RELEASE_CODE | (code & MODIFIER_BITS),
+            _ => code,
+        };
+        let values = [code, column, row].map(|value| value + X10_OFFSET);
+        match mouse_modes.get_encoding() {
+            MouseEncoding::Urxvt => {
+                let [code, _, _] = values;
+                Some(format!("\x1b[{code};{column};{row}M").into_bytes())
+            }
+            MouseEncoding::Utf8 => {
+                let mut report = String::from("\x1b[M");
+                for value in values {
+                    if value > MAX_UTF8_VALUE {
+                        return None;
+                    }
+                    report.push(char::from_u32(value)?);
+                }
+                Some(report.into_bytes())
+            }
+            _ => {
+                let mut report = b"\x1b[M".to_vec();
+                for value in values {
+                    report.push(u8::try_from(value).ok()?);
+                }
+                Some(report)
+            }
+        }
+    }
+
+    /// The button code of mouse reports, with the modifiers and motion added in.
+    fn get_code(&self) -> u32 {
+        let mut code = self.button.get_code();
+        if self.modifiers.is_shift() {
+            code |= SHIFT_BIT;
+        }
+        if self.modifiers.is_meta() {
+            code |= META_BIT;
+        }
+        if self.modifiers.is_control() {
+            code |= CONTROL_BIT;
+        }
+        if matches!(self.action, MouseAction::Drag | MouseAction::Move) {
+            code |= MOTION_BIT;
+        }
+
+        code
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use super::MouseEvent;
+    use crate::{Modifiers, MouseAction, MouseButton, MouseEncoding, MouseModes, MouseTracking};
+
+    fn modes(tracking: MouseTracking, encoding: MouseEncoding) -> MouseModes {
+        let mut mouse_modes = MouseModes::new();
+        mouse_modes.set_tracking(tracking);
+        mouse_modes.set_encoding(encoding);
+        mouse_modes
+    }
+
+    #[test]
+    fn decodes_sgr_reports() {
+        assert_eq!(
+            MouseEvent::from_sgr(0, 12, 5, false),
+            Some(MouseEvent::new(
+                MouseAction::Press,
+                MouseButton::Left,
+                UVec2::new(11, 4),
+                Modifiers::NONE
+            ))
+        );
+        let drag = MouseEvent::from_sgr(32 + 16 + 2, 1, 1, false).unwrap();
+        assert_eq!(drag.get_action(), MouseAction::Drag);
+        assert_eq!(drag.get_button(), MouseButton::Right);
+        assert!(drag.get_modifiers().is_control());
+        assert_eq!(
+            MouseEvent::from_sgr(35, 1, 1, false).map(|event| event.get_action()),
+            Some(MouseAction::Move)
+        );
+        assert_eq!(
+            MouseEvent::from_sgr(65, 1, 1, false).map(|event| event.get_button()),
+            Some(MouseButton::WheelDown)
+        );
+        assert!(MouseEvent::from_sgr(128, 1, 1, false).is_none());
+    }
+
+    #[test]
+    fn encodes_reports() {
+        let normal = |encoding| modes(MouseTracking::Normal, encoding);
+        let press = MouseEvent::from_sgr(0, 3, 2, false).unwrap();
+        let release = MouseEvent::from_sgr(0, 3, 2, true).unwrap();
+        assert_eq!(press.encode(MouseModes::new()), None);
+        assert_eq!(
+            press.encode(normal(MouseEncoding::Sgr)).unwrap(),
+            b"\x1b[<0;3;2M"
+        );
+        assert_eq!(
+            release.encode(normal(MouseEncoding::Sgr)).unwrap(),
+            b"\x1b[<0;3;2m"
+        );
+        assert_eq!(
+            press.encode(normal(MouseEncoding::Default)).unwrap(),
+            b"\x1b[M #\""
+        );
+        assert_eq!(
+            release.encode(normal(MouseEncoding::Default)).unwrap(),
+            b"\x1b[M##\""
+        );
+        assert_eq!(
+            release.encode(normal(MouseEncoding::Urxvt)).unwrap(),
+            b"\x1b[35;3;2M"
+        );
+
+        let far = press.with_position(UVec2::new(300, 0));
+        assert_eq!(far.encode(normal(MouseEncoding::Default)), None);
+        assert_eq!(
+            far.encode(normal(MouseEncoding::Utf8)).unwrap(),
+            "\x1b[M \u{14d}!".as_bytes()
+        );
+    }
+
+    #[test]
+    fn reports_what_the_tracking_asks_for() {
+        let drag = MouseEvent::from_sgr(32 + 4, 3, 2, false).unwrap();
+        let movement = MouseEvent::from_sgr(35, 3, 2, false).unwrap();
+        let release = MouseEvent::from_sgr(0, 3, 2, true).unwrap();
+        let sgr = |tracking| modes(tracking, MouseEncoding::Sgr);
+
+        assert_eq!(drag.encode(sgr(MouseTracking::Normal)), None);
+        assert_eq!(
+            drag.encode(sgr(MouseTracking::ButtonEvent)).unwrap(),
+            b"\x1b[<36;3;2M"
+        );
+        assert_eq!(movement.encode(sgr(MouseTracking::ButtonEvent)), None);
+        assert!(movement.encode(sgr(MouseTracking::AnyEvent)).is_some());
+        assert_eq!(release.encode(sgr(MouseTracking::X10)), None);
+
+        let shift_press = MouseEvent::from_sgr(4, 3, 2, false).unwrap();
+        assert_eq!(
+            shift_press.encode(sgr(MouseTracking::X10)).unwrap(),
+            b"\x1b[<0;3;2M"
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{MouseEncoding, MouseTracking};
+
+/// The mouse reporting a program running in a pane asked for, which decides whether mouse
+/// events over the pane are sent to it and how.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MouseModes {
+    tracking: MouseTracking,
+    encoding: MouseEncoding,
+}
+
+impl MouseModes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_tracking(&self) -> MouseTracking {
+        self.tracking
+    }
+
+    pub fn set_tracking(&mut self, tracking: MouseTracking) {
+        self.tracking = tracking;
+    }
+
+    pub fn get_encoding(&self) -> MouseEncoding {
+        self.encoding
+    }
+
+    pub fn set_encoding(&mut self, encoding: MouseEncoding) {
+        self.encoding = encoding;
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{MouseAction, MouseButton};
+
+/// Which mouse events a program running in a pane asked to be sent, from the private modes it
+/// set last.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MouseTracking {
+    #[default]
+    None,
+    /// Button presses only, without modifiers (mode 9).
+    X10,
+    /// Button presses and releases (mode 1000).
+    Normal,
+    /// Also moving the mouse while a button is held (mode 1002).
+    ButtonEvent,
+    /// Also moving the mouse with no button held (mode 1003).
+    AnyEvent,
+}
+
+impl MouseTracking {
+    /// The tracking a private mode switches on, if it is one of the mouse tracking modes.
+    pub fn from_mode(mode: u16) -> Option<Self> {
+        let tracking = match mode {
+            9 => Self::X10,
+            1000 => Self::Normal,
+            1002 => Self::ButtonEvent,
+            1003 => Self::AnyEvent,
+            _ => return None,
+        };
+
+        Some(tracking)
+    }
+
+    /// The private mode switching this tracking on, the reverse of `from_mode`.
+    pub fn get_mode(&self) -> Option<u16> {
+        match self {
+            Self::None => None,
+            Self::X10 => Some(9),
+            Self::Normal => Some(1000),
+            Self::ButtonEvent => Some(1002),
+            Self::AnyEvent => Some(1003),
+        }
+    }
+
+    /// Whether an event is reported with this tracking.
+    pub fn reports(&self, action: MouseAction, button: MouseButton) -> bool {
+        match self {
+            Self::None => false,
+            Self::X10 => action == MouseAction::Press,
+            Self::Normal => matches!(action, MouseAction::Press | MouseAction::Release),
+            Self::ButtonEvent => action != MouseAction::Move && button != MouseButton::None,
+            Self::AnyEvent => true,
+        }
+    }
+}
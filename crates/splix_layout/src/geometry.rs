@@ -0,0 +1,25 @@
+use glam::UVec2;
+
+/// A rectangle of cells within a window.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Geometry {
+    position: UVec2,
+    dimensions: UVec2,
+}
+
+impl Geometry {
+    pub fn new(position: UVec2, dimensions: UVec2) -> Self {
+        Self {
+            position,
+            dimensions,
+        }
+    }
+
+    pub fn get_position(&self) -> UVec2 {
+        self.position
+    }
+
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+}
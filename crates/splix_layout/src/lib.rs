@@ -0,0 +1,441 @@
+mod geometry;
+mod split_direction;
+
+use glam::UVec2;
+
+pub use geometry::Geometry;
+pub use split_direction::SplitDirection;
+use splix_id::PaneId;
+
+/// How the panes of a window divide its area, as a tree of splits.
+#[derive(Clone, Debug)]
+pub struct Layout {
+    root: LayoutCell,
+}
+
+/// The cells of a split share its length in proportion to their weights.
+#[derive(Clone, Debug)]
+enum LayoutCell {
+    Pane(PaneId),
+    Split(SplitDirection, Vec<LayoutCell>, Vec<u32>),
+}
+
+/// Panes and separators are at least this large along the split.
+const MINIMUM_CELL_LENGTH: u32 = 1;
+
+/// The weight of cells in splits that were never resized, which share the space evenly.
+const EVEN_WEIGHT: u32 = 1;
+
+impl Layout {
+    pub fn new(pane: PaneId) -> Self {
+        Self {
+            root: LayoutCell::Pane(pane),
+        }
+    }
+
+    /// Places `new_pane` after `pane`, sharing the space `pane` used to take.
+    ///
+    /// Returns `false` if `pane` isn't part of the layout.
+    pub fn split(&mut self, pane: PaneId, new_pane: PaneId, direction: SplitDirection) -> bool {
+        Self::split_cell(&mut self.root, pane, new_pane, direction)
+    }
+
+    /// Returns `false` if `pane` isn't part of the layout or is the last pane in it.
+    pub fn remove(&mut self, pane: PaneId) -> bool {
+        let removed = Self::remove_from_cell(&mut self.root, pane);
+
+        // Splits left with a single cell are replaced by that cell.
+        if let LayoutCell::Split(_, cells, _) = &mut self.root
+            && cells.len() == 1
+        {
+            self.root = cells.remove(0);
+        }
+
+        removed
+    }
+
+    /// Moves the separator following `pane` along `direction` to `position`, counted from the
+    /// edge of the window, as far as the panes on either side can shrink. The other cells of the
+    /// split keep their current lengths from then on.
+    ///
+    /// Returns `false` if no separator follows `pane` in that direction.
+    pub fn move_border(
+        &mut self,
+        dimensions: UVec2,
+        pane: PaneId,
+        direction: SplitDirection,
+        position: u32,
+    ) -> bool {
+        Self::move_cell_border(
+            &mut self.root,
+            Geometry::new(UVec2::ZERO, dimensions),
+            pane,
+            direction,
+            position,
+        )
+    }
+
+    /// Splits the space of every split between its cells, with a line of separators between
+    /// neighboring cells.
+    pub fn get_pane_geometries(&self, dimensions: UVec2) -> Vec<(PaneId, Geometry)> {
+        let mut geometries = Vec::new();
+        Self::collect_pane_geometries(
+            &self.root,
+            Geometry::new(UVec2::ZERO, dimensions),
+            &mut geometries,
+        );
+
+        geometries
+    }
+
+    /// Describes the layout the way tmux does, like `09fa,80x24,0,0{39x24,0,0,1,40x24,40,0,2}`,
+    /// with panes side by side in braces, stacked ones in brackets, and a checksum up front.
+    pub fn describe(&self, dimensions: UVec2) -> String {
+        let mut description = String::new();
+        Self::describe_cell(
+            &self.root,
+            Geometry::new(UVec2::ZERO, dimensions),
+            &mut description,
+        );
+
+        let checksum = description.bytes().fold(0u16, |checksum, byte| {
+            (checksum >> 1)
+                .wrapping_add((checksum & 1) << 15)
+                .wrapping_add(byte as u16)
+        });
+
+        format!("{checksum:04x},{description}")
+    }
+
+    fn split_cell(
+        cell: &mut LayoutCell,
+        pane: PaneId,
+        new_pane: PaneId,
+        direction: SplitDirection,
+    ) -> bool {
+        match cell {
+            LayoutCell::Pane(cell_pane) if *cell_pane == pane => {
+                *cell = LayoutCell::Split(
+                    direction,
+                    vec![LayoutCell::Pane(pane), LayoutCell::Pane(new_pane)],
+                    vec![EVEN_WEIGHT, EVEN_WEIGHT],
+                );
+                true
+            }
+            LayoutCell::Pane(_) => false,
+            LayoutCell::Split(split_direction, cells, weights) => {
+                // Splitting a pane along its parent's direction adds a sibling instead of nesting,
+                // which gets an average share of the space.
+                if *split_direction == direction
+                    && let Some(index) = cells.iter().position(
+                        |cell| matches!(cell, LayoutCell::Pane(cell_pane) if *cell_pane == pane),
+                    )
+                {
+                    let weight = weights.iter().sum::<u32>() / weights.len() as u32;
+                    cells.insert(index + 1, LayoutCell::Pane(new_pane));
+                    weights.insert(index + 1, weight.max(EVEN_WEIGHT));
+                    return true;
+                }
+
+                cells
+                    .iter_mut()
+                    .any(|cell| Self::split_cell(cell, pane, new_pane, direction))
+            }
+        }
+    }
+
+    fn remove_from_cell(cell: &mut LayoutCell, pane: PaneId) -> bool {
+        let LayoutCell::Split(_, cells, weights) = cell else {
+            return false;
+        };
+
+        if let Some(index) = cells
+            .iter()
+            .position(|cell| matches!(cell, LayoutCell::Pane(cell_pane) if *cell_pane == pane))
+        {
+            cells.remove(index);
+            weights.remove(index);
+            return true;
+        }
+
+        for cell in cells.iter_mut() {
+            if Self::remove_from_cell(cell, pane) {
+                if let LayoutCell::Split(_, children, _) = cell
+                    && children.len() == 1
+                {
+                    *cell = children.remove(0);
+                }
+
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn move_cell_border(
+        cell: &mut LayoutCell,
+        geometry: Geometry,
+        pane: PaneId,
+        direction: SplitDirection,
+        position: u32,
+    ) -> bool {
+        let LayoutCell::Split(split_direction, cells, weights) = cell else {
+            return false;
+        };
+        let Some(index) = cells.iter().position(|cell| Self::contains(cell, pane)) else {
+            return false;
+        };
+
+        let cell_geometries = Self::split_geometry(geometry, *split_direction, weights);
+        // The separator closest to the pane is the one to move.
+        if Self::move_cell_border(
+            &mut cells[index],
+            cell_geometries[index],
+            pane,
+            direction,
+            position,
+        ) {
+            return true;
+        }
+        if *split_direction != direction || index + 1 == cells.len() {
+            return false;
+        }
+
+        let axis = Self::axis(direction);
+        let mut lengths: Vec<u32> = cell_geometries
+            .iter()
+            .map(|geometry| (geometry.get_dimensions() * axis).element_sum())
+            .collect();
+        let start = (cell_geometries[index].get_position() * axis).element_sum();
+        let combined_length = lengths[index] + lengths[index + 1];
+        let length = position
+            .saturating_sub(start)
+            .min(combined_length.saturating_sub(MINIMUM_CELL_LENGTH))
+            .max(MINIMUM_CELL_LENGTH);
+
+        lengths[index] = length;
+        lengths[index + 1] = combined_length.saturating_sub(length);
+        *weights = lengths;
+        true
+    }
+
+    fn contains(cell: &LayoutCell, pane: PaneId) -> bool {
+        match cell {
+            LayoutCell::Pane(cell_pane) => *cell_pane == pane,
+            LayoutCell::Split(_, cells, _) => cells.iter().any(|cell| Self::contains(cell, pane)),
+        }
+    }
+
+    fn collect_pane_geometries(
+        cell: &LayoutCell,
+        geometry: Geometry,
+        geometries: &mut Vec<(PaneId, Geometry)>,
+    ) {
+        match cell {
+            LayoutCell::Pane(pane) => geometries.push((*pane, geometry)),
+            LayoutCell::Split(direction, cells, weights) => {
+                let cell_geometries = Self::split_geometry(geometry, *direction, weights);
+                for (cell, geometry) in cells.iter().zip(cell_geometries) {
+                    Self::collect_pane_geometries(cell, geometry, geometries);
+                }
+            }
+        }
+    }
+
+    fn describe_cell(cell: &LayoutCell, geometry: Geometry, description: &mut String) {
+        let dimensions = geometry.get_dimensions();
+        let position = geometry.get_position();
+        description.push_str(&format!(
+            "{}x{},{},{}",
+            dimensions.x, dimensions.y, position.x, position.y
+        ));
+
+        let (direction, cells, weights) = match cell {
+            LayoutCell::Pane(pane) => {
+                description.push_str(&format!(",{}", pane.get()));
+                return;
+            }
+            LayoutCell::Split(direction, cells, weights) => (direction, cells, weights),
+        };
+
+        let (open, close) = match direction {
+            SplitDirection::Horizontal => ('{', '}'),
+            SplitDirection::Vertical => ('[', ']'),
+        };
+
+        description.push(open);
+        let cell_geometries = Self::split_geometry(geometry, *direction, weights);
+        for (index, (cell, geometry)) in cells.iter().zip(cell_geometries).enumerate() {
+            if index > 0 {
+                description.push(',');
+            }
+            Self::describe_cell(cell, geometry, description);
+        }
+        description.push(close);
+    }
+
+    /// Divides `geometry` into cells along `direction` in proportion to `weights`, leaving a
+    /// separator between neighboring cells.
+    fn split_geometry(
+        geometry: Geometry,
+        direction: SplitDirection,
+        weights: &[u32],
+    ) -> Vec<Geometry> {
+        let axis = Self::axis(direction);
+        let count = weights.len();
+        let total_length = (geometry.get_dimensions() * axis).element_sum();
+        let available_length = total_length.saturating_sub(count as u32 - 1) as u64;
+        let total_weight = weights
+            .iter()
+            .map(|weight| *weight as u64)
+            .sum::<u64>()
+            .max(1);
+
+        let mut geometries = Vec::with_capacity(count);
+        let mut offset = 0;
+        for (index, weight) in weights.iter().enumerate() {
+            // The last cell takes whatever the division left over.
+            let length = if index == count - 1 {
+                total_length.saturating_sub(offset)
+            } else {
+                (available_length * *weight as u64 / total_weight) as u32
+            }
+            .max(MINIMUM_CELL_LENGTH);
+
+            let dimensions = geometry.get_dimensions() * (UVec2::ONE - axis) + axis * length;
+            geometries.push(Geometry::new(
+                geometry.get_position() + axis * offset,
+                dimensions,
+            ));
+
+            offset += length + 1;
+        }
+
+        geometries
+    }
+
+    fn axis(direction: SplitDirection) -> UVec2 {
+        match direction {
+            SplitDirection::Horizontal => UVec2::X,
+            SplitDirection::Vertical => UVec2::Y,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_id::{PaneId, SessionId, WindowId};
+
+    use super::{Geometry, Layout, SplitDirection};
+
+    fn pane(id: usize) -> PaneId {
+        PaneId::new(id, WindowId::new(0, SessionId::new(0)))
+    }
+
+    #[test]
+    fn single_pane_fills_window() {
+        let layout = Layout::new(pane(0));
+
+        assert_eq!(
+            layout.get_pane_geometries(UVec2::new(80, 24)),
+            vec![(pane(0), Geometry::new(UVec2::ZERO, UVec2::new(80, 24)))]
+        );
+    }
+
+    #[test]
+    fn horizontal_split_places_panes_side_by_side() {
+        let mut layout = Layout::new(pane(0));
+        assert!(layout.split(pane(0), pane(1), SplitDirection::Horizontal));
+
+        assert_eq!(
+            layout.get_pane_geometries(UVec2::new(81, 24)),
+            vec![
+                (pane(0), Geometry::new(UVec2::ZERO, UVec2::new(40, 24))),
+                (
+                    pane(1),
+                    Geometry::new(UVec2::new(41, 0), UVec2::new(40, 24))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_split_divides_only_the_split_pane() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Horizontal);
+        layout.split(pane(1), pane(2), SplitDirection::Vertical);
+
+        assert_eq!(
+            layout.get_pane_geometries(UVec2::new(81, 25)),
+            vec![
+                (pane(0), Geometry::new(UVec2::ZERO, UVec2::new(40, 25))),
+                (
+                    pane(1),
+                    Geometry::new(UVec2::new(41, 0), UVec2::new(40, 12))
+                ),
+                (
+                    pane(2),
+                    Geometry::new(UVec2::new(41, 13), UVec2::new(40, 12))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn describes_layout_like_tmux() {
+        let mut layout = Layout::new(pane(0));
+        assert_eq!(layout.describe(UVec2::new(80, 24)), "b25d,80x24,0,0,0");
+
+        layout.split(pane(0), pane(1), SplitDirection::Horizontal);
+        layout.split(pane(1), pane(2), SplitDirection::Vertical);
+        assert_eq!(
+            layout.describe(UVec2::new(81, 25)),
+            "d17e,81x25,0,0{40x25,0,0,0,40x25,41,0[40x12,41,0,1,40x12,41,13,2]}"
+        );
+    }
+
+    #[test]
+    fn moving_border_resizes_neighbors() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Horizontal);
+        layout.split(pane(1), pane(2), SplitDirection::Vertical);
+        let dimensions = UVec2::new(81, 25);
+
+        assert!(layout.move_border(dimensions, pane(0), SplitDirection::Horizontal, 20));
+        assert!(layout.move_border(dimensions, pane(1), SplitDirection::Vertical, 5));
+        assert!(!layout.move_border(dimensions, pane(2), SplitDirection::Vertical, 5));
+        assert_eq!(
+            layout.describe(dimensions),
+            "11e9,81x25,0,0{20x25,0,0,0,60x25,21,0[60x5,21,0,1,60x19,21,6,2]}"
+        );
+
+        // The panes keep their share when the window is resized, and don't shrink to nothing.
+        assert_eq!(
+            layout.get_pane_geometries(UVec2::new(161, 25))[0],
+            (pane(0), Geometry::new(UVec2::ZERO, UVec2::new(40, 25)))
+        );
+        layout.move_border(dimensions, pane(0), SplitDirection::Horizontal, 100);
+        assert_eq!(
+            layout.get_pane_geometries(dimensions)[1].1.get_dimensions(),
+            UVec2::new(1, 5)
+        );
+    }
+
+    #[test]
+    fn removing_pane_gives_its_space_back() {
+        let mut layout = Layout::new(pane(0));
+        layout.split(pane(0), pane(1), SplitDirection::Horizontal);
+        layout.split(pane(1), pane(2), SplitDirection::Vertical);
+
+        assert!(layout.remove(pane(2)));
+        assert!(layout.remove(pane(0)));
+        assert!(!layout.remove(pane(1)));
+
+        assert_eq!(
+            layout.get_pane_geometries(UVec2::new(80, 24)),
+            vec![(pane(1), Geometry::new(UVec2::ZERO, UVec2::new(80, 24)))]
+        );
+    }
+}
@@ -0,0 +1,7 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SplitDirection {
+    /// Places panes side by side, separated by a vertical line.
+    Horizontal,
+    /// Stacks panes on top of each other, separated by a horizontal line.
+    Vertical,
+}
@@ -1,9 +1,184 @@
-use splix::Splix;
+use std::{env, process::Command};
 
-#[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    let mut splix = Splix::new()?;
+use clap::{Parser, Subcommand};
+use splix::{ConfigFile, Splix};
+use splix_client::{Client, Connection, ControlClient, ServerLauncher};
+use splix_command::SplixCommand;
+use splix_socket::SocketPathResolver;
+use splix_terminal::TerminalConfig;
+use splix_terminfo::Terminfo;
+use tokio::runtime::Runtime;
+
+const START_SERVER_SUBCOMMAND: &str = "start-server";
+const NEW_SESSION_SUBCOMMAND: &str = "new-session";
+
+/// A terminal multiplexer. Without a command, a new session is created and attached to.
+#[derive(Parser)]
+#[command(name = "splix", version)]
+struct Cli {
+    /// Attach in control mode, reading commands from stdin and printing text replies and
+    /// notifications instead of drawing the session
+    #[arg(short = 'C')]
+    control: bool,
+    #[command(subcommand)]
+    command: Option<CliCommand>,
+}
+
+#[derive(Subcommand)]
+enum CliCommand {
+    /// Install the terminfo entry describing the terminal panes run in
+    InstallTerminfo,
+    /// Run the server in the foreground
+    #[command(hide = true)]
+    StartServer,
+    /// Attach to a session, the most recently used one by default
+    #[command(alias = "attach")]
+    AttachSession {
+        /// Ignore everything but detaching
+        #[arg(short = 'r')]
+        read_only: bool,
+        #[arg(short = 't', value_name = "target-session")]
+        target: Option<String>,
+    },
+    #[command(flatten)]
+    Server(SplixCommand),
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+    let arguments: Vec<String> = env::args()
+        .skip(1)
+        .skip_while(|argument| argument == "-C")
+        .collect();
+    let control = cli.control;
+
+    let runtime = Runtime::new()?;
+
+    let result = match cli.command {
+        Some(CliCommand::InstallTerminfo) => install_terminfo(),
+        Some(CliCommand::StartServer) => runtime.block_on(start_server()),
+        Some(CliCommand::AttachSession { read_only, target }) => {
+            runtime.block_on(attach(target, read_only, control))
+        }
+        Some(CliCommand::Server(SplixCommand::NewSession {
+            detached: false,
+            print,
+            ..
+        })) => runtime.block_on(new_session(&arguments[1..], print, control)),
+        Some(CliCommand::Server(SplixCommand::NewSession { .. })) => {
+            runtime.block_on(run_command(arguments, true))
+        }
+        Some(CliCommand::Server(_)) => runtime.block_on(run_command(arguments, false)),
+        None => runtime.block_on(new_session(&[], false, control)),
+    };
+
+    // Reading stdin blocks a runtime thread that can't be cancelled, so don't wait for it.
+    runtime.shutdown_background();
+
+    result
+}
+
+async fn start_server() -> anyhow::Result<()> {
+    let socket_path = SocketPathResolver::new().resolve()?;
+
+    let config_file = ConfigFile::load()?;
+
+    let mut splix = Splix::new(TerminalConfig::new(), &socket_path, config_file)?;
     splix.run().await?;
 
     Ok(())
 }
+
+/// Creates a session and attaches to it, starting the server first if it isn't running yet.
+async fn new_session(arguments: &[String], print: bool, control: bool) -> anyhow::Result<()> {
+    let mut connection = connect(true).await?;
+
+    // The server creates the session without attaching, the attachment is made afterwards.
+    let mut new_session_arguments = vec![
+        NEW_SESSION_SUBCOMMAND.to_string(),
+        "-d".to_string(),
+        "-P".to_string(),
+    ];
+    new_session_arguments.extend_from_slice(arguments);
+    let session = connection.run_command(new_session_arguments).await?;
+    let session = session.trim().to_string();
+    if print {
+        println!("{session}");
+    }
+
+    run_client(connection, Some(session), false, control).await
+}
+
+async fn attach(target: Option<String>, read_only: bool, control: bool) -> anyhow::Result<()> {
+    let connection = connect(false).await?;
+
+    run_client(connection, target, read_only, control).await
+}
+
+async fn run_client(
+    connection: Connection,
+    target: Option<String>,
+    read_only: bool,
+    control: bool,
+) -> anyhow::Result<()> {
+    if control {
+        let mut client = ControlClient::attach(connection, target).await?;
+        client.run().await?;
+        println!("%exit");
+
+        return Ok(());
+    }
+
+    let client = Client::attach(connection, target, read_only).await?;
+    run_terminal_client(client).await
+}
+
+async fn run_terminal_client(mut client: Client) -> anyhow::Result<()> {
+    let exit = client.run().await?;
+
+    // Restore the terminal before telling the user why the client stopped.
+    drop(client);
+    println!("{exit}");
+
+    Ok(())
+}
+
+/// Runs a command on the server and prints its output.
+async fn run_command(arguments: Vec<String>, start_server: bool) -> anyhow::Result<()> {
+    let mut connection = connect(start_server).await?;
+
+    let output = connection.run_command(arguments).await?;
+    print!("{output}");
+
+    Ok(())
+}
+
+/// Only creating sessions starts the server, every other command needs one to be running.
+async fn connect(start_server: bool) -> anyhow::Result<Connection> {
+    let socket_path = SocketPathResolver::new().resolve()?;
+
+    let server_launcher = if start_server {
+        Some(server_launcher()?)
+    } else {
+        None
+    };
+
+    Ok(Connection::open(&socket_path, server_launcher.as_ref()).await?)
+}
+
+fn server_launcher() -> anyhow::Result<ServerLauncher> {
+    let mut server_command = Command::new(env::current_exe()?);
+    server_command.arg(START_SERVER_SUBCOMMAND);
+
+    Ok(ServerLauncher::new(server_command))
+}
+
+fn install_terminfo() -> anyhow::Result<()> {
+    let installation_directory = Terminfo::new().install()?;
+    println!(
+        "installed the splix terminfo entry into {}",
+        installation_directory.display()
+    );
+
+    Ok(())
+}
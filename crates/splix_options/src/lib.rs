@@ -0,0 +1,11 @@
+mod option_definition;
+mod option_scope;
+mod option_type;
+mod option_value;
+mod options;
+
+pub use option_definition::OptionDefinition;
+pub use option_scope::OptionScope;
+pub use option_type::OptionType;
+pub use option_value::OptionValue;
+pub use options::Options;
@@ -0,0 +1,175 @@
+use crate::{OptionScope, OptionType, OptionValue, Options};
+
+/// An option Splix knows about: what it applies to, what values it takes and what it is when
+/// nothing sets it.
+#[derive(Debug)]
+pub struct OptionDefinition {
+    name: &'static str,
+    scope: OptionScope,
+    option_type: OptionType,
+    default: &'static str,
+}
+
+/// Every option, by name.
+const DEFINITIONS: &[OptionDefinition] = &[
+    // Empty for the shell of the user's environment.
+    OptionDefinition::new("default-shell", OptionScope::Server, OptionType::String, ""),
+    // The `TERM` of new panes, empty for `splix` when its terminfo entry is installed.
+    OptionDefinition::new(
+        "default-terminal",
+        OptionScope::Server,
+        OptionType::String,
+        "",
+    ),
+    // Only read when the server starts, so only useful in the config file.
+    OptionDefinition::new(
+        "event-channel-capacity",
+        OptionScope::Server,
+        OptionType::Number {
+            maximum: u32::MAX as u64,
+        },
+        "1024",
+    ),
+    // Starts shells as login shells, which read the profile of the user.
+    OptionDefinition::new("login-shell", OptionScope::Server, OptionType::Flag, "off"),
+    OptionDefinition::new(
+        "mode-keys",
+        OptionScope::Window,
+        OptionType::Choice(&["emacs", "vi"]),
+        "emacs",
+    ),
+    // Lets clients' terminals report the mouse.
+    OptionDefinition::new("mouse", OptionScope::Session, OptionType::Flag, "off"),
+    OptionDefinition::new("prefix", OptionScope::Session, OptionType::Key, "C-b"),
+    OptionDefinition::new("prefix2", OptionScope::Session, OptionType::Key, "None"),
+    // Keeps panes whose program exited around until they're killed or respawned.
+    OptionDefinition::new("remain-on-exit", OptionScope::Pane, OptionType::Flag, "on"),
+    // Milliseconds.
+    OptionDefinition::new(
+        "repeat-time",
+        OptionScope::Session,
+        OptionType::Number {
+            maximum: u32::MAX as u64,
+        },
+        "500",
+    ),
+    // Formats expanded with the variables of the active pane.
+    OptionDefinition::new(
+        "status-left",
+        OptionScope::Session,
+        OptionType::String,
+        "[#{session_name}] ",
+    ),
+    OptionDefinition::new(
+        "status-right",
+        OptionScope::Session,
+        OptionType::String,
+        "#{?client_readonly, (read-only),}",
+    ),
+    // How sessions are sized for the clients attached to them.
+    OptionDefinition::new(
+        "window-size",
+        OptionScope::Session,
+        OptionType::Choice(&["smallest", "largest", "latest"]),
+        "smallest",
+    ),
+];
+
+impl OptionDefinition {
+    const fn new(
+        name: &'static str,
+        scope: OptionScope,
+        option_type: OptionType,
+        default: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            scope,
+            option_type,
+            default,
+        }
+    }
+
+    pub fn find(name: &str) -> splix_error::Result<&'static Self> {
+        DEFINITIONS
+            .iter()
+            .find(|definition| definition.name == name)
+            .ok_or_else(|| splix_error::Error::UnknownOption(name.to_string()))
+    }
+
+    /// Sorted by name.
+    pub fn get_all() -> &'static [Self] {
+        DEFINITIONS
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The most specific level the option can be set at.
+    pub fn get_scope(&self) -> OptionScope {
+        self.scope
+    }
+
+    pub fn parse(&self, value: &str) -> splix_error::Result<OptionValue> {
+        self.option_type
+            .parse(value)
+            .ok_or_else(|| splix_error::Error::InvalidOptionValue {
+                option: self.name.to_string(),
+                value: value.to_string(),
+            })
+    }
+
+    pub fn get_default(&self) -> OptionValue {
+        self.parse(self.default)
+            .expect("option defaults are valid values")
+    }
+
+    /// The value of the first of `levels`, from the most specific one up, that sets the option,
+    /// or the default when none does.
+    pub fn resolve<'a>(&self, levels: impl IntoIterator<Item = &'a Options>) -> OptionValue {
+        levels
+            .into_iter()
+            .find_map(|options| options.get(self.name))
+            .cloned()
+            .unwrap_or_else(|| self.get_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFINITIONS, OptionDefinition};
+    use crate::{OptionValue, Options};
+
+    #[test]
+    fn defaults_are_valid_and_sorted() {
+        for definition in DEFINITIONS {
+            definition.parse(definition.default).unwrap();
+        }
+        assert!(DEFINITIONS.is_sorted_by_key(|definition| definition.name));
+    }
+
+    #[test]
+    fn resolves_the_most_specific_value() {
+        let definition = OptionDefinition::find("mode-keys").unwrap();
+        let mut window = Options::new();
+        let mut server = Options::new();
+        assert_eq!(
+            definition.resolve([&window, &server]),
+            OptionValue::String(String::from("emacs"))
+        );
+
+        server.set(definition, definition.parse("vi").unwrap());
+        assert_eq!(
+            definition.resolve([&window, &server]),
+            OptionValue::String(String::from("vi"))
+        );
+
+        window.set(definition, definition.parse("emacs").unwrap());
+        assert_eq!(
+            definition.resolve([&window, &server]),
+            OptionValue::String(String::from("emacs"))
+        );
+        assert!(OptionDefinition::find("mode-key").is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use std::fmt::{self, Display, Formatter};
+
+/// What an option applies to, from the whole server down to a single pane. Options can be set
+/// for their own scope and the broader ones above it, and whatever doesn't set an option itself
+/// inherits it from the level above.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OptionScope {
+    Server,
+    Session,
+    Window,
+    Pane,
+}
+
+impl Display for OptionScope {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = match self {
+            Self::Server => "server",
+            Self::Session => "session",
+            Self::Window => "window",
+            Self::Pane => "pane",
+        };
+
+        write!(f, "{name}")
+    }
+}
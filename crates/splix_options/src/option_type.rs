@@ -0,0 +1,72 @@
+use splix_key::Key;
+
+use crate::OptionValue;
+
+/// What values an option takes, and how they're written in commands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OptionType {
+    String,
+    /// Larger numbers are capped to `maximum`.
+    Number {
+        maximum: u64,
+    },
+    /// `on` or `off`.
+    Flag,
+    /// A key name, or `None` for no key.
+    Key,
+    /// One of a fixed set of words.
+    Choice(&'static [&'static str]),
+}
+
+impl OptionType {
+    pub fn parse(&self, value: &str) -> Option<OptionValue> {
+        match self {
+            Self::String => Some(OptionValue::String(value.to_string())),
+            Self::Number { maximum } => value
+                .parse::<u64>()
+                .ok()
+                .map(|value| OptionValue::Number(value.min(*maximum))),
+            Self::Flag => match value {
+                "on" | "yes" | "1" => Some(OptionValue::Flag(true)),
+                "off" | "no" | "0" => Some(OptionValue::Flag(false)),
+                _ => None,
+            },
+            Self::Key => match value {
+                "None" => Some(OptionValue::Key(None)),
+                _ => value.parse::<Key>().ok().map(Some).map(OptionValue::Key),
+            },
+            Self::Choice(choices) => choices
+                .contains(&value)
+                .then(|| OptionValue::String(value.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OptionType;
+    use crate::OptionValue;
+
+    #[test]
+    fn parses_values_of_each_type() {
+        let number = OptionType::Number { maximum: 1000 };
+        assert_eq!(number.parse("500"), Some(OptionValue::Number(500)));
+        assert_eq!(number.parse("5000"), Some(OptionValue::Number(1000)));
+        assert_eq!(number.parse("-1"), None);
+        assert_eq!(
+            OptionType::Flag.parse("off"),
+            Some(OptionValue::Flag(false))
+        );
+        assert_eq!(OptionType::Flag.parse("maybe"), None);
+        assert_eq!(
+            OptionType::Key.parse("C-a"),
+            Some(OptionValue::Key(Some("C-a".parse().unwrap())))
+        );
+        assert_eq!(OptionType::Key.parse("None"), Some(OptionValue::Key(None)));
+        assert_eq!(
+            OptionType::Choice(&["vi", "emacs"]).parse("vi"),
+            Some(OptionValue::String(String::from("vi")))
+        );
+        assert_eq!(OptionType::Choice(&["vi", "emacs"]).parse("ed"), None);
+    }
+}
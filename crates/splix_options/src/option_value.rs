@@ -0,0 +1,57 @@
+use std::fmt::{self, Display, Formatter};
+
+use splix_key::Key;
+
+/// The value of an option, written back the way it's given in commands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionValue {
+    /// Also the value of options choosing between words.
+    String(String),
+    Number(u64),
+    Flag(bool),
+    Key(Option<Key>),
+}
+
+impl OptionValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<u64> {
+        match self {
+            Self::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_flag(&self) -> Option<bool> {
+        match self {
+            Self::Flag(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Keys set to `None` are `Some(None)`.
+    pub fn as_key(&self) -> Option<Option<Key>> {
+        match self {
+            Self::Key(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl Display for OptionValue {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::String(value) => write!(f, "{value}"),
+            Self::Number(value) => write!(f, "{value}"),
+            Self::Flag(true) => write!(f, "on"),
+            Self::Flag(false) => write!(f, "off"),
+            Self::Key(Some(key)) => write!(f, "{key}"),
+            Self::Key(None) => write!(f, "None"),
+        }
+    }
+}
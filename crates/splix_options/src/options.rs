@@ -0,0 +1,29 @@
+use std::collections::BTreeMap;
+
+use crate::{OptionDefinition, OptionValue};
+
+/// The options set at one level, e.g. for one window. Options that aren't set are inherited
+/// from the level above.
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    values: BTreeMap<&'static str, OptionValue>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&OptionValue> {
+        self.values.get(name)
+    }
+
+    pub fn set(&mut self, definition: &OptionDefinition, value: OptionValue) {
+        self.values.insert(definition.get_name(), value);
+    }
+
+    /// Goes back to inheriting the option, returning whether it was set.
+    pub fn unset(&mut self, name: &str) -> bool {
+        self.values.remove(name).is_some()
+    }
+}
@@ -0,0 +1,32 @@
+use crate::Style;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    character: char,
+    style: Style,
+}
+
+impl Cell {
+    pub fn new(character: char, style: Style) -> Self {
+        Self { character, style }
+    }
+
+    pub fn get_character(&self) -> char {
+        self.character
+    }
+
+    pub fn get_style(&self) -> Style {
+        self.style
+    }
+
+    /// Whether the cell shows nothing, not even a background color.
+    pub fn is_blank(&self) -> bool {
+        self.character == ' ' && self.style == Style::default()
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::new(' ', Style::default())
+    }
+}
@@ -0,0 +1,10 @@
+/// The color of a cell's text or background.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Color {
+    /// Whatever the terminal displaying the pane uses by default.
+    #[default]
+    Default,
+    /// One of the 256 colors of the terminal's palette.
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
@@ -0,0 +1,45 @@
+/// Where a pane in copy mode is scrolled to, showing lines of its history in place of the
+/// program's output until it leaves copy mode again.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CopyMode {
+    scroll_offset: usize,
+    exit_at_bottom: bool,
+}
+
+impl CopyMode {
+    /// With `exit_at_bottom`, scrolling back down to the program's output leaves copy mode, like
+    /// after entering it with the mouse wheel.
+    pub fn new(exit_at_bottom: bool) -> Self {
+        Self {
+            scroll_offset: 0,
+            exit_at_bottom,
+        }
+    }
+
+    /// How many lines of history are shown above the screen.
+    pub fn get_scroll_offset(&self) -> usize {
+        self.scroll_offset
+    }
+
+    pub fn scroll_up(&mut self, lines: usize, history_length: usize) {
+        self.scroll_offset = (self.scroll_offset + lines).min(history_length);
+    }
+
+    /// Returns `false` once copy mode should be left.
+    pub fn scroll_down(&mut self, lines: usize) -> bool {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+        !(self.exit_at_bottom && self.scroll_offset == 0)
+    }
+
+    /// Keeps the same lines in view as new ones scroll into the history, unless at the bottom.
+    pub fn follow_history(&mut self, added_lines: usize, history_length: usize) {
+        if self.scroll_offset > 0 {
+            self.scroll_up(added_lines, history_length);
+        }
+    }
+
+    /// Stays within the history once it's shorter, like after the pane was resized.
+    pub fn fit_history(&mut self, history_length: usize) {
+        self.scroll_offset = self.scroll_offset.min(history_length);
+    }
+}
@@ -1,54 +1,517 @@
+use std::collections::VecDeque;
+
+use glam::UVec2;
+use splix_ansi::{AnsiAction, ControlSequence};
+
+use crate::{Cell, Line, Style};
+
+/// The screen of a pane with the lines that scrolled off its top, as drawn by the program
+/// running in it.
 #[derive(Clone)]
 pub struct Grid {
-    data: Vec<Vec<char>>,
+    dimensions: UVec2,
+    lines: Vec<Line>,
+    history: VecDeque<Line>,
+    cursor: UVec2,
+    /// Set after printing into the last column, so the next character wraps to the next line.
+    wrap_pending: bool,
+    style: Style,
+    scroll_top: u32,
+    scroll_bottom: u32,
+    saved_cursor: Option<(UVec2, Style)>,
+    /// The lines of the main screen while the alternate screen is shown.
+    main_screen_lines: Option<Vec<Line>>,
 }
 
+/// How many lines scrolled off the top of the screen are kept.
+const HISTORY_LIMIT: usize = 2000;
+const TAB_WIDTH: u32 = 8;
+
+/// The DEC private modes switching to the alternate screen.
+const ALTERNATE_SCREEN_MODES: [u16; 3] = [47, 1047, 1049];
+
 impl Grid {
-    pub fn new() -> Self {
-        Self { data: Vec::new() }
+    pub fn new(dimensions: UVec2) -> Self {
+        let dimensions = dimensions.max(UVec2::ONE);
+
+        Self {
+            dimensions,
+            lines: Self::blank_lines(dimensions),
+            history: VecDeque::new(),
+            cursor: UVec2::ZERO,
+            wrap_pending: false,
+            style: Style::default(),
+            scroll_top: 0,
+            scroll_bottom: dimensions.y - 1,
+            saved_cursor: None,
+            main_screen_lines: None,
+        }
+    }
+
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+
+    /// The lines currently on screen.
+    pub fn get_lines(&self) -> &[Line] {
+        &self.lines
+    }
+
+    pub fn get_history(&self) -> &VecDeque<Line> {
+        &self.history
+    }
+
+    pub fn get_cursor(&self) -> UVec2 {
+        self.cursor
+    }
+
+    /// Lines below the cursor are dropped first when the screen gets shorter, then lines at the
+    /// top scroll into the history.
+    pub fn resize(&mut self, dimensions: UVec2) {
+        let dimensions = dimensions.max(UVec2::ONE);
+        if dimensions == self.dimensions {
+            return;
+        }
+
+        for line in self
+            .lines
+            .iter_mut()
+            .chain(self.main_screen_lines.iter_mut().flatten())
+        {
+            line.resize(dimensions.x);
+        }
+
+        while self.lines.len() as u32 > dimensions.y {
+            if self.lines.len() as u32 - 1 > self.cursor.y {
+                self.lines.pop();
+            } else {
+                let line = self.lines.remove(0);
+                self.push_history(line);
+                self.cursor.y -= 1;
+            }
+        }
+        self.lines
+            .resize(dimensions.y as usize, Line::new(dimensions.x));
+
+        if let Some(main_screen_lines) = self.main_screen_lines.as_mut() {
+            main_screen_lines.resize(dimensions.y as usize, Line::new(dimensions.x));
+        }
+
+        self.dimensions = dimensions;
+        self.cursor = self.cursor.min(dimensions - 1);
+        self.wrap_pending = false;
+        self.scroll_top = 0;
+        self.scroll_bottom = dimensions.y - 1;
+    }
+
+    pub fn update(&mut self, action: &AnsiAction) {
+        match action {
+            AnsiAction::Print(c) => self.print(*c),
+            AnsiAction::Execute(c) => self.execute(*c),
+            AnsiAction::EscapeSequence {
+                intermediates,
+                final_character,
+            } if intermediates.is_empty() => self.handle_escape_sequence(*final_character),
+            AnsiAction::EscapeSequence { .. } => {}
+            AnsiAction::ControlSequence(control_sequence) => {
+                self.handle_control_sequence(control_sequence)
+            }
+        }
+    }
+
+    /// Returns the text of the lines from `start` to `end`, where lines are numbered from the
+    /// top of the screen and history lines are negative.
+    ///
+    /// With `escapes` the style of the text is kept as SGR sequences, and with `join_wrapped`
+    /// lines that were wrapped are joined back together.
+    pub fn capture(&self, start: i64, end: i64, escapes: bool, join_wrapped: bool) -> String {
+        let start = start.max(-(self.history.len() as i64));
+        let end = end.min(self.dimensions.y as i64 - 1);
+
+        let mut output = String::new();
+        for index in start..=end {
+            let line = if index < 0 {
+                &self.history[(self.history.len() as i64 + index) as usize]
+            } else {
+                &self.lines[index as usize]
+            };
+            let joined = join_wrapped && line.is_wrapped() && index < end;
+
+            // Trailing blanks of joined lines are part of the text that continues.
+            let mut cells = line.get_cells();
+            if !joined {
+                let trailing_blanks = cells
+                    .iter()
+                    .rev()
+                    .take_while(|cell| cell.get_character() == ' ' && (!escapes || cell.is_blank()))
+                    .count();
+                cells = &cells[..cells.len() - trailing_blanks];
+            }
+
+            let mut style = Style::default();
+            for cell in cells {
+                if escapes && cell.get_style() != style {
+                    style = cell.get_style();
+                    output.push_str(&style.to_sgr());
+                }
+                output.push(cell.get_character());
+            }
+            if style != Style::default() {
+                output.push_str(&Style::default().to_sgr());
+            }
+
+            if !joined {
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+
+    fn print(&mut self, c: char) {
+        if self.wrap_pending {
+            self.lines[self.cursor.y as usize].set_wrapped(true);
+            self.move_cursor_to(0, self.cursor.y);
+            self.line_feed();
+        }
+
+        self.lines[self.cursor.y as usize].set_cell(self.cursor.x, Cell::new(c, self.style));
+
+        if self.cursor.x + 1 < self.dimensions.x {
+            self.cursor.x += 1;
+        } else {
+            self.wrap_pending = true;
+        }
+    }
+
+    fn execute(&mut self, c: char) {
+        match c {
+            '\r' => self.move_cursor_to(0, self.cursor.y),
+            '\n' | '\x0b' | '\x0c' => self.line_feed(),
+            '\x08' => self.move_cursor_to(self.cursor.x.saturating_sub(1), self.cursor.y),
+            '\t' => {
+                let x = (self.cursor.x / TAB_WIDTH + 1) * TAB_WIDTH;
+                self.move_cursor_to(x, self.cursor.y);
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_escape_sequence(&mut self, final_character: char) {
+        match final_character {
+            '7' => self.save_cursor(),
+            '8' => self.restore_cursor(),
+            'D' => self.line_feed(),
+            'E' => {
+                self.move_cursor_to(0, self.cursor.y);
+                self.line_feed();
+            }
+            'M' => self.reverse_index(),
+            'c' => {
+                let history = std::mem::take(&mut self.history);
+                *self = Self::new(self.dimensions);
+                self.history = history;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_control_sequence(&mut self, control_sequence: &ControlSequence) {
+        match control_sequence.get_private_marker() {
+            None => {}
+            Some('?') => return self.handle_private_mode(control_sequence),
+            Some(_) => return,
+        }
+
+        let count = control_sequence.get_parameter(0, 1) as u32;
+        let UVec2 { x, y } = self.cursor;
+
+        match control_sequence.get_final_character() {
+            'A' => self.move_cursor_to(x, y.saturating_sub(count)),
+            'B' | 'e' => self.move_cursor_to(x, y.saturating_add(count)),
+            'C' | 'a' => self.move_cursor_to(x.saturating_add(count), y),
+            'D' => self.move_cursor_to(x.saturating_sub(count), y),
+            'E' => self.move_cursor_to(0, y.saturating_add(count)),
+            'F' => self.move_cursor_to(0, y.saturating_sub(count)),
+            'G' | '`' => self.move_cursor_to(count - 1, y),
+            'd' => self.move_cursor_to(x, count - 1),
+            'H' | 'f' => {
+                self.move_cursor_to(control_sequence.get_parameter(1, 1) as u32 - 1, count - 1)
+            }
+            'J' => self.erase_display(control_sequence.get_parameter(0, 0)),
+            'K' => self.erase_line(control_sequence.get_parameter(0, 0)),
+            '@' => {
+                let blank = self.blank_cell();
+                self.lines[y as usize].insert_cells(x, count, blank);
+            }
+            'P' => {
+                let blank = self.blank_cell();
+                self.lines[y as usize].delete_cells(x, count, blank);
+            }
+            'X' => {
+                let blank = self.blank_cell();
+                self.lines[y as usize].fill(x, x.saturating_add(count), blank);
+            }
+            'L' => self.insert_lines(count),
+            'M' => self.delete_lines(count),
+            'S' => self.scroll_up(count),
+            'T' => self.scroll_down(count),
+            'm' => self
+                .style
+                .apply_sgr(control_sequence.get_parameters_with_subparameters()),
+            'r' => {
+                let top = control_sequence.get_parameter(0, 1) as u32 - 1;
+                let bottom = (control_sequence.get_parameter(1, self.dimensions.y as u16) as u32)
+                    .min(self.dimensions.y)
+                    - 1;
+                if top < bottom {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.move_cursor_to(0, 0);
+                }
+            }
+            's' => self.save_cursor(),
+            'u' => self.restore_cursor(),
+            _ => {}
+        }
+    }
+
+    fn handle_private_mode(&mut self, control_sequence: &ControlSequence) {
+        let enabled = match control_sequence.get_final_character() {
+            'h' => true,
+            'l' => false,
+            _ => return,
+        };
+
+        if control_sequence
+            .get_parameters()
+            .any(|mode| ALTERNATE_SCREEN_MODES.contains(&mode))
+        {
+            self.set_alternate_screen(enabled);
+        }
     }
 
-    pub fn update(&mut self, c: char) {
-        // For now, just append to the last line or create a new line
-        if self.data.is_empty() {
-            self.data.push(Vec::new());
+    /// Programs drawing over the whole screen do so on the alternate screen, which leaves the
+    /// main screen and the history alone.
+    fn set_alternate_screen(&mut self, enabled: bool) {
+        match (enabled, self.main_screen_lines.take()) {
+            (true, None) => {
+                self.save_cursor();
+                self.main_screen_lines = Some(std::mem::replace(
+                    &mut self.lines,
+                    Self::blank_lines(self.dimensions),
+                ));
+            }
+            (false, Some(main_screen_lines)) => {
+                self.lines = main_screen_lines;
+                self.restore_cursor();
+            }
+            (_, main_screen_lines) => self.main_screen_lines = main_screen_lines,
         }
-        self.data.last_mut().unwrap().push(c);
     }
 
-    pub fn new_line(&mut self) {
-        self.data.push(Vec::new());
+    fn move_cursor_to(&mut self, x: u32, y: u32) {
+        self.cursor = UVec2::new(x, y).min(self.dimensions - 1);
+        self.wrap_pending = false;
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved_cursor = Some((self.cursor, self.style));
+    }
+
+    fn restore_cursor(&mut self) {
+        let (cursor, style) = self.saved_cursor.unwrap_or_default();
+        self.move_cursor_to(cursor.x, cursor.y);
+        self.style = style;
     }
 
-    pub fn get_data(&self) -> &[Vec<char>] {
-        &self.data
+    fn line_feed(&mut self) {
+        if self.cursor.y == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.y + 1 < self.dimensions.y {
+            self.cursor.y += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        if self.cursor.y == self.scroll_top {
+            self.scroll_down(1);
+        } else {
+            self.cursor.y = self.cursor.y.saturating_sub(1);
+        }
+    }
+
+    /// Lines scrolled off the top of the whole main screen go into the history.
+    fn scroll_up(&mut self, count: u32) {
+        let top = self.scroll_top as usize;
+        let bottom = self.scroll_bottom as usize;
+
+        for _ in 0..count.min(self.scroll_bottom - self.scroll_top + 1) {
+            let line = self.lines.remove(top);
+            self.lines.insert(bottom, self.blank_line());
+
+            if top == 0 && self.main_screen_lines.is_none() {
+                self.push_history(line);
+            }
+        }
+    }
+
+    fn scroll_down(&mut self, count: u32) {
+        let top = self.scroll_top as usize;
+        let bottom = self.scroll_bottom as usize;
+
+        for _ in 0..count.min(self.scroll_bottom - self.scroll_top + 1) {
+            self.lines.remove(bottom);
+            self.lines.insert(top, self.blank_line());
+        }
+    }
+
+    /// Inserting and deleting lines only affects the scroll region, and only if the cursor is in
+    /// it.
+    fn insert_lines(&mut self, count: u32) {
+        if !(self.scroll_top..=self.scroll_bottom).contains(&self.cursor.y) {
+            return;
+        }
+
+        for _ in 0..count.min(self.scroll_bottom - self.cursor.y + 1) {
+            self.lines.remove(self.scroll_bottom as usize);
+            self.lines.insert(self.cursor.y as usize, self.blank_line());
+        }
+        self.move_cursor_to(0, self.cursor.y);
+    }
+
+    fn delete_lines(&mut self, count: u32) {
+        if !(self.scroll_top..=self.scroll_bottom).contains(&self.cursor.y) {
+            return;
+        }
+
+        for _ in 0..count.min(self.scroll_bottom - self.cursor.y + 1) {
+            self.lines.remove(self.cursor.y as usize);
+            self.lines
+                .insert(self.scroll_bottom as usize, self.blank_line());
+        }
+        self.move_cursor_to(0, self.cursor.y);
+    }
+
+    /// Erases below the cursor with 0, above it with 1 and everything with 2. 3 clears the
+    /// history instead.
+    fn erase_display(&mut self, mode: u16) {
+        let UVec2 { x, y } = self.cursor;
+        let (lines, line) = match mode {
+            0 => (
+                (y as usize + 1)..self.lines.len(),
+                Some((x, self.dimensions.x)),
+            ),
+            1 => (0..y as usize, Some((0, x + 1))),
+            2 => (0..self.lines.len(), None),
+            3 => {
+                self.history.clear();
+                return;
+            }
+            _ => return,
+        };
+
+        let blank_line = self.blank_line();
+        self.lines[lines].fill(blank_line);
+        if let Some((start, end)) = line {
+            let blank = self.blank_cell();
+            self.lines[y as usize].fill(start, end, blank);
+        }
+    }
+
+    /// Erases right of the cursor with 0, left of it with 1 and the whole line with 2.
+    fn erase_line(&mut self, mode: u16) {
+        let x = self.cursor.x;
+        let (start, end) = match mode {
+            0 => (x, self.dimensions.x),
+            1 => (0, x + 1),
+            2 => (0, self.dimensions.x),
+            _ => return,
+        };
+
+        let blank = self.blank_cell();
+        let line = &mut self.lines[self.cursor.y as usize];
+        line.fill(start, end, blank);
+        line.set_wrapped(false);
+    }
+
+    fn blank_cell(&self) -> Cell {
+        Cell::new(' ', self.style.get_erased())
+    }
+
+    fn blank_line(&self) -> Line {
+        Line::filled(self.dimensions.x, self.blank_cell())
+    }
+
+    fn blank_lines(dimensions: UVec2) -> Vec<Line> {
+        vec![Line::new(dimensions.x); dimensions.y as usize]
+    }
+
+    fn push_history(&mut self, line: Line) {
+        self.history.push_back(line);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.pop_front();
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use glam::UVec2;
+    use splix_ansi::AnsiParser;
+
     use super::Grid;
 
+    fn grid(dimensions: UVec2, output: &str) -> Grid {
+        let mut grid = Grid::new(dimensions);
+        feed(&mut grid, output);
+        grid
+    }
+
+    fn feed(grid: &mut Grid, output: &str) {
+        let mut parser = AnsiParser::new();
+        for c in output.chars() {
+            if let Some(action) = parser.advance(c) {
+                grid.update(&action);
+            }
+        }
+    }
+
     #[test]
-    fn new_grid_is_empty() {
-        let grid = Grid::new();
-        assert!(grid.get_data().is_empty());
+    fn new_grid_is_blank() {
+        let grid = Grid::new(UVec2::new(4, 2));
+        assert_eq!(grid.capture(0, 1, false, false), "\n\n");
     }
 
     #[test]
-    fn update_appends_chars() {
-        let mut grid = Grid::new();
-        grid.update('a');
-        grid.update('b');
-        assert_eq!(grid.get_data(), &vec![vec!['a', 'b']]);
+    fn update_prints_at_the_cursor() {
+        let grid = grid(UVec2::new(6, 3), "ab\r\ncd\x1b[1;5Hx");
+        assert_eq!(grid.capture(0, 2, false, false), "ab  x\ncd\n\n");
+        assert_eq!(grid.get_cursor(), UVec2::new(5, 0));
     }
 
     #[test]
-    fn new_line_creates_new_empty_line() {
-        let mut grid = Grid::new();
-        grid.update('a');
-        grid.new_line();
-        grid.update('b');
-        assert_eq!(grid.get_data(), &vec![vec!['a'], vec!['b']]);
+    fn lines_scroll_into_history() {
+        let grid = grid(UVec2::new(4, 2), "1\r\n2\r\n3\r\n4");
+        assert_eq!(grid.get_history().len(), 2);
+        assert_eq!(grid.capture(-10, 10, false, false), "1\n2\n3\n4\n");
+        assert_eq!(grid.capture(-1, 0, false, false), "2\n3\n");
+    }
+
+    #[test]
+    fn captures_wrapped_lines_and_styles() {
+        let grid = grid(UVec2::new(3, 4), "abcdefg\r\n\x1b[1;31mef\x1b[m");
+        assert_eq!(grid.capture(0, 3, false, false), "abc\ndef\ng\nef\n");
+        assert_eq!(grid.capture(0, 3, false, true), "abcdefg\nef\n");
+        assert_eq!(grid.capture(3, 3, true, false), "\x1b[0;1;31mef\x1b[0m\n");
+    }
+
+    #[test]
+    fn alternate_screen_leaves_main_screen_alone() {
+        let mut grid = grid(UVec2::new(4, 2), "main\r\n\x1b[?1049h\x1b[Hfull");
+        assert_eq!(grid.capture(0, 0, false, false), "full\n");
+
+        feed(&mut grid, "\x1b[?1049l");
+        assert_eq!(grid.capture(0, 1, false, false), "main\n\n");
     }
 }
@@ -1,39 +1,352 @@
+mod cell;
+mod color;
+mod copy_mode;
 mod grid;
+mod line;
+mod mode_tracker;
+mod style;
 
-use std::error::Error;
+use std::{error::Error, path::PathBuf, process};
 
-use grid::Grid;
-use tokio::sync::mpsc::{self, Receiver, Sender};
+use glam::UVec2;
+use mode_tracker::ModeTracker;
+use tokio::{
+    sync::{
+        mpsc::{self, Sender, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+    task::JoinHandle,
+};
 
-use splix_event::{Event, GridUpdate, PaneUpdateEvent};
+pub use cell::Cell;
+pub use color::Color;
+pub use copy_mode::CopyMode;
+pub use grid::Grid;
+pub use line::Line;
+use splix_ansi::AnsiParser;
+use splix_event::{Event, GridUpdate, PaneExitEvent, PaneUpdateEvent};
 use splix_id::PaneId;
-use splix_terminal::Terminal;
+use splix_key::{KeyboardModes, MouseModes};
+use splix_options::Options;
+use splix_terminal::{ChildProcess, Command, Terminal, TerminalConfig};
+pub use style::Style;
 
 pub struct Pane {
     id: PaneId,
+    command: Command,
+    terminal_config: TerminalConfig,
+    dimensions: UVec2,
+    parser: AnsiParser,
     grid: Grid,
-    input_sender: Sender<u8>,
+    mode_tracker: ModeTracker,
+    copy_mode: Option<CopyMode>,
+    event_sender: Sender<Event>,
+    input_sender: UnboundedSender<Vec<u8>>,
+    dimensions_sender: watch::Sender<UVec2>,
+    process: ChildProcess,
+    terminal_io_task: JoinHandle<()>,
+    /// Counts respawns, so that events from the programs that ran before can be told apart.
+    generation: u64,
+    exited: bool,
+    options: Options,
 }
 
+/// The handles a pane keeps for talking to the I/O task of its terminal.
+type SpawnedTerminal = (
+    UnboundedSender<Vec<u8>>,
+    watch::Sender<UVec2>,
+    ChildProcess,
+    JoinHandle<()>,
+);
+
+const SPLIX_ENVIRONMENT_VARIABLE: &str = "SPLIX";
+const SPLIX_PANE_ENVIRONMENT_VARIABLE: &str = "SPLIX_PANE";
+
 impl Pane {
-    pub fn new(id: PaneId, event_sender: Sender<Event>) -> splix_error::Result<Self> {
-        let grid = Grid::new();
-        let (input_sender, input_receiver): (Sender<u8>, Receiver<u8>) = mpsc::channel(32);
-        let pane = Self {
+    pub fn new(
+        id: PaneId,
+        command: Command,
+        dimensions: UVec2,
+        terminal_config: TerminalConfig,
+        event_sender: Sender<Event>,
+    ) -> splix_error::Result<Self> {
+        let grid = Grid::new(dimensions);
+        let (input_sender, dimensions_sender, process, terminal_io_task) = Self::spawn_terminal(
+            id,
+            0,
+            &command,
+            dimensions,
+            &terminal_config,
+            event_sender.clone(),
+        )?;
+
+        Ok(Self {
             id,
+            command,
+            terminal_config,
+            dimensions,
+            parser: AnsiParser::new(),
             grid,
+            mode_tracker: ModeTracker::new(),
+            event_sender,
             input_sender,
+            dimensions_sender,
+            process,
+            terminal_io_task,
+            generation: 0,
+            copy_mode: None,
+            exited: false,
+            options: Options::new(),
+        })
+    }
+
+    pub fn get_id(&self) -> PaneId {
+        self.id
+    }
+
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+
+    pub fn get_grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// The options set for this pane alone.
+    pub fn get_options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn get_options_mut(&mut self) -> &mut Options {
+        &mut self.options
+    }
+
+    /// The keyboard modes the program in the pane asked for, which keys sent to it must follow.
+    pub fn get_keyboard_modes(&self) -> KeyboardModes {
+        self.mode_tracker.get_keyboard_modes()
+    }
+
+    /// The mouse reporting the program in the pane asked for, which mouse events sent to it must
+    /// follow.
+    pub fn get_mouse_modes(&self) -> MouseModes {
+        self.mode_tracker.get_mouse_modes()
+    }
+
+    pub fn get_copy_mode(&self) -> Option<&CopyMode> {
+        self.copy_mode.as_ref()
+    }
+
+    /// Does nothing if the pane already is in copy mode.
+    pub fn enter_copy_mode(&mut self, exit_at_bottom: bool) {
+        self.copy_mode.get_or_insert(CopyMode::new(exit_at_bottom));
+    }
+
+    pub fn exit_copy_mode(&mut self) {
+        self.copy_mode = None;
+    }
+
+    /// Runs a command of copy mode, named like those `send-keys -X` takes: `cancel`,
+    /// `scroll-up`, `scroll-down`, `halfpage-up`, `halfpage-down`, `page-up`, `page-down`,
+    /// `history-top` and `history-bottom`.
+    pub fn run_copy_mode_command(&mut self, command: &str) -> splix_error::Result<()> {
+        let history_length = self.grid.get_history().len();
+        let page = self.dimensions.y.max(1) as usize;
+        let Some(copy_mode) = self.copy_mode.as_mut() else {
+            return Err(splix_error::Error::NotInCopyMode(self.id.to_string()));
         };
 
-        // Clone the sender for the async task
-        let task_id = pane.id;
+        let staying = match command {
+            "cancel" => false,
+            "scroll-up" => {
+                copy_mode.scroll_up(1, history_length);
+                true
+            }
+            "halfpage-up" => {
+                copy_mode.scroll_up(page / 2, history_length);
+                true
+            }
+            "page-up" => {
+                copy_mode.scroll_up(page, history_length);
+                true
+            }
+            "history-top" => {
+                copy_mode.scroll_up(history_length, history_length);
+                true
+            }
+            "scroll-down" => copy_mode.scroll_down(1),
+            "halfpage-down" => copy_mode.scroll_down(page / 2),
+            "page-down" => copy_mode.scroll_down(page),
+            "history-bottom" => copy_mode.scroll_down(history_length),
+            command => {
+                return Err(splix_error::Error::UnknownCopyModeCommand(
+                    command.to_string(),
+                ));
+            }
+        };
+
+        if !staying {
+            self.exit_copy_mode();
+        }
+        Ok(())
+    }
+
+    /// The lines to show for the pane, from the history while scrolled up in copy mode.
+    pub fn get_visible_lines(&self) -> Vec<&Line> {
+        let history = self.grid.get_history();
+        let scroll_offset = self
+            .copy_mode
+            .map(|copy_mode| copy_mode.get_scroll_offset())
+            .unwrap_or(0);
+
+        history
+            .range(history.len() - scroll_offset.min(history.len())..)
+            .chain(self.grid.get_lines().iter())
+            .take(self.dimensions.y as usize)
+            .collect()
+    }
+
+    /// Events from the programs the pane ran before it was last respawned carry older ones.
+    pub fn get_generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn is_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// The process started in the pane, which stays the same until the pane is respawned.
+    pub fn get_process(&self) -> &ChildProcess {
+        &self.process
+    }
+
+    /// The working directory of the program in the foreground of the pane.
+    pub fn get_working_directory(&self) -> Option<PathBuf> {
+        self.process.get_foreground_working_directory()
+    }
+
+    pub fn update(&mut self, grid_update: &GridUpdate) {
+        let c = match grid_update {
+            GridUpdate::AppendChar(c) => *c,
+            GridUpdate::NewLine => '\n',
+        };
+
+        if let Some(action) = self.parser.advance(c) {
+            let history_length = self.grid.get_history().len();
+            self.mode_tracker.update(&action);
+            if let Some(reply) = self.mode_tracker.get_reply(&action) {
+                self.process_input(reply.as_bytes());
+            }
+            self.grid.update(&action);
+
+            if let Some(copy_mode) = self.copy_mode.as_mut() {
+                let new_history_length = self.grid.get_history().len();
+                copy_mode.follow_history(
+                    new_history_length.saturating_sub(history_length),
+                    new_history_length,
+                );
+            }
+        }
+    }
+
+    pub fn resize(&mut self, dimensions: UVec2) {
+        if dimensions == self.dimensions {
+            return;
+        }
+
+        self.dimensions = dimensions;
+        self.grid.resize(dimensions);
+        if let Some(copy_mode) = self.copy_mode.as_mut() {
+            copy_mode.fit_history(self.grid.get_history().len());
+        }
+
+        // The terminal is only reachable from its I/O task, which picks up the latest size.
+        self.dimensions_sender.send_replace(dimensions);
+    }
+
+    pub fn mark_exited(&mut self) {
+        self.exited = true;
+    }
+
+    /// Kills whatever is still running in the pane and starts a fresh child in its place.
+    ///
+    /// When `command` is `None` the pane's previous command is executed again.
+    pub fn respawn(
+        &mut self,
+        command: Option<Command>,
+        clear_grid: bool,
+    ) -> splix_error::Result<()> {
+        if let Some(command) = command {
+            self.command = command;
+        }
+
+        // Aborting the task drops its `Terminal`, which hangs up the old process group.
+        self.terminal_io_task.abort();
+
+        let generation = self.generation + 1;
+        let (input_sender, dimensions_sender, process, terminal_io_task) = Self::spawn_terminal(
+            self.id,
+            generation,
+            &self.command,
+            self.dimensions,
+            &self.terminal_config,
+            self.event_sender.clone(),
+        )?;
+        self.input_sender = input_sender;
+        self.dimensions_sender = dimensions_sender;
+        self.process = process;
+        self.terminal_io_task = terminal_io_task;
+        self.generation = generation;
+        self.parser = AnsiParser::new();
+        self.mode_tracker = ModeTracker::new();
+        self.exited = false;
+
+        // Without a history to scroll through, copy mode has nothing left to show.
+        if clear_grid {
+            self.grid = Grid::new(self.dimensions);
+            self.copy_mode = None;
+        }
+
+        Ok(())
+    }
+
+    /// Queues `input` for the pane's program without waiting for it to be written, which happens
+    /// as fast as the program reads it. Nothing is dropped however long the program takes, as
+    /// only the clients typing or pasting decide how much input there is.
+    pub fn process_input(&mut self, input: &[u8]) {
+        if self.exited || input.is_empty() {
+            return;
+        }
+
+        self.input_sender.send(input.to_vec()).ok();
+    }
+
+    fn spawn_terminal(
+        id: PaneId,
+        generation: u64,
+        command: &Command,
+        dimensions: UVec2,
+        terminal_config: &TerminalConfig,
+        event_sender: Sender<Event>,
+    ) -> splix_error::Result<SpawnedTerminal> {
+        let (input_sender, input_receiver) = mpsc::unbounded_channel();
+        let (dimensions_sender, dimensions_receiver) = watch::channel(dimensions);
+
+        let command = Self::add_splix_environment(command, terminal_config, id);
 
         // Create a terminal for the async task
-        let task_terminal = Terminal::new()?;
+        let task_terminal = Terminal::new(&command, terminal_config, dimensions)?;
+        let process = task_terminal.get_process();
 
-        tokio::spawn(async move {
-            if let Err(e) =
-                Self::handle_terminal_io(task_terminal, input_receiver, event_sender, task_id).await
+        let terminal_io_task = tokio::spawn(async move {
+            if let Err(e) = Self::handle_terminal_io(
+                task_terminal,
+                input_receiver,
+                dimensions_receiver,
+                &event_sender,
+                id,
+                generation,
+            )
+            .await
             {
                 println!(
                     "Error while handling terminal I/O: {} / {}",
@@ -41,48 +354,70 @@ impl Pane {
                     e.source().map(|e| e.to_string()).unwrap_or(String::new()),
                 );
             }
-        });
 
-        Ok(pane)
-    }
+            event_sender
+                .send(Event::PaneExit(PaneExitEvent::new(id, generation)))
+                .await
+                .ok();
+        });
 
-    pub fn get_grid(&self) -> &Grid {
-        &self.grid
+        Ok((input_sender, dimensions_sender, process, terminal_io_task))
     }
 
-    pub fn update(&mut self, grid_update: &GridUpdate) {
-        match grid_update {
-            GridUpdate::AppendChar(c) => self.grid.update(*c),
-            GridUpdate::NewLine => self.grid.new_line(),
-        }
-    }
+    /// Lets programs in the pane find the server and pane they are running in, similarly to
+    /// `TMUX` and `TMUX_PANE`.
+    fn add_splix_environment(
+        command: &Command,
+        terminal_config: &TerminalConfig,
+        id: PaneId,
+    ) -> Command {
+        let server_socket = terminal_config
+            .get_server_socket()
+            .map(|server_socket| server_socket.display().to_string())
+            .unwrap_or_default();
 
-    pub async fn process_input(&mut self, input: u8) {
-        self.input_sender.send(input).await.unwrap();
+        command
+            .clone()
+            .with_environment_variable(
+                SPLIX_ENVIRONMENT_VARIABLE,
+                format!(
+                    "{server_socket},{},{}",
+                    process::id(),
+                    id.get_window().get_session().get()
+                ),
+            )
+            .with_environment_variable(SPLIX_PANE_ENVIRONMENT_VARIABLE, id.to_string())
     }
 
     async fn handle_terminal_io(
         mut terminal: Terminal,
-        mut input_receiver: Receiver<u8>,
-        event_sender: Sender<Event>,
+        mut input_receiver: UnboundedReceiver<Vec<u8>>,
+        mut dimensions_receiver: watch::Receiver<UVec2>,
+        event_sender: &Sender<Event>,
         pane_id: PaneId,
+        generation: u64,
     ) -> splix_error::Result<()> {
+        // Input waits here until the PTY takes it, rather than the server waiting on a program
+        // that doesn't read.
+        let mut pending_input = Vec::new();
+
         loop {
             tokio::select! {
-                Some(input) = input_receiver.recv() => Self::handle_terminal_input(&mut terminal, input).await?,
-                Ok(chars) = terminal.read() => Self::handle_terminal_output(&chars, &event_sender, pane_id).await?,
+                Some(input) = input_receiver.recv() => pending_input.extend_from_slice(&input),
+                Ok(()) = dimensions_receiver.changed() => terminal.resize(*dimensions_receiver.borrow_and_update())?,
+                output = terminal.read(&mut pending_input) => match output? {
+                    Some(chars) => Self::handle_terminal_output(&chars, event_sender, pane_id, generation).await?,
+                    None => return Ok(()),
+                },
             }
         }
     }
 
-    async fn handle_terminal_input(terminal: &mut Terminal, input: u8) -> splix_error::Result<()> {
-        terminal.write(input).await
-    }
-
     async fn handle_terminal_output(
         chars: &[char],
         event_sender: &Sender<Event>,
         pane_id: PaneId,
+        generation: u64,
     ) -> splix_error::Result<()> {
         for ch in chars.iter() {
             let update = if *ch == '\n' {
@@ -92,7 +427,9 @@ impl Pane {
             };
 
             event_sender
-                .send(Event::PaneUpdate(PaneUpdateEvent::new(pane_id, update)))
+                .send(Event::PaneUpdate(PaneUpdateEvent::new(
+                    pane_id, generation, update,
+                )))
                 .await
                 .map_err(|_| splix_error::Error::SendPaneUpdate)?;
         }
@@ -100,3 +437,65 @@ impl Pane {
         Ok(())
     }
 }
+
+impl Drop for Pane {
+    fn drop(&mut self) {
+        self.terminal_io_task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use tokio::sync::mpsc;
+
+    use splix_event::{Event, GridUpdate};
+    use splix_id::{PaneId, SessionId, WindowId};
+    use splix_terminal::{Command, TerminalConfig};
+
+    use super::Pane;
+
+    #[tokio::test]
+    async fn keeps_all_input_for_a_program_that_does_not_read_yet() {
+        const LINE_COUNT: usize = 5000;
+
+        let (event_sender, mut event_receiver) = mpsc::channel(1024);
+        let command = Command::new(
+            ["sh", "-c", "stty -echo; sleep 1; echo; wc -l"]
+                .map(String::from)
+                .to_vec(),
+        );
+        let mut pane = Pane::new(
+            PaneId::new(0, WindowId::new(0, SessionId::new(0))),
+            command,
+            UVec2::new(80, 24),
+            TerminalConfig::new(),
+            event_sender,
+        )
+        .unwrap();
+
+        for _ in 0..LINE_COUNT {
+            pane.process_input(&[b'x'; 63]);
+            pane.process_input(b"\n");
+        }
+        // End of file for `wc`.
+        pane.process_input(b"\x04");
+
+        let mut output = String::new();
+        while let Some(event) = event_receiver.recv().await {
+            match event {
+                Event::PaneUpdate(update) => match update.get_grid_update() {
+                    GridUpdate::AppendChar(ch) => output.push(*ch),
+                    GridUpdate::NewLine => output.push('\n'),
+                },
+                Event::PaneExit(_) => break,
+                _ => {}
+            }
+        }
+        assert!(
+            output
+                .lines()
+                .any(|line| line.trim() == LINE_COUNT.to_string())
+        );
+    }
+}
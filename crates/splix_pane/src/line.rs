@@ -0,0 +1,73 @@
+use crate::Cell;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Line {
+    cells: Vec<Cell>,
+    wrapped: bool,
+}
+
+impl Line {
+    pub fn new(width: u32) -> Self {
+        Self::filled(width, Cell::default())
+    }
+
+    pub fn filled(width: u32, cell: Cell) -> Self {
+        Self {
+            cells: vec![cell; width as usize],
+            wrapped: false,
+        }
+    }
+
+    pub fn get_cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Cells outside of the line are ignored.
+    pub fn set_cell(&mut self, x: u32, cell: Cell) {
+        if let Some(line_cell) = self.cells.get_mut(x as usize) {
+            *line_cell = cell;
+        }
+    }
+
+    /// Whether the text of the line continues on the next one because it didn't fit.
+    pub fn is_wrapped(&self) -> bool {
+        self.wrapped
+    }
+
+    pub fn set_wrapped(&mut self, wrapped: bool) {
+        self.wrapped = wrapped;
+    }
+
+    pub fn resize(&mut self, width: u32) {
+        self.cells.resize(width as usize, Cell::default());
+    }
+
+    /// Fills the cells from `start` up to, but not including, `end` with `cell`.
+    pub fn fill(&mut self, start: u32, end: u32, cell: Cell) {
+        let end = (end as usize).min(self.cells.len());
+        let start = (start as usize).min(end);
+        self.cells[start..end].fill(cell);
+    }
+
+    /// Shifts the cells from `x` on right by `count`, dropping those pushed past the end.
+    pub fn insert_cells(&mut self, x: u32, count: u32, cell: Cell) {
+        let width = self.cells.len();
+        let x = (x as usize).min(width);
+        let count = (count as usize).min(width - x);
+
+        self.cells.truncate(width - count);
+        self.cells
+            .splice(x..x, std::iter::repeat_n(cell, count))
+            .for_each(drop);
+    }
+
+    /// Shifts the cells after the deleted ones left by `count`, filling the end with `cell`.
+    pub fn delete_cells(&mut self, x: u32, count: u32, cell: Cell) {
+        let width = self.cells.len();
+        let x = (x as usize).min(width);
+        let count = (count as usize).min(width - x);
+
+        self.cells.drain(x..x + count);
+        self.cells.resize(width, cell);
+    }
+}
@@ -0,0 +1,249 @@
+use splix_ansi::{AnsiAction, ControlSequence};
+use splix_key::{KeyboardModes, MouseEncoding, MouseModes, MouseTracking};
+
+/// Follows the escape sequences a program writes to its terminal to know which keyboard and
+/// mouse modes it switched on.
+#[derive(Clone, Default)]
+pub struct ModeTracker {
+    keyboard_modes: KeyboardModes,
+    /// The kitty keyboard protocol flags saved by pushing new ones, restored by popping them.
+    kitty_flags_stack: Vec<u8>,
+    mouse_modes: MouseModes,
+}
+
+/// The private mode of DECCKM, switching cursor keys into application mode.
+const APPLICATION_CURSOR_MODE: u16 = 1;
+const BRACKETED_PASTE_MODE: u16 = 2004;
+
+/// The resource of xterm's `CSI > Pp ; Pv m` setting modifyOtherKeys.
+const MODIFY_OTHER_KEYS_RESOURCE: u16 = 4;
+
+/// How many flags are saved at most, dropping the oldest beyond that like kitty does.
+const MAX_KITTY_FLAGS_STACK: usize = 16;
+
+impl ModeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get_keyboard_modes(&self) -> KeyboardModes {
+        self.keyboard_modes
+    }
+
+    pub fn get_mouse_modes(&self) -> MouseModes {
+        self.mouse_modes
+    }
+
+    /// Handles setting (`CSI ? Pm h`) and resetting (`CSI ? Pm l`) private modes, pushing
+    /// (`CSI > flags u`), popping (`CSI < count u`) and setting (`CSI = flags ; mode u`) the
+    /// flags of the kitty keyboard protocol, and setting modifyOtherKeys (`CSI > 4 ; level m`).
+    pub fn update(&mut self, action: &AnsiAction) {
+        let control_sequence = match action {
+            AnsiAction::ControlSequence(control_sequence) => control_sequence,
+            // RIS resets the terminal to its initial state.
+            AnsiAction::EscapeSequence {
+                final_character: 'c',
+                ..
+            } => {
+                self.keyboard_modes = KeyboardModes::new();
+                self.kitty_flags_stack.clear();
+                self.mouse_modes = MouseModes::new();
+                return;
+            }
+            _ => return,
+        };
+
+        match (
+            control_sequence.get_private_marker(),
+            control_sequence.get_final_character(),
+        ) {
+            (Some('?'), 'h') => self.update_private_modes(control_sequence, true),
+            (Some('?'), 'l') => self.update_private_modes(control_sequence, false),
+            (Some('>'), 'u') => {
+                if self.kitty_flags_stack.len() == MAX_KITTY_FLAGS_STACK {
+                    self.kitty_flags_stack.remove(0);
+                }
+                self.kitty_flags_stack
+                    .push(self.keyboard_modes.get_kitty_flags());
+                self.keyboard_modes
+                    .set_kitty_flags(Self::get_flags(control_sequence));
+            }
+            (Some('<'), 'u') => {
+                for _ in 0..control_sequence.get_parameter(0, 1) {
+                    let kitty_flags = self.kitty_flags_stack.pop().unwrap_or(0);
+                    self.keyboard_modes.set_kitty_flags(kitty_flags);
+                }
+            }
+            (Some('='), 'u') => {
+                let flags = Self::get_flags(control_sequence);
+                let kitty_flags = self.keyboard_modes.get_kitty_flags();
+                let kitty_flags = match control_sequence.get_parameter(1, 1) {
+                    1 => flags,
+                    2 => kitty_flags | flags,
+                    3 => kitty_flags & !flags,
+                    _ => return,
+                };
+                self.keyboard_modes.set_kitty_flags(kitty_flags);
+            }
+            (Some('>'), 'm')
+                if control_sequence.get_parameters().next() == Some(MODIFY_OTHER_KEYS_RESOURCE) =>
+            {
+                let level = control_sequence.get_parameters().nth(1);
+                self.keyboard_modes
+                    .set_modify_other_keys(level.unwrap_or(0).min(u8::MAX as u16) as u8);
+            }
+            _ => {}
+        }
+    }
+
+    /// What the terminal answers to queries among the sequences `update` handles, which is the
+    /// kitty keyboard protocol flags in effect for `CSI ? u`.
+    pub fn get_reply(&self, action: &AnsiAction) -> Option<String> {
+        match action {
+            AnsiAction::ControlSequence(control_sequence)
+                if control_sequence.get_private_marker() == Some('?')
+                    && control_sequence.get_final_character() == 'u' =>
+            {
+                Some(format!("\x1b[?{}u", self.keyboard_modes.get_kitty_flags()))
+            }
+            _ => None,
+        }
+    }
+
+    fn update_private_modes(&mut self, control_sequence: &ControlSequence, enabled: bool) {
+        for mode in control_sequence.get_parameters() {
+            match mode {
+                APPLICATION_CURSOR_MODE => self.keyboard_modes.set_application_cursor(enabled),
+                BRACKETED_PASTE_MODE => self.keyboard_modes.set_bracketed_paste(enabled),
+                mode => self.update_mouse_mode(mode, enabled),
+            }
+        }
+    }
+
+    fn get_flags(control_sequence: &ControlSequence) -> u8 {
+        let flags = control_sequence.get_parameters().next();
+        flags.unwrap_or(0).min(u8::MAX as u16) as u8
+    }
+
+    /// Setting a mouse tracking or encoding mode replaces the previous one, while resetting one
+    /// only turns it off if it is the current one, as in xterm.
+    fn update_mouse_mode(&mut self, mode: u16, enabled: bool) {
+        if let Some(tracking) = MouseTracking::from_mode(mode) {
+            if enabled {
+                self.mouse_modes.set_tracking(tracking);
+            } else if self.mouse_modes.get_tracking() == tracking {
+                self.mouse_modes.set_tracking(MouseTracking::None);
+            }
+        } else if let Some(encoding) = MouseEncoding::from_mode(mode) {
+            if enabled {
+                self.mouse_modes.set_encoding(encoding);
+            } else if self.mouse_modes.get_encoding() == encoding {
+                self.mouse_modes.set_encoding(MouseEncoding::Default);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_ansi::AnsiParser;
+    use splix_key::{MouseEncoding, MouseModes, MouseTracking};
+
+    use super::ModeTracker;
+
+    fn track(output: &str) -> ModeTracker {
+        let mut parser = AnsiParser::new();
+        let mut mode_tracker = ModeTracker::new();
+        for c in output.chars() {
+            if let Some(action) = parser.advance(c) {
+                mode_tracker.update(&action);
+            }
+        }
+
+        mode_tracker
+    }
+
+    #[test]
+    fn follows_application_cursor_mode() {
+        assert!(
+            track("\x1b[?1049;1h")
+                .get_keyboard_modes()
+                .is_application_cursor()
+        );
+        assert!(
+            !track("\x1b[?1h text \x1b[?1l")
+                .get_keyboard_modes()
+                .is_application_cursor()
+        );
+        assert!(
+            !track("\x1b[?1h\x1bc")
+                .get_keyboard_modes()
+                .is_application_cursor()
+        );
+        assert!(
+            !track("\x1b[1h")
+                .get_keyboard_modes()
+                .is_application_cursor()
+        );
+    }
+
+    #[test]
+    fn follows_bracketed_paste_mode() {
+        assert!(
+            track("\x1b[?2004h")
+                .get_keyboard_modes()
+                .is_bracketed_paste()
+        );
+        assert!(
+            !track("\x1b[?2004h\x1b[?2004l")
+                .get_keyboard_modes()
+                .is_bracketed_paste()
+        );
+    }
+
+    #[test]
+    fn follows_keyboard_enhancements() {
+        let keyboard_modes = |output| track(output).get_keyboard_modes();
+        assert_eq!(keyboard_modes("\x1b[>1u").get_kitty_flags(), 1);
+        assert_eq!(keyboard_modes("\x1b[>1u\x1b[>31u").get_kitty_flags(), 9);
+        assert_eq!(
+            keyboard_modes("\x1b[>1u\x1b[>8u\x1b[<u").get_kitty_flags(),
+            1
+        );
+        assert_eq!(
+            keyboard_modes("\x1b[>1u\x1b[>8u\x1b[<5u").get_kitty_flags(),
+            0
+        );
+        assert_eq!(keyboard_modes("\x1b[=9u\x1b[=1;3u").get_kitty_flags(), 8);
+        assert_eq!(keyboard_modes("\x1b[>4;2m").get_modify_other_keys(), 2);
+        assert_eq!(
+            keyboard_modes("\x1b[>4;2m\x1b[>4m").get_modify_other_keys(),
+            0
+        );
+
+        let mode_tracker = track("\x1b[>1u");
+        let mut parser = AnsiParser::new();
+        let query = "\x1b[?u".chars().find_map(|c| parser.advance(c)).unwrap();
+        assert_eq!(mode_tracker.get_reply(&query).as_deref(), Some("\x1b[?1u"));
+    }
+
+    #[test]
+    fn follows_mouse_modes() {
+        let mouse_modes = track("\x1b[?1002;1006h").get_mouse_modes();
+        assert_eq!(mouse_modes.get_tracking(), MouseTracking::ButtonEvent);
+        assert_eq!(mouse_modes.get_encoding(), MouseEncoding::Sgr);
+
+        let mouse_modes = track("\x1b[?1000h\x1b[?1003h\x1b[?1000l\x1b[?1015h").get_mouse_modes();
+        assert_eq!(mouse_modes.get_tracking(), MouseTracking::AnyEvent);
+        assert_eq!(mouse_modes.get_encoding(), MouseEncoding::Urxvt);
+
+        assert_eq!(
+            track("\x1b[?9h\x1b[?9l").get_mouse_modes(),
+            MouseModes::new()
+        );
+        assert_eq!(
+            track("\x1b[?1003;1005h\x1bc").get_mouse_modes(),
+            MouseModes::new()
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use crate::Color;
+
+/// How the text of a cell is rendered, as set with SGR sequences.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    bold: bool,
+    dim: bool,
+    italic: bool,
+    underline: bool,
+    blink: bool,
+    inverse: bool,
+    hidden: bool,
+    strikethrough: bool,
+    foreground: Color,
+    background: Color,
+}
+
+const EXTENDED_COLOR_INDEXED: u16 = 5;
+const EXTENDED_COLOR_RGB: u16 = 2;
+
+impl Style {
+    pub fn get_foreground(&self) -> Color {
+        self.foreground
+    }
+
+    pub fn get_background(&self) -> Color {
+        self.background
+    }
+
+    /// The style of cells erased while this one is in effect, which keep only its background.
+    pub fn get_erased(&self) -> Self {
+        Self {
+            background: self.background,
+            ..Self::default()
+        }
+    }
+
+    /// Applies the parameters of an SGR sequence, each followed by its subparameters, where no
+    /// parameters reset the style.
+    ///
+    /// Subparameters are only understood for underline styles, all of which underline, and for
+    /// extended colors. Other parameters apply as if they had none.
+    pub fn apply_sgr(&mut self, parameters: &[Vec<u16>]) {
+        if parameters.is_empty() {
+            *self = Self::default();
+            return;
+        }
+
+        let mut parameters = parameters.iter();
+        while let Some(parameter) = parameters.next() {
+            let (parameter, subparameters) = parameter.split_first().unwrap_or((&0, &[]));
+            match *parameter {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                3 => self.italic = true,
+                4 => self.underline = subparameters.first() != Some(&0),
+                21 => self.underline = true,
+                5 | 6 => self.blink = true,
+                7 => self.inverse = true,
+                8 => self.hidden = true,
+                9 => self.strikethrough = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                23 => self.italic = false,
+                24 => self.underline = false,
+                25 => self.blink = false,
+                27 => self.inverse = false,
+                28 => self.hidden = false,
+                29 => self.strikethrough = false,
+                30..=37 => self.foreground = Color::Indexed((parameter - 30) as u8),
+                38 => self.foreground = Self::parse_extended_color(subparameters, &mut parameters),
+                39 => self.foreground = Color::Default,
+                40..=47 => self.background = Color::Indexed((parameter - 40) as u8),
+                48 => self.background = Self::parse_extended_color(subparameters, &mut parameters),
+                49 => self.background = Color::Default,
+                90..=97 => self.foreground = Color::Indexed((parameter - 90 + 8) as u8),
+                100..=107 => self.background = Color::Indexed((parameter - 100 + 8) as u8),
+                _ => {}
+            }
+        }
+    }
+
+    /// The SGR sequence switching to this style from any other.
+    pub fn to_sgr(&self) -> String {
+        let mut parameters = vec![String::from("0")];
+        let attributes = [
+            (self.bold, "1"),
+            (self.dim, "2"),
+            (self.italic, "3"),
+            (self.underline, "4"),
+            (self.blink, "5"),
+            (self.inverse, "7"),
+            (self.hidden, "8"),
+            (self.strikethrough, "9"),
+        ];
+        parameters.extend(
+            attributes
+                .iter()
+                .filter(|(enabled, _)| *enabled)
+                .map(|(_, parameter)| parameter.to_string()),
+        );
+        parameters.extend(Self::color_to_sgr(self.foreground, 30, 90, 38));
+        parameters.extend(Self::color_to_sgr(self.background, 40, 100, 48));
+
+        format!("\x1b[{}m", parameters.join(";"))
+    }
+
+    /// Parses the subparameters of `38 : 5 : index` or `38 : 2 : colorspace : r : g : b`, where
+    /// the colorspace is commonly left out, or the following parameters of `38 ; 5 ; index` or
+    /// `38 ; 2 ; r ; g ; b` when there are no subparameters.
+    fn parse_extended_color<'a>(
+        subparameters: &[u16],
+        parameters: &mut impl Iterator<Item = &'a Vec<u16>>,
+    ) -> Color {
+        let component = |value: u16| value.min(u8::MAX as u16) as u8;
+
+        if subparameters.is_empty() {
+            let mut next = || {
+                parameters
+                    .next()
+                    .and_then(|parameter| parameter.first().copied())
+                    .map_or(0, component)
+            };
+
+            return match next() as u16 {
+                EXTENDED_COLOR_INDEXED => Color::Indexed(next()),
+                EXTENDED_COLOR_RGB => Color::Rgb(next(), next(), next()),
+                _ => Color::Default,
+            };
+        }
+
+        match subparameters {
+            [EXTENDED_COLOR_INDEXED, index, ..] => Color::Indexed(component(*index)),
+            [EXTENDED_COLOR_RGB, _, red, green, blue, ..]
+            | [EXTENDED_COLOR_RGB, red, green, blue] => {
+                Color::Rgb(component(*red), component(*green), component(*blue))
+            }
+            _ => Color::Default,
+        }
+    }
+
+    fn color_to_sgr(color: Color, base: u8, bright_base: u8, extended: u8) -> Option<String> {
+        match color {
+            Color::Default => None,
+            Color::Indexed(index @ 0..=7) => Some((base + index).to_string()),
+            Color::Indexed(index @ 8..=15) => Some((bright_base + index - 8).to_string()),
+            Color::Indexed(index) => Some(format!("{extended};5;{index}")),
+            Color::Rgb(red, green, blue) => Some(format!("{extended};2;{red};{green};{blue}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Color, Style};
+
+    /// Parameters without subparameters.
+    fn sgr(parameters: &[u16]) -> Vec<Vec<u16>> {
+        parameters
+            .iter()
+            .map(|parameter| vec![*parameter])
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_through_sgr() {
+        let mut style = Style::default();
+        style.apply_sgr(&sgr(&[1, 38, 2, 10, 20, 30, 44, 92]));
+
+        assert_eq!(style.get_foreground(), Color::Indexed(10));
+        assert_eq!(style.get_background(), Color::Indexed(4));
+        assert_eq!(style.to_sgr(), "\x1b[0;1;92;44m");
+
+        style.apply_sgr(&sgr(&[22, 48, 5, 200]));
+        assert_eq!(style.to_sgr(), "\x1b[0;92;48;5;200m");
+
+        style.apply_sgr(&[]);
+        assert_eq!(style, Style::default());
+    }
+
+    #[test]
+    fn applies_subparameters() {
+        let mut style = Style::default();
+        style.apply_sgr(&[vec![4, 3], vec![38, 2, 0, 10, 20, 30], vec![48, 2, 1, 2, 3]]);
+        assert_eq!(style.get_foreground(), Color::Rgb(10, 20, 30));
+        assert_eq!(style.get_background(), Color::Rgb(1, 2, 3));
+        assert_eq!(style.to_sgr(), "\x1b[0;4;38;2;10;20;30;48;2;1;2;3m");
+
+        // Unknown subparameters are ignored rather than taken for parameters.
+        style.apply_sgr(&[vec![4, 0], vec![1, 38, 5]]);
+        assert_eq!(style.to_sgr(), "\x1b[0;1;38;2;10;20;30;48;2;1;2;3m");
+    }
+}
@@ -0,0 +1,169 @@
+use glam::UVec2;
+
+use crate::{Message, payload_reader::PayloadReader, payload_writer::PayloadWriter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientMessage {
+    /// The first message on every connection, announcing the client's protocol version.
+    Hello {
+        version: u32,
+    },
+    Resize(UVec2),
+    /// The bytes of whole keys as typed, rather than decoded keys, so that the protocol doesn't
+    /// depend on how keys are represented.
+    Input(Vec<u8>),
+    /// Attaches to the session matching the target, or to the most recently used session.
+    ///
+    /// Read-only clients can only detach, everything else they type is dropped. Control clients
+    /// get text notifications instead of frames and send commands as lines.
+    Attach {
+        target: Option<String>,
+        read_only: bool,
+        control: bool,
+    },
+    /// Runs a command, given as its arguments, without attaching.
+    Command(Vec<String>),
+    /// A line typed into a control client, which is a command or empty to detach.
+    ControlCommand(String),
+}
+
+const HELLO_TAG: u8 = 0;
+const RESIZE_TAG: u8 = 1;
+const INPUT_TAG: u8 = 2;
+const ATTACH_TAG: u8 = 3;
+const COMMAND_TAG: u8 = 4;
+const CONTROL_COMMAND_TAG: u8 = 5;
+
+impl Message for ClientMessage {
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = PayloadWriter::new();
+
+        match self {
+            Self::Hello { version } => {
+                writer.write_u8(HELLO_TAG);
+                writer.write_u32(*version);
+            }
+            Self::Resize(dimensions) => {
+                writer.write_u8(RESIZE_TAG);
+                writer.write_u32(dimensions.x);
+                writer.write_u32(dimensions.y);
+            }
+            Self::Input(input) => {
+                writer.write_u8(INPUT_TAG);
+                writer.write_bytes(input);
+            }
+            Self::Attach {
+                target,
+                read_only,
+                control,
+            } => {
+                writer.write_u8(ATTACH_TAG);
+                writer.write_optional_string(target.as_deref());
+                writer.write_bool(*read_only);
+                writer.write_bool(*control);
+            }
+            Self::Command(arguments) => {
+                writer.write_u8(COMMAND_TAG);
+                writer.write_u32(arguments.len() as u32);
+                for argument in arguments.iter() {
+                    writer.write_string(argument);
+                }
+            }
+            Self::ControlCommand(line) => {
+                writer.write_u8(CONTROL_COMMAND_TAG);
+                writer.write_string(line);
+            }
+        }
+
+        writer.into_payload()
+    }
+
+    fn decode(payload: &[u8]) -> splix_error::Result<Self> {
+        let mut reader = PayloadReader::new(payload);
+
+        let message = match reader.read_u8()? {
+            HELLO_TAG => Self::Hello {
+                version: reader.read_u32()?,
+            },
+            RESIZE_TAG => Self::Resize(UVec2::new(reader.read_u32()?, reader.read_u32()?)),
+            INPUT_TAG => Self::Input(reader.read_bytes()?.to_vec()),
+            ATTACH_TAG => Self::Attach {
+                target: reader.read_optional_string()?,
+                read_only: reader.read_bool()?,
+                control: reader.read_bool()?,
+            },
+            COMMAND_TAG => {
+                let count = reader.read_u32()?;
+                Self::Command(
+                    (0..count)
+                        .map(|_| reader.read_string())
+                        .collect::<splix_error::Result<_>>()?,
+                )
+            }
+            CONTROL_COMMAND_TAG => Self::ControlCommand(reader.read_string()?),
+            _ => return Err(splix_error::Error::DecodeMessage),
+        };
+
+        reader.finish()?;
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+
+    use super::ClientMessage;
+    use crate::{Message, PROTOCOL_VERSION};
+
+    #[test]
+    fn round_trips() {
+        let messages = [
+            ClientMessage::Hello {
+                version: PROTOCOL_VERSION,
+            },
+            ClientMessage::Resize(UVec2::new(80, 24)),
+            ClientMessage::Input(b"ls\r".to_vec()),
+            ClientMessage::Attach {
+                target: None,
+                read_only: false,
+                control: false,
+            },
+            ClientMessage::Attach {
+                target: Some(String::new()),
+                read_only: true,
+                control: false,
+            },
+            ClientMessage::Attach {
+                target: Some(String::from("$1")),
+                read_only: false,
+                control: true,
+            },
+            ClientMessage::Command(vec![String::from("new-session"), String::from("-d")]),
+            ClientMessage::ControlCommand(String::from("list-windows -F '#{window_id}'")),
+        ];
+
+        for message in messages {
+            assert_eq!(ClientMessage::decode(&message.encode()).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn hello_encoding_is_stable() {
+        assert_eq!(
+            ClientMessage::Hello { version: 7 }.encode(),
+            [0, 0, 0, 0, 7]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_payloads() {
+        let payload = ClientMessage::Resize(UVec2::new(80, 24)).encode();
+
+        assert!(ClientMessage::decode(&[]).is_err());
+        assert!(ClientMessage::decode(&[0xFF]).is_err());
+        assert!(ClientMessage::decode(&payload[..payload.len() - 1]).is_err());
+        assert!(ClientMessage::decode(&[payload.as_slice(), &[0]].concat()).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+mod client_message;
+mod message;
+mod message_reader;
+mod message_writer;
+mod payload_reader;
+mod payload_writer;
+mod protocol_version;
+mod server_message;
+
+pub use client_message::ClientMessage;
+pub use message::Message;
+pub use message_reader::MessageReader;
+pub use message_writer::MessageWriter;
+pub use protocol_version::{PROTOCOL_VERSION, check_protocol_version};
+pub use server_message::ServerMessage;
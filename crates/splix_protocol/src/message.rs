@@ -0,0 +1,5 @@
+pub trait Message: Sized {
+    fn encode(&self) -> Vec<u8>;
+
+    fn decode(payload: &[u8]) -> splix_error::Result<Self>;
+}
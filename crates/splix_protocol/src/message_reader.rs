@@ -0,0 +1,40 @@
+use std::io;
+
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::Message;
+
+/// Reads length-prefixed messages off a stream.
+pub struct MessageReader<R> {
+    reader: R,
+}
+
+/// Guards against allocating absurd amounts of memory for a corrupted length prefix.
+const MAXIMUM_MESSAGE_LENGTH: usize = 64 * 1024 * 1024;
+
+impl<R: AsyncRead + Unpin> MessageReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader }
+    }
+
+    /// Returns `None` once the other side closed the connection.
+    pub async fn read<M: Message>(&mut self) -> splix_error::Result<Option<M>> {
+        let length = match self.reader.read_u32().await {
+            Ok(length) => length as usize,
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(splix_error::Error::ReadMessage(e)),
+        };
+
+        if length > MAXIMUM_MESSAGE_LENGTH {
+            return Err(splix_error::Error::DecodeMessage);
+        }
+
+        let mut payload = vec![0; length];
+        self.reader
+            .read_exact(&mut payload)
+            .await
+            .map_err(splix_error::Error::ReadMessage)?;
+
+        M::decode(&payload).map(Some)
+    }
+}
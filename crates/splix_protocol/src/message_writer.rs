@@ -0,0 +1,31 @@
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::Message;
+
+/// Writes length-prefixed messages to a stream.
+pub struct MessageWriter<W> {
+    writer: W,
+}
+
+impl<W: AsyncWrite + Unpin> MessageWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub async fn write<M: Message>(&mut self, message: &M) -> splix_error::Result<()> {
+        let payload = message.encode();
+
+        let mut buffer = Vec::with_capacity(4 + payload.len());
+        buffer.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+        buffer.extend_from_slice(&payload);
+
+        self.writer
+            .write_all(&buffer)
+            .await
+            .map_err(splix_error::Error::WriteMessage)?;
+        self.writer
+            .flush()
+            .await
+            .map_err(splix_error::Error::WriteMessage)
+    }
+}
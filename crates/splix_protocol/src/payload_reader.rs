@@ -0,0 +1,68 @@
+use byteorder::{BigEndian, ReadBytesExt};
+
+/// Reads big-endian fields out of a message payload.
+pub struct PayloadReader<'a> {
+    payload: &'a [u8],
+}
+
+impl<'a> PayloadReader<'a> {
+    pub fn new(payload: &'a [u8]) -> Self {
+        Self { payload }
+    }
+
+    pub fn read_u8(&mut self) -> splix_error::Result<u8> {
+        self.payload
+            .read_u8()
+            .map_err(|_| splix_error::Error::DecodeMessage)
+    }
+
+    pub fn read_u32(&mut self) -> splix_error::Result<u32> {
+        self.payload
+            .read_u32::<BigEndian>()
+            .map_err(|_| splix_error::Error::DecodeMessage)
+    }
+
+    pub fn read_bool(&mut self) -> splix_error::Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(splix_error::Error::DecodeMessage),
+        }
+    }
+
+    /// Reads a length-prefixed byte string.
+    pub fn read_bytes(&mut self) -> splix_error::Result<&'a [u8]> {
+        let length = self.read_u32()? as usize;
+        if self.payload.len() < length {
+            return Err(splix_error::Error::DecodeMessage);
+        }
+
+        let (bytes, rest) = self.payload.split_at(length);
+        self.payload = rest;
+
+        Ok(bytes)
+    }
+
+    /// Reads a length-prefixed UTF-8 string.
+    pub fn read_string(&mut self) -> splix_error::Result<String> {
+        String::from_utf8(self.read_bytes()?.to_vec())
+            .map_err(|_| splix_error::Error::DecodeMessage)
+    }
+
+    pub fn read_optional_string(&mut self) -> splix_error::Result<Option<String>> {
+        if self.read_bool()? {
+            self.read_string().map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Fails if anything was left unread, which means the payload doesn't match the message.
+    pub fn finish(self) -> splix_error::Result<()> {
+        if self.payload.is_empty() {
+            Ok(())
+        } else {
+            Err(splix_error::Error::DecodeMessage)
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use byteorder::{BigEndian, WriteBytesExt};
+
+/// Writes big-endian fields into a message payload, in the format `PayloadReader` reads.
+#[derive(Default)]
+pub struct PayloadWriter {
+    payload: Vec<u8>,
+}
+
+impl PayloadWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        // Writing into a `Vec` can't fail.
+        self.payload.write_u8(value).unwrap();
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.payload.write_u32::<BigEndian>(value).unwrap();
+    }
+
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(value as u8);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_u32(bytes.len() as u32);
+        self.payload.extend_from_slice(bytes);
+    }
+
+    pub fn write_string(&mut self, string: &str) {
+        self.write_bytes(string.as_bytes());
+    }
+
+    pub fn write_optional_string(&mut self, string: Option<&str>) {
+        self.write_bool(string.is_some());
+        if let Some(string) = string {
+            self.write_string(string);
+        }
+    }
+
+    pub fn into_payload(self) -> Vec<u8> {
+        self.payload
+    }
+}
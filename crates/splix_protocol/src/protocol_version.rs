@@ -0,0 +1,38 @@
+/// Bumped whenever the encoding of any message changes.
+///
+/// The hello messages carrying it must be encoded the same way in every version, so that
+/// mismatched peers can still tell each other apart.
+pub const PROTOCOL_VERSION: u32 = 5;
+
+/// Peers only talk to each other when they speak exactly the same version.
+pub fn check_protocol_version(version: u32) -> splix_error::Result<()> {
+    if version == PROTOCOL_VERSION {
+        Ok(())
+    } else {
+        Err(splix_error::Error::ProtocolVersionMismatch {
+            expected: PROTOCOL_VERSION,
+            found: version,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PROTOCOL_VERSION, check_protocol_version};
+
+    #[test]
+    fn accepts_same_version() {
+        assert!(check_protocol_version(PROTOCOL_VERSION).is_ok());
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let e = check_protocol_version(PROTOCOL_VERSION + 1).unwrap_err();
+
+        assert!(matches!(
+            e,
+            splix_error::Error::ProtocolVersionMismatch { expected, found }
+                if expected == PROTOCOL_VERSION && found == PROTOCOL_VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,200 @@
+use glam::UVec2;
+use splix_frame::{Frame, FrameUpdate};
+use splix_key::MouseTracking;
+
+use crate::{Message, payload_reader::PayloadReader, payload_writer::PayloadWriter};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerMessage {
+    /// Answers the client's hello once both speak the same protocol version.
+    Hello {
+        version: u32,
+    },
+    /// The whole screen, sent when the client doesn't have a frame of the same dimensions yet.
+    Frame(Frame),
+    /// The lines that changed since the previous frame.
+    FrameUpdate(FrameUpdate),
+    /// What the client's terminal should be titled.
+    SetTitle(String),
+    /// Which mouse events the client's terminal should report.
+    SetMouseTracking(MouseTracking),
+    /// Lines for a control client to print, like command replies and notifications.
+    ControlOutput(String),
+    /// The output of a command on success, or the reason it failed.
+    CommandResponse(Result<String, String>),
+    /// The server is shutting down.
+    Exit,
+    /// The client was detached from its session.
+    Detach,
+    Error(String),
+}
+
+const HELLO_TAG: u8 = 0;
+const FRAME_TAG: u8 = 1;
+const FRAME_UPDATE_TAG: u8 = 2;
+const COMMAND_RESPONSE_TAG: u8 = 3;
+const EXIT_TAG: u8 = 4;
+const DETACH_TAG: u8 = 5;
+const ERROR_TAG: u8 = 6;
+const SET_TITLE_TAG: u8 = 7;
+const CONTROL_OUTPUT_TAG: u8 = 8;
+const SET_MOUSE_TRACKING_TAG: u8 = 9;
+
+impl Message for ServerMessage {
+    fn encode(&self) -> Vec<u8> {
+        let mut writer = PayloadWriter::new();
+
+        match self {
+            Self::Hello { version } => {
+                writer.write_u8(HELLO_TAG);
+                writer.write_u32(*version);
+            }
+            Self::Frame(frame) => {
+                writer.write_u8(FRAME_TAG);
+                writer.write_u32(frame.get_dimensions().x);
+                writer.write_u32(frame.get_dimensions().y);
+                writer.write_string(&frame.get_cells().iter().collect::<String>());
+            }
+            Self::FrameUpdate(update) => {
+                writer.write_u8(FRAME_UPDATE_TAG);
+                writer.write_u32(update.get_lines().len() as u32);
+                for (y, line) in update.get_lines().iter() {
+                    writer.write_u32(*y);
+                    writer.write_string(&line.iter().collect::<String>());
+                }
+            }
+            Self::SetTitle(title) => {
+                writer.write_u8(SET_TITLE_TAG);
+                writer.write_string(title);
+            }
+            Self::SetMouseTracking(tracking) => {
+                writer.write_u8(SET_MOUSE_TRACKING_TAG);
+                writer.write_u32(tracking.get_mode().map_or(0, u32::from));
+            }
+            Self::ControlOutput(output) => {
+                writer.write_u8(CONTROL_OUTPUT_TAG);
+                writer.write_string(output);
+            }
+            Self::CommandResponse(response) => {
+                writer.write_u8(COMMAND_RESPONSE_TAG);
+                writer.write_bool(response.is_ok());
+                match response {
+                    Ok(output) | Err(output) => writer.write_string(output),
+                }
+            }
+            Self::Exit => writer.write_u8(EXIT_TAG),
+            Self::Detach => writer.write_u8(DETACH_TAG),
+            Self::Error(message) => {
+                writer.write_u8(ERROR_TAG);
+                writer.write_string(message);
+            }
+        }
+
+        writer.into_payload()
+    }
+
+    fn decode(payload: &[u8]) -> splix_error::Result<Self> {
+        let mut reader = PayloadReader::new(payload);
+
+        let message = match reader.read_u8()? {
+            HELLO_TAG => Self::Hello {
+                version: reader.read_u32()?,
+            },
+            FRAME_TAG => {
+                let dimensions = UVec2::new(reader.read_u32()?, reader.read_u32()?);
+                let cells = reader.read_string()?.chars().collect();
+
+                Frame::from_cells(dimensions, cells)
+                    .map(Self::Frame)
+                    .ok_or(splix_error::Error::DecodeMessage)?
+            }
+            FRAME_UPDATE_TAG => {
+                let count = reader.read_u32()?;
+                let mut update = FrameUpdate::new();
+                for _ in 0..count {
+                    let y = reader.read_u32()?;
+                    update = update.with_line(y, reader.read_string()?.chars().collect());
+                }
+
+                Self::FrameUpdate(update)
+            }
+            SET_TITLE_TAG => Self::SetTitle(reader.read_string()?),
+            SET_MOUSE_TRACKING_TAG => match reader.read_u32()? {
+                0 => Self::SetMouseTracking(MouseTracking::None),
+                mode => u16::try_from(mode)
+                    .ok()
+                    .and_then(MouseTracking::from_mode)
+                    .map(Self::SetMouseTracking)
+                    .ok_or(splix_error::Error::DecodeMessage)?,
+            },
+            CONTROL_OUTPUT_TAG => Self::ControlOutput(reader.read_string()?),
+            COMMAND_RESPONSE_TAG => {
+                let success = reader.read_bool()?;
+                let output = reader.read_string()?;
+
+                Self::CommandResponse(if success { Ok(output) } else { Err(output) })
+            }
+            EXIT_TAG => Self::Exit,
+            DETACH_TAG => Self::Detach,
+            ERROR_TAG => Self::Error(reader.read_string()?),
+            _ => return Err(splix_error::Error::DecodeMessage),
+        };
+
+        reader.finish()?;
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::UVec2;
+    use splix_frame::{Frame, FrameUpdate};
+    use splix_key::MouseTracking;
+
+    use super::ServerMessage;
+    use crate::{Message, PROTOCOL_VERSION};
+
+    #[test]
+    fn round_trips() {
+        let mut frame = Frame::new(UVec2::new(2, 2));
+        frame.set_cell(UVec2::new(1, 0), 'é');
+
+        let messages = [
+            ServerMessage::Hello {
+                version: PROTOCOL_VERSION,
+            },
+            ServerMessage::Frame(frame),
+            ServerMessage::FrameUpdate(FrameUpdate::new().with_line(1, vec!['a', ' '])),
+            ServerMessage::SetTitle(String::from("work:0:vim")),
+            ServerMessage::SetMouseTracking(MouseTracking::None),
+            ServerMessage::SetMouseTracking(MouseTracking::AnyEvent),
+            ServerMessage::ControlOutput(String::from("%window-add @3\n")),
+            ServerMessage::CommandResponse(Ok(String::from("$0: 1 windows\n"))),
+            ServerMessage::CommandResponse(Err(String::from("can't find session $7"))),
+            ServerMessage::Exit,
+            ServerMessage::Detach,
+            ServerMessage::Error(String::from("protocol version mismatch")),
+        ];
+
+        for message in messages {
+            assert_eq!(ServerMessage::decode(&message.encode()).unwrap(), message);
+        }
+    }
+
+    #[test]
+    fn hello_encoding_is_stable() {
+        assert_eq!(
+            ServerMessage::Hello { version: 7 }.encode(),
+            [0, 0, 0, 0, 7]
+        );
+    }
+
+    #[test]
+    fn rejects_frame_not_matching_its_dimensions() {
+        let mut payload = ServerMessage::Frame(Frame::new(UVec2::new(2, 2))).encode();
+        payload[4] = 3;
+
+        assert!(ServerMessage::decode(&payload).is_err());
+    }
+}
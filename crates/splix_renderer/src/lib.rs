@@ -1,8 +1,7 @@
 use std::io::{self, Stdout, Write};
 
 use glam::UVec2;
-use splix_pane::Pane;
-use splix_window::Window;
+use splix_frame::Frame;
 
 pub struct Renderer {
     screen_dimensions: UVec2,
@@ -19,6 +18,15 @@ impl Renderer {
         }
     }
 
+    pub fn get_screen_dimensions(&self) -> UVec2 {
+        self.screen_dimensions
+    }
+
+    pub fn resize(&mut self, screen_dimensions: UVec2) {
+        self.screen_dimensions = screen_dimensions;
+        self.render_buffer = vec![' '; (screen_dimensions.y * screen_dimensions.x) as usize];
+    }
+
     pub fn begin_frame(&mut self) {
         self.reset_cursor();
 
@@ -31,8 +39,25 @@ impl Renderer {
         self.stdout.flush().unwrap();
     }
 
-    pub fn draw_window(&mut self, window: &Window) {
-        self.draw_pane(window.get_pane(0));
+    /// Titles the terminal window or tab the client runs in. Control characters are dropped so
+    /// they can't end the title early.
+    pub fn set_title(&mut self, title: &str) {
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        write!(self.stdout, "\x1B]2;{title}\x07").unwrap();
+        self.stdout.flush().unwrap();
+    }
+
+    /// Frames composed for different dimensions are clipped to the screen.
+    pub fn draw_frame(&mut self, frame: &Frame) {
+        let dimensions = frame.get_dimensions().min(self.screen_dimensions);
+
+        for y in 0..dimensions.y {
+            let line = frame.get_line(y);
+            for x in 0..dimensions.x {
+                let index = self.render_buffer_index_from_position(UVec2::new(x, y));
+                self.render_buffer[index] = line[x as usize];
+            }
+        }
     }
 
     fn render_buffer_index_from_position(&self, position: UVec2) -> usize {
@@ -57,11 +82,11 @@ impl Renderer {
             write!(
                 self.stdout,
                 "{}",
-                (&self.render_buffer[self.render_buffer_index_from_position(UVec2::new(0, y))
+                self.render_buffer[self.render_buffer_index_from_position(UVec2::new(0, y))
                     ..self.render_buffer_index_from_position(UVec2::new(
                         self.screen_dimensions.x,
                         y
-                    ))])
+                    ))]
                     .iter()
                     .collect::<String>()
             )
@@ -74,21 +99,4 @@ impl Renderer {
     fn move_cursor_to_next_line(&mut self) {
         self.stdout.write_all(b"\x1B[1E").unwrap();
     }
-
-    fn draw_pane(&mut self, pane: &Pane) {
-        for (y, line) in pane.get_grid().get_data().iter().enumerate() {
-            if (y as u32) >= self.screen_dimensions.y {
-                break;
-            }
-
-            for (x, character) in line.iter().enumerate() {
-                if (x as u32) >= self.screen_dimensions.x {
-                    break;
-                }
-
-                let index = self.render_buffer_index_from_position(UVec2::new(x as u32, y as u32));
-                self.render_buffer[index] = *character;
-            }
-        }
-    }
 }
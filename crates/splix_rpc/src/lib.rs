@@ -0,0 +1,13 @@
+mod rpc_error;
+mod rpc_notification;
+mod rpc_request;
+mod rpc_response;
+
+pub use rpc_error::RpcError;
+pub use rpc_notification::RpcNotification;
+pub use rpc_request::RpcRequest;
+pub use rpc_response::RpcResponse;
+pub use serde_json::{Value, json};
+
+/// The version of JSON-RPC spoken on the JSON socket, one message per line.
+pub const JSON_RPC_VERSION: &str = "2.0";
@@ -0,0 +1,34 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::JSON_RPC_VERSION;
+
+/// A message the server sends without being asked, like the events of a subscription.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RpcNotification {
+    jsonrpc: &'static str,
+    method: String,
+    params: Value,
+}
+
+impl RpcNotification {
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: JSON_RPC_VERSION,
+            method: method.into(),
+            params,
+        }
+    }
+
+    pub fn get_method(&self) -> &str {
+        &self.method
+    }
+
+    pub fn get_params(&self) -> &Value {
+        &self.params
+    }
+
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).expect("notifications always serialize") + "\n"
+    }
+}
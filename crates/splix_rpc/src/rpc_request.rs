@@ -0,0 +1,124 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::{JSON_RPC_VERSION, RpcError};
+
+/// A call of a method, answered by a response with the same ID.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct RpcRequest {
+    jsonrpc: String,
+    /// JSON-RPC allows numbers and strings, which are echoed back as they are.
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+impl RpcRequest {
+    pub fn get_id(&self) -> &Value {
+        &self.id
+    }
+
+    pub fn get_method(&self) -> &str {
+        &self.method
+    }
+
+    /// `Null` when the request has no parameters.
+    pub fn get_params(&self) -> &Value {
+        &self.params
+    }
+
+    /// Reads the parameters into what the method expects, which is its default when there are
+    /// none.
+    pub fn parse_params<T: DeserializeOwned + Default>(&self) -> Result<T, RpcError> {
+        if self.params.is_null() {
+            return Ok(T::default());
+        }
+
+        T::deserialize(&self.params).map_err(|e| RpcError::invalid_params(e.to_string()))
+    }
+}
+
+/// Lines that aren't JSON fail with a parse error, and JSON that isn't a request with an
+/// invalid request error.
+impl FromStr for RpcRequest {
+    type Err = RpcError;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let value: Value =
+            serde_json::from_str(line).map_err(|e| RpcError::parse_error(e.to_string()))?;
+        let request: Self =
+            serde_json::from_value(value).map_err(|e| RpcError::invalid_request(e.to_string()))?;
+
+        if request.jsonrpc != JSON_RPC_VERSION {
+            return Err(RpcError::invalid_request(format!(
+                "unsupported JSON-RPC version: {}",
+                request.jsonrpc
+            )));
+        }
+
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::RpcRequest;
+    use crate::RpcError;
+
+    #[test]
+    fn parses_requests() {
+        let request: RpcRequest =
+            r#"{"jsonrpc":"2.0","id":7,"method":"list_panes"}"#.parse().unwrap();
+        assert_eq!(request.get_id(), &json!(7));
+        assert_eq!(request.get_method(), "list_panes");
+        assert!(request.get_params().is_null());
+    }
+
+    #[test]
+    fn parses_params() {
+        #[derive(Debug, Default, PartialEq, serde::Deserialize)]
+        #[serde(default, deny_unknown_fields)]
+        struct Params {
+            target: Option<String>,
+        }
+
+        let request: RpcRequest = r#"{"jsonrpc":"2.0","id":1,"method":"x"}"#.parse().unwrap();
+        assert_eq!(request.parse_params::<Params>().unwrap(), Params::default());
+
+        let request: RpcRequest =
+            r#"{"jsonrpc":"2.0","id":1,"method":"x","params":{"target":"%1"}}"#
+                .parse()
+                .unwrap();
+        assert_eq!(
+            request.parse_params::<Params>().unwrap().target.as_deref(),
+            Some("%1")
+        );
+
+        let request: RpcRequest =
+            r#"{"jsonrpc":"2.0","id":1,"method":"x","params":{"tagret":"%1"}}"#
+                .parse()
+                .unwrap();
+        assert_eq!(
+            request.parse_params::<Params>().unwrap_err().get_code(),
+            RpcError::INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let error = "{".parse::<RpcRequest>().unwrap_err();
+        assert_eq!(error.get_code(), RpcError::PARSE_ERROR);
+
+        let error = r#"{"jsonrpc":"2.0","id":1}"#.parse::<RpcRequest>().unwrap_err();
+        assert_eq!(error.get_code(), RpcError::INVALID_REQUEST);
+
+        let error = r#"{"jsonrpc":"1.0","id":1,"method":"x"}"#.parse::<RpcRequest>().unwrap_err();
+        assert_eq!(error.get_code(), RpcError::INVALID_REQUEST);
+    }
+}
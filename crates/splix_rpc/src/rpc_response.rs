@@ -0,0 +1,58 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{JSON_RPC_VERSION, RpcError};
+
+/// The answer to a request, carrying either its result or why it failed.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    /// Requests that couldn't be parsed far enough to know their ID are answered with a `Null`
+    /// one.
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            jsonrpc: JSON_RPC_VERSION,
+            id,
+            result,
+            error,
+        }
+    }
+
+    pub fn to_line(&self) -> String {
+        serde_json::to_string(self).expect("responses always serialize") + "\n"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::RpcResponse;
+    use crate::RpcError;
+
+    #[test]
+    fn serializes_results_and_errors() {
+        assert_eq!(
+            RpcResponse::new(json!(1), Ok(json!({"pane": "%3"}))).to_line(),
+            "{\"jsonrpc\":\"2.0\",\"id\":1,\"result\":{\"pane\":\"%3\"}}\n"
+        );
+        assert_eq!(
+            RpcResponse::new(json!("a"), Err(RpcError::method_not_found("frobnicate"))).to_line(),
+            "{\"jsonrpc\":\"2.0\",\"id\":\"a\",\"error\":{\"code\":-32601,\
+             \"message\":\"unknown method: frobnicate\"}}\n"
+        );
+    }
+}
@@ -1,66 +1,287 @@
+use std::path::PathBuf;
+
+use glam::UVec2;
 use tokio::sync::mpsc::Sender;
 
 use splix_event::{Event, GridUpdate};
-use splix_id::{PaneId, SessionId, WindowId};
+use splix_id::{ClientId, IdAllocator, PaneId, SessionId, WindowId};
+use splix_options::Options;
+use splix_terminal::{Command, TerminalConfig};
 use splix_window::Window;
 
 pub struct Session {
     id: SessionId,
+    name: String,
+    dimensions: UVec2,
+    terminal_config: TerminalConfig,
     event_sender: Sender<Event>,
     windows: Vec<Window>,
-    next_window_id: usize,
+    active_window: WindowId,
+    last_window: Option<WindowId>,
+    id_allocator: IdAllocator,
+    attached_clients: Vec<ClientId>,
+    options: Options,
 }
 
 impl Session {
-    pub fn new(id: SessionId, event_sender: Sender<Event>) -> splix_error::Result<Self> {
-        let mut session = Self {
+    pub fn new(
+        id: SessionId,
+        name: String,
+        command: Command,
+        dimensions: UVec2,
+        terminal_config: TerminalConfig,
+        event_sender: Sender<Event>,
+        id_allocator: IdAllocator,
+    ) -> splix_error::Result<Self> {
+        let window_id = id_allocator.allocate_window_id(id);
+        let window = Window::new(
+            window_id,
+            None,
+            command,
+            dimensions,
+            terminal_config.clone(),
+            event_sender.clone(),
+            id_allocator.clone(),
+        )?;
+
+        Ok(Self {
             id,
+            name,
+            dimensions,
+            terminal_config,
             event_sender,
-            windows: Vec::new(),
-            next_window_id: 0,
-        };
+            windows: vec![window],
+            active_window: window_id,
+            last_window: None,
+            id_allocator,
+            attached_clients: Vec::new(),
+            options: Options::new(),
+        })
+    }
+
+    pub fn get_id(&self) -> SessionId {
+        self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// The options set for this session rather than inherited from the server.
+    pub fn get_options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn get_options_mut(&mut self) -> &mut Options {
+        &mut self.options
+    }
+
+    /// Applies to the windows and panes created from now on.
+    pub fn set_terminal_config(&mut self, terminal_config: TerminalConfig) {
+        for window in &mut self.windows {
+            window.set_terminal_config(terminal_config.clone());
+        }
+        self.terminal_config = terminal_config;
+    }
+
+    /// The size every window of the session is laid out at.
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+
+    pub fn resize(&mut self, dimensions: UVec2) {
+        if dimensions == self.dimensions {
+            return;
+        }
+
+        self.dimensions = dimensions;
+
+        for window in self.windows.iter_mut() {
+            window.resize(dimensions);
+        }
+    }
+
+    pub fn attach(&mut self, client: ClientId) {
+        if !self.attached_clients.contains(&client) {
+            self.attached_clients.push(client);
+        }
+    }
+
+    pub fn detach(&mut self, client: ClientId) {
+        self.attached_clients
+            .retain(|attached_client| *attached_client != client);
+    }
+
+    pub fn get_attached_clients(&self) -> &[ClientId] {
+        &self.attached_clients
+    }
+
+    pub fn is_attached(&self) -> bool {
+        !self.attached_clients.is_empty()
+    }
 
-        session.new_window()?;
+    pub fn get_window(&self, window: WindowId) -> Option<&Window> {
+        self.windows
+            .iter()
+            .find(|candidate| candidate.get_id() == window)
+    }
+
+    pub fn get_window_mut(&mut self, window: WindowId) -> Option<&mut Window> {
+        self.windows
+            .iter_mut()
+            .find(|candidate| candidate.get_id() == window)
+    }
+
+    pub fn get_windows(&self) -> &[Window] {
+        &self.windows
+    }
+
+    pub fn get_active_window(&self) -> WindowId {
+        self.active_window
+    }
+
+    /// Returns `false` if the window isn't part of the session.
+    pub fn select_window(&mut self, window: WindowId) -> bool {
+        if self.get_window(window).is_none() {
+            return false;
+        }
 
-        Ok(session)
+        self.set_active_window(window);
+        true
     }
 
-    pub async fn attach(&mut self) {
-        // loop {
-        //     let grid = self.windows[0].get_pane_grid(0).unwrap();
-        //     for line in grid.get_data().iter() {
-        //         for char in line.iter() {
-        //             print!("{}", char);
-        //         }
-        //         println!();
-        //     }
-        // }
-        // loop {
-        // let byte = self.terminal.read().await;
-        // io::stdout().write_u8(byte).unwrap();
-        // }
+    /// The previously active window, if it still exists.
+    pub fn get_last_window(&self) -> Option<WindowId> {
+        self.last_window
     }
 
-    /// TODO: Should probably use `WindowId` instead of `usize`.
-    pub fn get_window(&self, index: usize) -> &Window {
-        &self.windows[index]
+    pub fn get_active_pane(&self) -> PaneId {
+        self.windows
+            .iter()
+            .find(|window| window.get_id() == self.active_window)
+            .map(Window::get_active_pane)
+            .unwrap_or_else(|| PaneId::new(0, self.active_window))
+    }
+
+    pub fn get_pane_working_directory(&self, pane: PaneId) -> Option<PathBuf> {
+        self.get_window(pane.get_window())?
+            .get_pane_working_directory(pane)
     }
 
     pub fn update_pane(&mut self, pane: PaneId, grid_update: &GridUpdate) {
-        let window = &mut self.windows[pane.get_window().get()];
-        window.update_pane(pane, grid_update);
+        if let Some(window) = self.get_window_mut(pane.get_window()) {
+            window.update_pane(pane, grid_update);
+        }
+    }
+
+    pub fn mark_pane_exited(&mut self, pane: PaneId) {
+        if let Some(window) = self.get_window_mut(pane.get_window()) {
+            window.mark_pane_exited(pane);
+        }
     }
 
-    pub async fn process_input(&mut self, input: u8) {
-        self.windows[0].process_input(input).await;
+    pub fn respawn_pane(
+        &mut self,
+        pane: PaneId,
+        command: Option<Command>,
+        clear_grid: bool,
+    ) -> splix_error::Result<()> {
+        match self.get_window_mut(pane.get_window()) {
+            Some(window) => window.respawn_pane(pane, command, clear_grid),
+            None => Err(splix_error::Error::PaneNotFound(pane.to_string())),
+        }
     }
 
-    fn new_window(&mut self) -> splix_error::Result<()> {
-        let id = WindowId::new(self.next_window_id, self.id);
-        let window = Window::new(id, self.event_sender.clone())?;
+    pub fn process_input(&mut self, input: &[u8]) {
+        if let Some(window) = self.get_window_mut(self.active_window) {
+            window.process_input(input);
+        }
+    }
+
+    /// The new window becomes the active one unless `detached` is set.
+    pub fn new_window(
+        &mut self,
+        name: Option<String>,
+        command: Command,
+        detached: bool,
+    ) -> splix_error::Result<WindowId> {
+        let id = self.id_allocator.allocate_window_id(self.id);
+        let mut window = Window::new(
+            id,
+            name,
+            command,
+            self.dimensions,
+            self.terminal_config.clone(),
+            self.event_sender.clone(),
+            self.id_allocator.clone(),
+        )?;
+        window.set_index(self.get_free_index());
         self.windows.push(window);
-        self.next_window_id += 1;
+        self.windows.sort_by_key(Window::get_index);
+
+        if !detached {
+            self.set_active_window(id);
+        }
+
+        Ok(id)
+    }
+
+    /// Killing the last window leaves the session empty, at which point it should be removed.
+    pub fn kill_window(&mut self, window: WindowId) {
+        self.windows
+            .retain(|candidate| candidate.get_id() != window);
+
+        if self.last_window == Some(window) {
+            self.last_window = None;
+        }
+
+        // The previously active window takes over, like going back to it.
+        if self.active_window == window
+            && let Some(next_window) = self
+                .last_window
+                .take()
+                .or_else(|| self.windows.last().map(Window::get_id))
+        {
+            self.active_window = next_window;
+        }
+    }
+
+    /// Removes the window of the pane as well if it was its last pane.
+    pub fn kill_pane(&mut self, pane: PaneId) {
+        let Some(window) = self.get_window_mut(pane.get_window()) else {
+            return;
+        };
+
+        window.kill_pane(pane);
+        if window.is_empty() {
+            self.kill_window(pane.get_window());
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    fn set_active_window(&mut self, window: WindowId) {
+        if window != self.active_window {
+            self.last_window = Some(self.active_window);
+            self.active_window = window;
+        }
+    }
 
-        Ok(())
+    /// New windows take the lowest index no other window has.
+    fn get_free_index(&self) -> usize {
+        (0..)
+            .find(|index| {
+                !self
+                    .windows
+                    .iter()
+                    .any(|window| window.get_index() == *index)
+            })
+            .unwrap_or_default()
     }
 }
@@ -0,0 +1,3 @@
+mod socket_path_resolver;
+
+pub use socket_path_resolver::SocketPathResolver;
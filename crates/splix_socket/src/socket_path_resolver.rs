@@ -0,0 +1,75 @@
+use std::{
+    env,
+    fs::{self, DirBuilder},
+    os::unix::fs::{DirBuilderExt, MetadataExt},
+    path::PathBuf,
+};
+
+use nix::unistd;
+
+pub struct SocketPathResolver {
+    name: String,
+}
+
+const DEFAULT_SOCKET_NAME: &str = "default";
+const RUNTIME_DIRECTORY_ENVIRONMENT_VARIABLE: &str = "XDG_RUNTIME_DIR";
+const RUNTIME_DIRECTORY_NAME: &str = "splix";
+const FALLBACK_RUNTIME_DIRECTORY_PARENT: &str = "/tmp";
+const RUNTIME_DIRECTORY_MODE: u32 = 0o700;
+const GROUP_AND_OTHERS_PERMISSIONS_MASK: u32 = 0o077;
+
+impl SocketPathResolver {
+    pub fn new() -> Self {
+        Self::with_name(DEFAULT_SOCKET_NAME)
+    }
+
+    /// Allows running several independent servers side by side.
+    pub fn with_name(name: impl Into<String>) -> Self {
+        Self { name: name.into() }
+    }
+
+    /// Resolves the socket inside the per-user runtime directory, creating the directory if
+    /// needed.
+    ///
+    /// The directory is `$XDG_RUNTIME_DIR/splix`, or `/tmp/splix-<uid>` when the variable isn't
+    /// set. It must be owned by the current user and inaccessible to anyone else, as whoever can
+    /// connect to the socket can run commands as the user.
+    pub fn resolve(&self) -> splix_error::Result<PathBuf> {
+        let runtime_directory = Self::runtime_directory();
+
+        DirBuilder::new()
+            .recursive(true)
+            .mode(RUNTIME_DIRECTORY_MODE)
+            .create(&runtime_directory)
+            .map_err(splix_error::Error::CreateRuntimeDirectory)?;
+
+        let metadata =
+            fs::metadata(&runtime_directory).map_err(splix_error::Error::CreateRuntimeDirectory)?;
+        if !metadata.is_dir()
+            || metadata.uid() != unistd::getuid().as_raw()
+            || (metadata.mode() & GROUP_AND_OTHERS_PERMISSIONS_MASK) != 0
+        {
+            return Err(splix_error::Error::InsecureRuntimeDirectory(
+                runtime_directory,
+            ));
+        }
+
+        Ok(runtime_directory.join(&self.name))
+    }
+
+    fn runtime_directory() -> PathBuf {
+        match env::var_os(RUNTIME_DIRECTORY_ENVIRONMENT_VARIABLE) {
+            Some(runtime_directory) if !runtime_directory.is_empty() => {
+                PathBuf::from(runtime_directory).join(RUNTIME_DIRECTORY_NAME)
+            }
+            _ => PathBuf::from(FALLBACK_RUNTIME_DIRECTORY_PARENT)
+                .join(format!("{RUNTIME_DIRECTORY_NAME}-{}", unistd::getuid())),
+        }
+    }
+}
+
+impl Default for SocketPathResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,66 @@
+use std::{fs::File, path::PathBuf, sync::Arc};
+
+use nix::unistd::{self, Pid};
+
+/// A handle for inspecting the process running in a terminal.
+#[derive(Clone, Debug)]
+pub struct ChildProcess {
+    pid: Pid,
+    /// The master side of the terminal, which knows the process group in its foreground.
+    pty: Arc<File>,
+}
+
+impl ChildProcess {
+    pub fn new(pid: Pid, pty: Arc<File>) -> Self {
+        Self { pid, pty }
+    }
+
+    pub fn get_pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// The process group currently in the foreground of the terminal, e.g. an editor started from
+    /// the shell.
+    pub fn get_foreground_process_group(&self) -> Option<Pid> {
+        unistd::tcgetpgrp(self.pty.as_ref())
+            .ok()
+            .filter(|process_group| process_group.as_raw() > 0)
+    }
+
+    /// Falls back to the working directory of the child itself when the foreground process
+    /// can't be inspected.
+    pub fn get_foreground_working_directory(&self) -> Option<PathBuf> {
+        self.get_foreground_process_group()
+            .and_then(Self::working_directory)
+            .or_else(|| Self::working_directory(self.pid))
+    }
+
+    /// The name of the program in the foreground of the terminal, like `vim` or the shell.
+    pub fn get_foreground_command(&self) -> Option<String> {
+        self.get_foreground_process_group()
+            .and_then(Self::command)
+            .or_else(|| Self::command(self.pid))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn working_directory(pid: Pid) -> Option<PathBuf> {
+        std::fs::read_link(format!("/proc/{pid}/cwd")).ok()
+    }
+
+    /// Only Linux exposes other processes through `/proc`.
+    #[cfg(not(target_os = "linux"))]
+    fn working_directory(_pid: Pid) -> Option<PathBuf> {
+        None
+    }
+
+    #[cfg(target_os = "linux")]
+    fn command(pid: Pid) -> Option<String> {
+        let command = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+        Some(command.trim_end_matches('\n').to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn command(_pid: Pid) -> Option<String> {
+        None
+    }
+}
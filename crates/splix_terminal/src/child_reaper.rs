@@ -0,0 +1,105 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use nix::{
+    sys::{
+        signal::{self, Signal},
+        wait::{self, WaitPidFlag, WaitStatus},
+    },
+    unistd::Pid,
+};
+use tokio::{
+    runtime::Handle,
+    signal::unix::{self, SignalKind},
+    time,
+};
+
+/// Waits for the children of dropped terminals from a task of the runtime, whenever `SIGCHLD`
+/// says one of them may have exited, so they don't linger as zombies.
+///
+/// Only those children are waited for, leaving the exit of any other process to whoever started
+/// it. Children ignoring the hangup they got are sent `SIGTERM` and finally `SIGKILL`, each after
+/// a grace period.
+pub(crate) struct ChildReaper;
+
+const GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+static PENDING_CHILDREN: Mutex<Vec<Pid>> = Mutex::new(Vec::new());
+static STARTED: AtomicBool = AtomicBool::new(false);
+
+impl ChildReaper {
+    /// Without a runtime to reap on, the child is only reaped once one is around.
+    pub(crate) fn reap(child: Pid) {
+        Self::start();
+
+        let mut pending_children = PENDING_CHILDREN
+            .lock()
+            .expect("pending children lock poisoned");
+        pending_children.push(child);
+        Self::reap_exited(&mut pending_children);
+        if !pending_children.contains(&child) {
+            return;
+        }
+
+        if let Ok(handle) = Handle::try_current() {
+            handle.spawn(Self::escalate(child));
+        }
+    }
+
+    /// Signals the process group of `child` for as long as the child is waited for, which keeps
+    /// its PID from being reused in the meantime.
+    async fn escalate(child: Pid) {
+        for escalation in [Signal::SIGTERM, Signal::SIGKILL] {
+            time::sleep(GRACE_PERIOD).await;
+
+            let mut pending_children = PENDING_CHILDREN
+                .lock()
+                .expect("pending children lock poisoned");
+            Self::reap_exited(&mut pending_children);
+            if !pending_children.contains(&child) {
+                return;
+            }
+            signal::killpg(child, escalation).ok();
+        }
+    }
+
+    fn start() {
+        let Ok(handle) = Handle::try_current() else {
+            return;
+        };
+        if STARTED.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        // Registered before the first child is waited for, so that no `SIGCHLD` goes unnoticed.
+        let _runtime = handle.enter();
+        let Ok(mut child_signals) = unix::signal(SignalKind::child()) else {
+            STARTED.store(false, Ordering::SeqCst);
+            return;
+        };
+
+        handle.spawn(async move {
+            while child_signals.recv().await.is_some() {
+                let mut pending_children = PENDING_CHILDREN
+                    .lock()
+                    .expect("pending children lock poisoned");
+                Self::reap_exited(&mut pending_children);
+            }
+        });
+    }
+
+    /// Children that can't be waited for, e.g. because they were reaped elsewhere, are dropped.
+    fn reap_exited(pending_children: &mut Vec<Pid>) {
+        pending_children.retain(|child| {
+            matches!(
+                wait::waitpid(*child, Some(WaitPidFlag::WNOHANG)),
+                Ok(WaitStatus::StillAlive)
+            )
+        });
+    }
+}
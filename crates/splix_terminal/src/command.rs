@@ -0,0 +1,61 @@
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, Default)]
+pub struct Command {
+    argv: Vec<String>,
+    working_directory: Option<PathBuf>,
+    environment: Vec<(String, String)>,
+}
+
+impl Command {
+    /// Creates a command that runs the user's shell.
+    pub fn shell() -> Self {
+        Self::default()
+    }
+
+    pub fn new(argv: Vec<String>) -> Self {
+        Self {
+            argv,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_working_directory(mut self, working_directory: impl Into<PathBuf>) -> Self {
+        self.working_directory = Some(working_directory.into());
+        self
+    }
+
+    pub fn with_environment_variable(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        let name = name.into();
+        let value = value.into();
+
+        match self
+            .environment
+            .iter_mut()
+            .find(|(existing_name, _)| *existing_name == name)
+        {
+            Some((_, existing_value)) => *existing_value = value,
+            None => self.environment.push((name, value)),
+        }
+
+        self
+    }
+
+    /// An empty argv means the user's shell should be executed.
+    pub fn get_argv(&self) -> &[String] {
+        &self.argv
+    }
+
+    pub fn get_working_directory(&self) -> Option<&Path> {
+        self.working_directory.as_deref()
+    }
+
+    /// Variables set on top of the environment inherited from Splix.
+    pub fn get_environment(&self) -> &[(String, String)] {
+        &self.environment
+    }
+}
@@ -1,34 +1,55 @@
+mod child_process;
+mod child_reaper;
+mod command;
+mod prepared_command;
 mod shell_path_resolver;
+mod terminal_config;
 
 use std::{
-    ffi::CString,
+    error::Error,
+    fs::File,
+    io::{self, Read, Write},
     os::{
         fd::AsRawFd,
-        unix::{
-            ffi::OsStrExt,
-            io::{FromRawFd, IntoRawFd},
-        },
+        unix::io::{FromRawFd, IntoRawFd},
     },
+    sync::Arc,
 };
 
+use glam::UVec2;
 use nix::{
+    errno::Errno,
     fcntl::{self, FcntlArg, OFlag},
-    pty::{self, ForkptyResult},
+    pty::{self, ForkptyResult, Winsize},
+    sys::signal::{self, Signal},
     unistd::{self, Pid},
 };
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufStream};
+use tokio::io::{Interest, Ready, unix::AsyncFd};
 
-use shell_path_resolver::ShellPathResolver;
+pub use child_process::ChildProcess;
+use child_reaper::ChildReaper;
+pub use command::Command;
+use prepared_command::PreparedCommand;
+pub use terminal_config::TerminalConfig;
 
 pub struct Terminal {
-    _child: Pid,
-    pty: BufStream<tokio::fs::File>,
+    child: Pid,
+    /// Shared with the handles of the child, which look up its foreground process group.
+    pty: AsyncFd<Arc<File>>,
     incomplete_utf8: Vec<u8>,
 }
 
+const READ_BUFFER_SIZE: usize = 4096;
+const CHILD_FAILURE_EXIT_STATUS: i32 = 127;
+const CHILD_ERROR_MESSAGE_CAPACITY: usize = 512;
+
 impl Terminal {
-    pub fn new() -> splix_error::Result<Self> {
-        let (child, master_pty) = Self::spawn_child()?;
+    pub fn new(
+        command: &Command,
+        terminal_config: &TerminalConfig,
+        dimensions: UVec2,
+    ) -> splix_error::Result<Self> {
+        let (child, master_pty) = Self::spawn_child(command, terminal_config, dimensions)?;
 
         let pty_flags = OFlag::from_bits_truncate(
             fcntl::fcntl(master_pty.as_raw_fd(), FcntlArg::F_GETFL).unwrap(),
@@ -39,28 +60,69 @@ impl Terminal {
         )
         .unwrap();
 
-        let file = tokio::fs::File::from_std(master_pty);
+        let pty = AsyncFd::new(Arc::new(master_pty)).map_err(splix_error::Error::RegisterPty)?;
 
         Ok(Self {
-            _child: child,
-            pty: BufStream::new(file),
+            child,
+            pty,
             incomplete_utf8: Vec::new(),
         })
     }
 
-    pub async fn read(&mut self) -> splix_error::Result<Vec<char>> {
-        // Get the available bytes in the buffer
-        let buffer = self
-            .pty
-            .fill_buf()
-            .await
-            .map_err(splix_error::Error::ReadFromTerminal)?;
+    pub fn get_process(&self) -> ChildProcess {
+        ChildProcess::new(self.child, self.pty.get_ref().clone())
+    }
+
+    /// Waits for output from the child, meanwhile writing as much of `input` to it as the PTY
+    /// takes and removing that from `input`. Returns `None` once the child process has exited
+    /// and the PTY was closed.
+    ///
+    /// Writing while waiting to read keeps a child that echoes a large input from filling up the
+    /// PTY in both directions, and a write never blocks the read.
+    pub async fn read(&mut self, input: &mut Vec<u8>) -> splix_error::Result<Option<Vec<char>>> {
+        let mut read_buffer = [0u8; READ_BUFFER_SIZE];
+        let buffer_length = loop {
+            let interest = if input.is_empty() {
+                Interest::READABLE
+            } else {
+                Interest::READABLE | Interest::WRITABLE
+            };
+            let mut guard = self
+                .pty
+                .ready(interest)
+                .await
+                .map_err(splix_error::Error::ReadFromTerminal)?;
+
+            if guard.ready().is_writable() && !input.is_empty() {
+                match self.pty.get_ref().as_ref().write(input) {
+                    Ok(length) => {
+                        input.drain(..length);
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        guard.clear_ready_matching(Ready::WRITABLE)
+                    }
+                    Err(e) => return Err(splix_error::Error::WriteToTerminal(e)),
+                }
+            }
+
+            if guard.ready().is_readable() {
+                match self.pty.get_ref().as_ref().read(&mut read_buffer) {
+                    Ok(length) => break length,
+                    // Linux reports a closed PTY slave as `EIO` rather than EOF.
+                    Err(e) if e.raw_os_error() == Some(Errno::EIO as i32) => break 0,
+                    Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                        guard.clear_ready_matching(Ready::READABLE)
+                    }
+                    Err(e) => return Err(splix_error::Error::ReadFromTerminal(e)),
+                }
+            }
+        };
 
-        if buffer.is_empty() {
-            return Ok(Vec::new()); // EOF
+        if buffer_length == 0 {
+            return Ok(None); // EOF
         }
 
-        let buffer_length = buffer.len();
+        let buffer = &read_buffer[..buffer_length];
 
         // Combine any pending bytes with the newly read bytes while reusing the
         // existing allocation for `self.incomplete_utf8`
@@ -91,49 +153,87 @@ impl Terminal {
             }
         }
 
-        // Consume only the bytes we have read from the pty buffer
-        self.pty.consume(buffer_length);
-
-        Ok(chars)
+        Ok(Some(chars))
     }
 
-    pub async fn write(&mut self, input: u8) -> splix_error::Result<()> {
-        self.pty
-            .get_mut()
-            .write_u8(input)
-            .await
-            .map_err(splix_error::Error::WriteToTerminal)?;
+    /// Lets the child know the size of its terminal changed, which also sends it `SIGWINCH`.
+    pub fn resize(&self, dimensions: UVec2) -> splix_error::Result<()> {
+        let window_size = Self::window_size_from_dimensions(dimensions);
 
-        Ok(())
+        Errno::result(unsafe {
+            libc::ioctl(
+                self.pty.get_ref().as_raw_fd(),
+                libc::TIOCSWINSZ,
+                &window_size as *const Winsize,
+            )
+        })
+        .map(drop)
+        .map_err(splix_error::Error::ResizeTerminal)
     }
 
-    fn spawn_child() -> splix_error::Result<(Pid, std::fs::File)> {
-        match Self::fork_child_process_in_pty()? {
+    fn spawn_child(
+        command: &Command,
+        terminal_config: &TerminalConfig,
+        dimensions: UVec2,
+    ) -> splix_error::Result<(Pid, File)> {
+        let prepared_command = PreparedCommand::new(command, terminal_config)?;
+
+        match Self::fork_child_process_in_pty(dimensions)? {
             ForkptyResult::Parent { child, master } => {
-                let file = unsafe { std::fs::File::from_raw_fd(master.into_raw_fd()) };
+                let file = unsafe { File::from_raw_fd(master.into_raw_fd()) };
                 Ok((child, file))
             }
             ForkptyResult::Child => {
-                Self::execute_shell()?;
-                Err(splix_error::Error::TerminalSpawnChild)
+                let e = prepared_command.execute();
+                Self::report_child_error(&e);
+
+                // Exit without unwinding into, or running the destructors of, the parent's copy
+                // of Splix.
+                unsafe { libc::_exit(CHILD_FAILURE_EXIT_STATUS) }
             }
         }
     }
 
-    fn fork_child_process_in_pty() -> splix_error::Result<ForkptyResult> {
-        unsafe { pty::forkpty(None, None) }.map_err(splix_error::Error::ForkChildProcessInPty)
+    /// The child's stderr is the PTY, so the message ends up in the pane.
+    ///
+    /// The message is formatted on the stack, as the forked child may not allocate. Whatever
+    /// doesn't fit is cut off.
+    fn report_child_error(e: &splix_error::Error) {
+        let mut message = [0u8; CHILD_ERROR_MESSAGE_CAPACITY];
+        let mut remaining = &mut message[..];
+        match e.source() {
+            Some(source) => writeln!(remaining, "splix: {e}: {source}"),
+            None => writeln!(remaining, "splix: {e}"),
+        }
+        .ok();
+        let length = CHILD_ERROR_MESSAGE_CAPACITY - remaining.len();
+
+        unistd::write(io::stderr(), &message[..length]).ok();
     }
 
-    fn execute_shell() -> splix_error::Result<()> {
-        let shell_path_resolver = ShellPathResolver::new();
+    fn fork_child_process_in_pty(dimensions: UVec2) -> splix_error::Result<ForkptyResult> {
+        let window_size = Self::window_size_from_dimensions(dimensions);
 
-        let shell_path = shell_path_resolver.resolve();
-        let shell_path_c_string = CString::new(shell_path.as_os_str().as_bytes())
-            .expect("CString should be successfully created");
+        unsafe { pty::forkpty(Some(&window_size), None) }
+            .map_err(splix_error::Error::ForkChildProcessInPty)
+    }
+
+    fn window_size_from_dimensions(dimensions: UVec2) -> Winsize {
+        Winsize {
+            ws_row: u16::try_from(dimensions.y).unwrap_or(u16::MAX),
+            ws_col: u16::try_from(dimensions.x).unwrap_or(u16::MAX),
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
 
-        unistd::execv(&shell_path_c_string, &[&shell_path_c_string])
-            .expect("shell should be executed");
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // The child is a session leader (`forkpty` calls `setsid`), so its PID is also the ID of
+        // the process group that should be torn down together with it.
+        signal::killpg(self.child, Signal::SIGHUP).ok();
 
-        Ok(())
+        ChildReaper::reap(self.child);
     }
 }
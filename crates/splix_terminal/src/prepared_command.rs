@@ -0,0 +1,143 @@
+use std::{
+    env,
+    ffi::{CString, OsStr, OsString},
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    ptr,
+};
+
+use libc::c_char;
+use nix::{errno::Errno, unistd};
+
+use crate::{Command, TerminalConfig};
+
+/// A `Command` converted into the C strings `exec` expects.
+///
+/// Everything is allocated up front in the parent, so the forked child only has to change its
+/// working directory and replace itself with the new program, neither of which allocates.
+pub struct PreparedCommand {
+    program: CString,
+    /// Only kept for the pointers into them, as the strings don't move when the vectors do.
+    _argv: Vec<CString>,
+    _environment: Vec<CString>,
+    /// Null-terminated, like `exec` takes them.
+    argv_pointers: Vec<*const c_char>,
+    environment_pointers: Vec<*const c_char>,
+    working_directory: Option<CString>,
+}
+
+const TERM_ENVIRONMENT_VARIABLE: &str = "TERM";
+
+impl PreparedCommand {
+    pub fn new(command: &Command, terminal_config: &TerminalConfig) -> splix_error::Result<Self> {
+        let (program, argv) = if command.get_argv().is_empty() {
+            Self::prepare_shell(terminal_config)?
+        } else {
+            let argv = command
+                .get_argv()
+                .iter()
+                .map(|argument| Self::c_string(OsStr::new(argument)))
+                .collect::<splix_error::Result<Vec<_>>>()?;
+            (argv[0].clone(), argv)
+        };
+
+        let environment = Self::build_environment(command, terminal_config)?;
+
+        let working_directory = command
+            .get_working_directory()
+            .map(|working_directory| Self::c_string(working_directory.as_os_str()))
+            .transpose()?;
+
+        Ok(Self {
+            argv_pointers: Self::pointers(&argv),
+            environment_pointers: Self::pointers(&environment),
+            program,
+            _argv: argv,
+            _environment: environment,
+            working_directory,
+        })
+    }
+
+    /// Only returns if the command could not be executed.
+    pub fn execute(&self) -> splix_error::Error {
+        if let Some(working_directory) = &self.working_directory
+            && let Err(e) = unistd::chdir(working_directory.as_c_str())
+        {
+            return splix_error::Error::ChangeWorkingDirectory(e);
+        }
+
+        unsafe {
+            libc::execvpe(
+                self.program.as_ptr(),
+                self.argv_pointers.as_ptr(),
+                self.environment_pointers.as_ptr(),
+            )
+        };
+        splix_error::Error::ExecuteCommand(Errno::last())
+    }
+
+    fn pointers(strings: &[CString]) -> Vec<*const c_char> {
+        strings
+            .iter()
+            .map(|string| string.as_ptr())
+            .chain([ptr::null()])
+            .collect()
+    }
+
+    fn prepare_shell(
+        terminal_config: &TerminalConfig,
+    ) -> splix_error::Result<(CString, Vec<CString>)> {
+        let shell_path = terminal_config.resolve_shell()?;
+
+        let mut argv0 = OsString::new();
+        if terminal_config.is_login_shell() {
+            argv0.push("-");
+        }
+        argv0.push(shell_path.file_name().unwrap_or(shell_path.as_os_str()));
+
+        Ok((
+            Self::c_string(shell_path.as_os_str())?,
+            vec![Self::c_string(&argv0)?],
+        ))
+    }
+
+    fn build_environment(
+        command: &Command,
+        terminal_config: &TerminalConfig,
+    ) -> splix_error::Result<Vec<CString>> {
+        let mut variables: Vec<(OsString, OsString)> = env::vars_os().collect();
+
+        // The command's own variables are set last, so they may still override `TERM`.
+        Self::set_variable(
+            &mut variables,
+            OsString::from(TERM_ENVIRONMENT_VARIABLE),
+            OsString::from(terminal_config.get_term()),
+        );
+        for (name, value) in command.get_environment() {
+            Self::set_variable(&mut variables, OsString::from(name), OsString::from(value));
+        }
+
+        variables
+            .into_iter()
+            .map(|(name, value)| {
+                let mut variable = name.into_vec();
+                variable.push(b'=');
+                variable.extend(value.into_vec());
+                CString::new(variable).map_err(splix_error::Error::InvalidCommandString)
+            })
+            .collect()
+    }
+
+    fn set_variable(variables: &mut Vec<(OsString, OsString)>, name: OsString, value: OsString) {
+        match variables
+            .iter_mut()
+            .find(|(existing_name, _)| *existing_name == name)
+        {
+            Some((_, existing_value)) => *existing_value = value,
+            None => variables.push((name, value)),
+        }
+    }
+
+    fn c_string(string: &OsStr) -> splix_error::Result<CString> {
+        CString::new(string.as_bytes()).map_err(splix_error::Error::InvalidCommandString)
+    }
+}
@@ -1,16 +1,83 @@
-use std::{env, path::PathBuf};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
 
-pub struct ShellPathResolver;
+use nix::unistd::{self, AccessFlags, User};
+
+pub struct ShellPathResolver {
+    default_shell: Option<PathBuf>,
+}
 
 const SHELL_ENVIRONMENT_VARIABLE: &str = "SHELL";
+const FALLBACK_SHELL: &str = "/bin/sh";
 
 impl ShellPathResolver {
-    pub fn new() -> Self {
-        Self
+    pub fn new(default_shell: Option<PathBuf>) -> Self {
+        Self { default_shell }
+    }
+
+    /// Picks the first executable shell out of the configured default shell, `$SHELL`, the
+    /// user's passwd entry and `/bin/sh`, in that order.
+    pub fn resolve(&self) -> splix_error::Result<PathBuf> {
+        let candidates = [
+            self.default_shell.clone(),
+            env::var_os(SHELL_ENVIRONMENT_VARIABLE).map(PathBuf::from),
+            Self::passwd_shell(),
+            Some(PathBuf::from(FALLBACK_SHELL)),
+        ];
+
+        candidates
+            .into_iter()
+            .flatten()
+            .find(|candidate| Self::is_executable(candidate))
+            .ok_or(splix_error::Error::ResolveShell)
+    }
+
+    fn passwd_shell() -> Option<PathBuf> {
+        User::from_uid(unistd::getuid())
+            .ok()
+            .flatten()
+            .map(|user| user.shell)
+    }
+
+    fn is_executable(path: &Path) -> bool {
+        path.is_absolute()
+            && fs::metadata(path).is_ok_and(|metadata| metadata.is_file())
+            && unistd::access(path, AccessFlags::X_OK).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::ShellPathResolver;
+
+    #[test]
+    fn default_shell_takes_precedence() {
+        let resolver = ShellPathResolver::new(Some(PathBuf::from("/bin/sh")));
+        assert_eq!(resolver.resolve().unwrap(), PathBuf::from("/bin/sh"));
+    }
+
+    #[test]
+    fn missing_default_shell_is_skipped() {
+        let resolver = ShellPathResolver::new(Some(PathBuf::from("/nonexistent/shell")));
+        assert_ne!(
+            resolver.resolve().unwrap(),
+            PathBuf::from("/nonexistent/shell")
+        );
+    }
+
+    #[test]
+    fn directory_is_not_a_shell() {
+        let resolver = ShellPathResolver::new(Some(PathBuf::from("/bin")));
+        assert_ne!(resolver.resolve().unwrap(), PathBuf::from("/bin"));
     }
 
-    pub fn resolve(&self) -> PathBuf {
-        // TODO: Handle cases where the `SHELL` environment variable doesn't exist.
-        PathBuf::from(env::var(SHELL_ENVIRONMENT_VARIABLE).unwrap())
+    #[test]
+    fn relative_default_shell_is_skipped() {
+        let resolver = ShellPathResolver::new(Some(PathBuf::from("sh")));
+        assert!(resolver.resolve().unwrap().is_absolute());
     }
 }
@@ -0,0 +1,75 @@
+use std::path::{Path, PathBuf};
+
+use splix_terminfo::Terminfo;
+
+use crate::shell_path_resolver::ShellPathResolver;
+
+#[derive(Clone, Debug)]
+pub struct TerminalConfig {
+    default_shell: Option<PathBuf>,
+    login_shell: bool,
+    term: String,
+    server_socket: Option<PathBuf>,
+}
+
+impl TerminalConfig {
+    pub fn new() -> Self {
+        Self {
+            default_shell: None,
+            login_shell: false,
+            term: String::from(Terminfo::new().resolve_term()),
+            server_socket: None,
+        }
+    }
+
+    /// Overrides the shell that would otherwise be resolved from the user's environment.
+    pub fn with_default_shell(mut self, default_shell: impl Into<PathBuf>) -> Self {
+        self.default_shell = Some(default_shell.into());
+        self
+    }
+
+    /// Starts the shell as a login shell, by prefixing its `argv[0]` with a `-`.
+    pub fn with_login_shell(mut self, login_shell: bool) -> Self {
+        self.login_shell = login_shell;
+        self
+    }
+
+    /// The `TERM` children see, instead of the one of the terminal Splix runs in.
+    pub fn with_term(mut self, term: impl Into<String>) -> Self {
+        self.term = term.into();
+        self
+    }
+
+    /// The socket of the server owning the terminals, advertised to children through `SPLIX`.
+    pub fn with_server_socket(mut self, server_socket: impl Into<PathBuf>) -> Self {
+        self.server_socket = Some(server_socket.into());
+        self
+    }
+
+    pub fn get_default_shell(&self) -> Option<&Path> {
+        self.default_shell.as_deref()
+    }
+
+    pub fn is_login_shell(&self) -> bool {
+        self.login_shell
+    }
+
+    pub fn get_term(&self) -> &str {
+        &self.term
+    }
+
+    pub fn get_server_socket(&self) -> Option<&Path> {
+        self.server_socket.as_deref()
+    }
+
+    /// The shell executed for commands without arguments.
+    pub fn resolve_shell(&self) -> splix_error::Result<PathBuf> {
+        ShellPathResolver::new(self.default_shell.clone()).resolve()
+    }
+}
+
+impl Default for TerminalConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
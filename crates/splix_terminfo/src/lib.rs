@@ -0,0 +1,3 @@
+mod terminfo;
+
+pub use terminfo::Terminfo;
@@ -0,0 +1,160 @@
+use std::{
+    env,
+    fs::{self, DirBuilder, OpenOptions},
+    io::{self, Write},
+    os::unix::fs::{DirBuilderExt, OpenOptionsExt},
+    path::{Path, PathBuf},
+    process::{self, Command},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+pub struct Terminfo;
+
+const NAME: &str = "splix";
+const FALLBACK_NAME: &str = "screen-256color";
+const SOURCE: &str = include_str!("../terminfo/splix.terminfo");
+const SOURCE_FILE_NAME: &str = "splix.terminfo";
+/// How many names are tried for the directory the source is written into before giving up.
+const SOURCE_DIRECTORY_ATTEMPTS: u32 = 16;
+
+const TERMINFO_ENVIRONMENT_VARIABLE: &str = "TERMINFO";
+const TERMINFO_DIRS_ENVIRONMENT_VARIABLE: &str = "TERMINFO_DIRS";
+const HOME_ENVIRONMENT_VARIABLE: &str = "HOME";
+const USER_TERMINFO_DIRECTORY: &str = ".terminfo";
+const SYSTEM_TERMINFO_DIRECTORIES: [&str; 5] = [
+    "/etc/terminfo",
+    "/lib/terminfo",
+    "/usr/share/terminfo",
+    "/usr/lib/terminfo",
+    "/usr/share/lib/terminfo",
+];
+
+impl Terminfo {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The `TERM` panes should advertise: the Splix entry when it is installed, or an entry that
+    /// exists practically everywhere otherwise.
+    pub fn resolve_term(&self) -> &'static str {
+        if self.is_installed() {
+            NAME
+        } else {
+            FALLBACK_NAME
+        }
+    }
+
+    pub fn is_installed(&self) -> bool {
+        Self::search_directories()
+            .iter()
+            .any(|directory| Self::entry_exists(directory))
+    }
+
+    /// Compiles the Splix entry into `~/.terminfo` using `tic`, returning the directory it was
+    /// installed into.
+    pub fn install(&self) -> splix_error::Result<PathBuf> {
+        let home = env::var_os(HOME_ENVIRONMENT_VARIABLE)
+            .map(PathBuf::from)
+            .ok_or(splix_error::Error::ResolveHomeDirectory)?;
+        let output_directory = home.join(USER_TERMINFO_DIRECTORY);
+        fs::create_dir_all(&output_directory)
+            .map_err(splix_error::Error::CreateTerminfoDirectory)?;
+
+        let source_directory =
+            Self::write_source().map_err(splix_error::Error::WriteTerminfoSource)?;
+
+        let status = Command::new("tic")
+            .arg("-x")
+            .arg("-o")
+            .arg(&output_directory)
+            .arg(source_directory.join(SOURCE_FILE_NAME))
+            .status();
+        fs::remove_dir_all(&source_directory).ok();
+
+        let status = status.map_err(splix_error::Error::ExecuteTic)?;
+        if !status.success() {
+            return Err(splix_error::Error::CompileTerminfo(status));
+        }
+
+        Ok(output_directory)
+    }
+
+    /// Writes the source for `tic` into a new directory only the user can access, so that nobody
+    /// else can swap it out before it's compiled, returning that directory.
+    fn write_source() -> io::Result<PathBuf> {
+        let mut attempt = 0;
+        let directory = loop {
+            let nanos = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.subsec_nanos())
+                .unwrap_or_default();
+            let directory =
+                env::temp_dir().join(format!("{NAME}-terminfo-{}-{nanos}", process::id()));
+
+            // Fails if anything already exists there rather than reusing it.
+            match DirBuilder::new().mode(0o700).create(&directory) {
+                Ok(()) => break directory,
+                Err(e)
+                    if e.kind() == io::ErrorKind::AlreadyExists
+                        && attempt + 1 < SOURCE_DIRECTORY_ATTEMPTS =>
+                {
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(directory.join(SOURCE_FILE_NAME))
+            .and_then(|mut file| file.write_all(SOURCE.as_bytes()));
+        if let Err(e) = written {
+            fs::remove_dir_all(&directory).ok();
+            return Err(e);
+        }
+
+        Ok(directory)
+    }
+
+    /// The directories ncurses looks entries up in, see terminfo(5).
+    fn search_directories() -> Vec<PathBuf> {
+        let mut directories = Vec::new();
+
+        if let Some(terminfo) = env::var_os(TERMINFO_ENVIRONMENT_VARIABLE) {
+            directories.push(PathBuf::from(terminfo));
+        }
+
+        if let Some(home) = env::var_os(HOME_ENVIRONMENT_VARIABLE) {
+            directories.push(PathBuf::from(home).join(USER_TERMINFO_DIRECTORY));
+        }
+
+        if let Some(terminfo_dirs) = env::var_os(TERMINFO_DIRS_ENVIRONMENT_VARIABLE) {
+            directories.extend(
+                env::split_paths(&terminfo_dirs)
+                    .filter(|directory| !directory.as_os_str().is_empty()),
+            );
+        }
+
+        directories.extend(SYSTEM_TERMINFO_DIRECTORIES.iter().map(PathBuf::from));
+
+        directories
+    }
+
+    /// Entries are stored under their first letter, or its hex code on some platforms.
+    fn entry_exists(directory: &Path) -> bool {
+        let first_letter = &NAME[..1];
+        let first_letter_hex = format!("{:x}", NAME.as_bytes()[0]);
+
+        [first_letter, first_letter_hex.as_str()]
+            .iter()
+            .any(|subdirectory| directory.join(subdirectory).join(NAME).is_file())
+    }
+}
+
+impl Default for Terminfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
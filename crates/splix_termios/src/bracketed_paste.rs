@@ -0,0 +1,37 @@
+use std::io::{self, Stdout, Write};
+
+use splix_ansi::AnsiEncoder;
+
+/// Has the terminal send pasted text between markers, so that it can't be taken for typed keys
+/// and reaches panes the way the programs in them asked for.
+pub struct BracketedPaste {
+    ansi_encoder: AnsiEncoder,
+    tty: Stdout,
+}
+
+const ENABLE_ANSI_ESCAPE_CODE: &str = "?2004h";
+const DISABLE_ANSI_ESCAPE_CODE: &str = "?2004l";
+
+impl BracketedPaste {
+    pub fn new() -> splix_error::Result<Self> {
+        let ansi_encoder = AnsiEncoder::new();
+
+        let mut tty = io::stdout();
+        tty.write_all(ansi_encoder.encode(ENABLE_ANSI_ESCAPE_CODE).as_bytes())
+            .map_err(splix_error::Error::EnableBracketedPaste)?;
+
+        Ok(Self { ansi_encoder, tty })
+    }
+}
+
+impl Drop for BracketedPaste {
+    fn drop(&mut self) {
+        self.tty
+            .write_all(
+                self.ansi_encoder
+                    .encode(DISABLE_ANSI_ESCAPE_CODE)
+                    .as_bytes(),
+            )
+            .ok();
+    }
+}
@@ -0,0 +1,39 @@
+use std::io::{self, Stdout, Write};
+
+use splix_ansi::AnsiEncoder;
+
+/// Has the terminal send keys with modifiers the traditional sequences lose, like `C-;` or
+/// `C-S-a`, using the kitty keyboard protocol where supported and xterm's modifyOtherKeys
+/// elsewhere. Terminals supporting neither ignore both.
+pub struct KeyboardReporting {
+    ansi_encoder: AnsiEncoder,
+    tty: Stdout,
+}
+
+/// Pushes the flag disambiguating escape codes, and sets modifyOtherKeys to level 2.
+const ENABLE_ANSI_ESCAPE_CODES: [&str; 2] = [">1u", ">4;2m"];
+const DISABLE_ANSI_ESCAPE_CODES: [&str; 2] = ["<u", ">4;0m"];
+
+impl KeyboardReporting {
+    pub fn new() -> splix_error::Result<Self> {
+        let ansi_encoder = AnsiEncoder::new();
+
+        let mut tty = io::stdout();
+        for escape_code in ENABLE_ANSI_ESCAPE_CODES {
+            tty.write_all(ansi_encoder.encode(escape_code).as_bytes())
+                .map_err(splix_error::Error::EnableKeyboardReporting)?;
+        }
+
+        Ok(Self { ansi_encoder, tty })
+    }
+}
+
+impl Drop for KeyboardReporting {
+    fn drop(&mut self) {
+        for escape_code in DISABLE_ANSI_ESCAPE_CODES {
+            self.tty
+                .write_all(self.ansi_encoder.encode(escape_code).as_bytes())
+                .ok();
+        }
+    }
+}
@@ -1,22 +1,45 @@
 mod alternate_screen;
+mod bracketed_paste;
+mod keyboard_reporting;
+mod mouse_reporting;
 mod raw_mode;
 
 use alternate_screen::AlternateScreen;
+use bracketed_paste::BracketedPaste;
+use keyboard_reporting::KeyboardReporting;
+use mouse_reporting::MouseReporting;
 use raw_mode::RawMode;
+use splix_key::MouseTracking;
 
 pub struct Termios {
     _raw_mode: RawMode,
+    /// Dropped before leaving the alternate screen, as kitty keeps the keyboard flags of each
+    /// screen apart.
+    _keyboard_reporting: KeyboardReporting,
     _alternate_screen: AlternateScreen,
+    mouse_reporting: MouseReporting,
+    _bracketed_paste: BracketedPaste,
 }
 
 impl Termios {
     pub fn new() -> splix_error::Result<Self> {
         let raw_mode = RawMode::new()?;
         let alternate_screen = AlternateScreen::new()?;
+        let mouse_reporting = MouseReporting::new();
+        let bracketed_paste = BracketedPaste::new()?;
+        let keyboard_reporting = KeyboardReporting::new()?;
 
         Ok(Self {
             _raw_mode: raw_mode,
+            _keyboard_reporting: keyboard_reporting,
             _alternate_screen: alternate_screen,
+            mouse_reporting,
+            _bracketed_paste: bracketed_paste,
         })
     }
+
+    /// Which mouse events the terminal reports, none until set.
+    pub fn set_mouse_tracking(&mut self, tracking: MouseTracking) -> splix_error::Result<()> {
+        self.mouse_reporting.set_tracking(tracking)
+    }
 }
@@ -0,0 +1,67 @@
+use std::io::{self, Stdout, Write};
+
+use splix_ansi::AnsiEncoder;
+use splix_key::MouseTracking;
+
+/// Has the terminal report the mouse as SGR sequences, which aren't limited in how far right they
+/// can report positions, with the tracking the server asks for. Nothing is reported until then.
+pub struct MouseReporting {
+    ansi_encoder: AnsiEncoder,
+    tracking: MouseTracking,
+    tty: Stdout,
+}
+
+const SGR_ENCODING_MODE: u16 = 1006;
+
+impl MouseReporting {
+    pub fn new() -> Self {
+        Self {
+            ansi_encoder: AnsiEncoder::new(),
+            tracking: MouseTracking::None,
+            tty: io::stdout(),
+        }
+    }
+
+    pub fn set_tracking(&mut self, tracking: MouseTracking) -> splix_error::Result<()> {
+        if tracking == self.tracking {
+            return Ok(());
+        }
+
+        self.disable()?;
+        if let Some(mode) = tracking.get_mode() {
+            self.tty
+                .write_all(
+                    self.ansi_encoder
+                        .encode(&format!("?{mode};{SGR_ENCODING_MODE}h"))
+                        .as_bytes(),
+                )
+                .map_err(splix_error::Error::EnableMouseReporting)?;
+        }
+        self.tracking = tracking;
+
+        self.tty
+            .flush()
+            .map_err(splix_error::Error::EnableMouseReporting)
+    }
+
+    fn disable(&mut self) -> splix_error::Result<()> {
+        let Some(mode) = self.tracking.get_mode() else {
+            return Ok(());
+        };
+
+        self.tracking = MouseTracking::None;
+        self.tty
+            .write_all(
+                self.ansi_encoder
+                    .encode(&format!("?{mode};{SGR_ENCODING_MODE}l"))
+                    .as_bytes(),
+            )
+            .map_err(splix_error::Error::EnableMouseReporting)
+    }
+}
+
+impl Drop for MouseReporting {
+    fn drop(&mut self) {
+        self.disable().ok();
+    }
+}
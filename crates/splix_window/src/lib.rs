@@ -1,51 +1,385 @@
+use std::path::PathBuf;
+
+use glam::UVec2;
 use tokio::sync::mpsc::Sender;
 
 use splix_error::Result;
 use splix_event::{Event, GridUpdate};
-use splix_id::{PaneId, WindowId};
+use splix_id::{IdAllocator, PaneId, WindowId};
+use splix_layout::{Geometry, Layout, SplitDirection};
+use splix_options::Options;
 use splix_pane::Pane;
+use splix_terminal::{Command, TerminalConfig};
 
 pub struct Window {
     id: WindowId,
+    index: usize,
+    name: String,
+    dimensions: UVec2,
+    terminal_config: TerminalConfig,
     event_sender: Sender<Event>,
     panes: Vec<Pane>,
-    next_pane_id: usize,
+    layout: Layout,
+    active_pane: PaneId,
+    last_pane: Option<PaneId>,
+    id_allocator: IdAllocator,
+    options: Options,
 }
 
+/// Names windows running a shell that couldn't be resolved.
+const FALLBACK_WINDOW_NAME: &str = "shell";
+
 impl Window {
-    pub fn new(id: WindowId, event_sender: Sender<Event>) -> Result<Self> {
-        let mut window = Self {
+    /// Windows without a name are named after the program they run.
+    pub fn new(
+        id: WindowId,
+        name: Option<String>,
+        command: Command,
+        dimensions: UVec2,
+        terminal_config: TerminalConfig,
+        event_sender: Sender<Event>,
+        id_allocator: IdAllocator,
+    ) -> Result<Self> {
+        let name = name.unwrap_or_else(|| Self::name_from_command(&command, &terminal_config));
+
+        let pane_id = id_allocator.allocate_pane_id(id);
+        let pane = Pane::new(
+            pane_id,
+            command,
+            dimensions,
+            terminal_config.clone(),
+            event_sender.clone(),
+        )?;
+
+        Ok(Self {
             id,
+            index: 0,
+            name,
+            dimensions,
+            terminal_config,
             event_sender,
-            panes: Vec::new(),
-            next_pane_id: 0,
-        };
+            panes: vec![pane],
+            layout: Layout::new(pane_id),
+            active_pane: pane_id,
+            last_pane: None,
+            id_allocator,
+            options: Options::new(),
+        })
+    }
+
+    pub fn get_id(&self) -> WindowId {
+        self.id
+    }
+
+    /// Where the window is listed within its session, which picks it when creating the window.
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn set_index(&mut self, index: usize) {
+        self.index = index;
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// The options set for this window rather than inherited from its session.
+    pub fn get_options(&self) -> &Options {
+        &self.options
+    }
+
+    pub fn get_options_mut(&mut self) -> &mut Options {
+        &mut self.options
+    }
+
+    /// Applies to the panes created from now on.
+    pub fn set_terminal_config(&mut self, terminal_config: TerminalConfig) {
+        self.terminal_config = terminal_config;
+    }
+
+    pub fn get_dimensions(&self) -> UVec2 {
+        self.dimensions
+    }
+
+    pub fn get_pane(&self, pane: PaneId) -> Option<&Pane> {
+        self.panes
+            .iter()
+            .find(|candidate| candidate.get_id() == pane)
+    }
+
+    pub fn get_panes(&self) -> &[Pane] {
+        &self.panes
+    }
+
+    /// The panes in the order they're laid out, top to bottom and left to right, which is what
+    /// their indices refer to.
+    pub fn get_pane_ids(&self) -> Vec<PaneId> {
+        self.get_pane_geometries()
+            .into_iter()
+            .map(|(pane, _)| pane)
+            .collect()
+    }
+
+    /// Where the pane is in layout order.
+    pub fn get_pane_index(&self, pane: PaneId) -> Option<usize> {
+        self.get_pane_ids()
+            .iter()
+            .position(|candidate| *candidate == pane)
+    }
+
+    /// Where each pane is placed within the window.
+    pub fn get_pane_geometries(&self) -> Vec<(PaneId, Geometry)> {
+        self.layout.get_pane_geometries(self.dimensions)
+    }
+
+    /// The pane covering `position` within the window, along with where it's placed.
+    pub fn find_pane_at(&self, position: UVec2) -> Option<(PaneId, Geometry)> {
+        self.get_pane_geometries()
+            .into_iter()
+            .find(|(_, geometry)| {
+                position.cmpge(geometry.get_position()).all()
+                    && position
+                        .cmplt(geometry.get_position() + geometry.get_dimensions())
+                        .all()
+            })
+    }
+
+    /// The separator at `position` within the window, as the pane right before it and the
+    /// direction it separates panes in, which is how `move_border` takes it.
+    pub fn find_border_at(&self, position: UVec2) -> Option<(PaneId, SplitDirection)> {
+        if position.cmpge(self.dimensions).any() || self.find_pane_at(position).is_some() {
+            return None;
+        }
+
+        self.get_pane_geometries()
+            .into_iter()
+            .find_map(|(pane, geometry)| {
+                let start = geometry.get_position();
+                let end = start + geometry.get_dimensions();
+                if position.x == end.x && (start.y..end.y).contains(&position.y) {
+                    Some((pane, SplitDirection::Horizontal))
+                } else if position.y == end.y && (start.x..end.x).contains(&position.x) {
+                    Some((pane, SplitDirection::Vertical))
+                } else {
+                    None
+                }
+            })
+    }
+
+    /// Moves the separator following `pane` along `direction` to `position` within the window.
+    ///
+    /// Returns `false` if no separator follows `pane` in that direction.
+    pub fn move_border(&mut self, pane: PaneId, direction: SplitDirection, position: u32) -> bool {
+        let moved = self
+            .layout
+            .move_border(self.dimensions, pane, direction, position);
+        if moved {
+            self.layout_panes();
+        }
+
+        moved
+    }
+
+    /// Describes where the panes are, the way tmux does.
+    pub fn get_layout_description(&self) -> String {
+        self.layout.describe(self.dimensions)
+    }
+
+    pub fn get_active_pane(&self) -> PaneId {
+        self.active_pane
+    }
 
-        window.new_pane()?;
+    /// Returns `false` if the pane isn't part of the window.
+    pub fn select_pane(&mut self, pane: PaneId) -> bool {
+        if self.get_pane(pane).is_none() {
+            return false;
+        }
 
-        Ok(window)
+        self.set_active_pane(pane);
+        true
     }
 
-    /// TODO: Should probably use `PaneId` instead of `usize`.
-    pub fn get_pane(&self, index: usize) -> &Pane {
-        &self.panes[index]
+    /// The previously active pane, if it still exists.
+    pub fn get_last_pane(&self) -> Option<PaneId> {
+        self.last_pane
     }
 
+    pub fn get_pane_working_directory(&self, pane: PaneId) -> Option<PathBuf> {
+        self.get_pane(pane)?.get_working_directory()
+    }
+
+    /// Updates of panes that were killed in the meantime are dropped.
     pub fn update_pane(&mut self, pane: PaneId, grid_update: &GridUpdate) {
-        let pane = &mut self.panes[pane.get()];
-        pane.update(grid_update);
+        if let Some(pane) = self.get_pane_mut(pane) {
+            pane.update(grid_update);
+        }
+    }
+
+    pub fn mark_pane_exited(&mut self, pane: PaneId) {
+        if let Some(pane) = self.get_pane_mut(pane) {
+            pane.mark_exited();
+        }
+    }
+
+    pub fn respawn_pane(
+        &mut self,
+        pane: PaneId,
+        command: Option<Command>,
+        clear_grid: bool,
+    ) -> Result<()> {
+        match self.get_pane_mut(pane) {
+            Some(pane) => pane.respawn(command, clear_grid),
+            None => Err(splix_error::Error::PaneNotFound(pane.to_string())),
+        }
+    }
+
+    pub fn resize(&mut self, dimensions: UVec2) {
+        self.dimensions = dimensions;
+        self.layout_panes();
+    }
+
+    pub fn process_input(&mut self, input: &[u8]) {
+        if let Some(pane) = self.get_pane_mut(self.active_pane) {
+            pane.process_input(input);
+        }
+    }
+
+    pub fn process_pane_input(&mut self, pane: PaneId, input: &[u8]) {
+        if let Some(pane) = self.get_pane_mut(pane) {
+            pane.process_input(input);
+        }
+    }
+
+    /// Splits `pane` in two, running `command` in the new half, which becomes the active pane.
+    pub fn split_pane(
+        &mut self,
+        pane: PaneId,
+        direction: SplitDirection,
+        command: Command,
+    ) -> Result<PaneId> {
+        let id = self.id_allocator.allocate_pane_id(self.id);
+        if !self.layout.split(pane, id, direction) {
+            return Err(splix_error::Error::PaneNotFound(pane.to_string()));
+        }
+
+        let dimensions = self
+            .get_pane_geometries()
+            .into_iter()
+            .find(|(geometry_pane, _)| *geometry_pane == id)
+            .map(|(_, geometry)| geometry.get_dimensions())
+            .unwrap_or(self.dimensions);
+        let new_pane = match Pane::new(
+            id,
+            command,
+            dimensions,
+            self.terminal_config.clone(),
+            self.event_sender.clone(),
+        ) {
+            Ok(new_pane) => new_pane,
+            Err(e) => {
+                self.layout.remove(id);
+                return Err(e);
+            }
+        };
+
+        self.panes.push(new_pane);
+        self.set_active_pane(id);
+        self.layout_panes();
+
+        Ok(id)
     }
 
-    pub async fn process_input(&mut self, input: u8) {
-        self.panes[0].process_input(input).await;
+    /// Killing the last pane leaves the window empty, at which point it should be removed.
+    pub fn kill_pane(&mut self, pane: PaneId) {
+        self.layout.remove(pane);
+        self.panes.retain(|candidate| candidate.get_id() != pane);
+
+        if self.last_pane == Some(pane) {
+            self.last_pane = None;
+        }
+
+        // The previously active pane takes over, like going back to it.
+        if self.active_pane == pane
+            && let Some(next_pane) = self
+                .last_pane
+                .take()
+                .or_else(|| self.panes.last().map(Pane::get_id))
+        {
+            self.active_pane = next_pane;
+        }
+
+        self.layout_panes();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.panes.is_empty()
+    }
+
+    pub fn get_pane_mut(&mut self, pane: PaneId) -> Option<&mut Pane> {
+        self.panes
+            .iter_mut()
+            .find(|candidate| candidate.get_id() == pane)
+    }
+
+    fn set_active_pane(&mut self, pane: PaneId) {
+        if pane != self.active_pane {
+            self.last_pane = Some(self.active_pane);
+            self.active_pane = pane;
+        }
+    }
+
+    fn layout_panes(&mut self) {
+        for (pane, geometry) in self.get_pane_geometries() {
+            if let Some(pane) = self.get_pane_mut(pane) {
+                pane.resize(geometry.get_dimensions());
+            }
+        }
+    }
+
+    fn name_from_command(command: &Command, terminal_config: &TerminalConfig) -> String {
+        let program = match command.get_argv().first() {
+            Some(program) => PathBuf::from(program),
+            None => match terminal_config.resolve_shell() {
+                Ok(shell) => shell,
+                Err(_) => return String::from(FALLBACK_WINDOW_NAME),
+            },
+        };
+
+        program
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| program.display().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use splix_terminal::{Command, TerminalConfig};
+
+    use super::Window;
+
+    #[test]
+    fn names_window_after_program() {
+        let command = Command::new(vec![String::from("/usr/bin/htop"), String::from("-d")]);
+
+        assert_eq!(
+            Window::name_from_command(&command, &TerminalConfig::new()),
+            "htop"
+        );
     }
 
-    fn new_pane(&mut self) -> splix_error::Result<()> {
-        let id = PaneId::new(self.next_pane_id, self.id);
-        let pane = Pane::new(id, self.event_sender.clone())?;
-        self.panes.push(pane);
-        self.next_pane_id += 1;
+    #[test]
+    fn names_shell_window_after_shell() {
+        let terminal_config = TerminalConfig::new().with_default_shell("/bin/sh");
 
-        Ok(())
+        assert_eq!(
+            Window::name_from_command(&Command::shell(), &terminal_config),
+            "sh"
+        );
     }
 }